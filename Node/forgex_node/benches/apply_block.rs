@@ -0,0 +1,185 @@
+//! apply_block на больших блоках: state на бинарных ключах против
+//! прежнего state на строках (String, String), воспроизведённого ниже.
+//!
+//!   cargo bench --bench apply_block
+
+use std::collections::HashMap;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use forgex_node::block::{Block, BlockBody, BlockHeader};
+use forgex_node::config::{FeeConfig, GenesisBalance, RewardConfig};
+use forgex_node::model::Tx;
+use forgex_node::network::DEVNET;
+use forgex_node::state::{ChainState, GLD, apply_block, treasury_addr};
+use forgex_node::tx::{TxExtra, ValidTxCore, return_structured_tx};
+use forgex_node::wallet::Wallet;
+
+const ACCOUNTS: u64 = 256;
+const BLOCK_SIZES: &[usize] = &[1_000, 10_000];
+
+/// Блок из n transfer по кругу между аккаунтами.
+/// Подписи не нужны: apply_block их не проверяет.
+fn make_block(accounts: &[Wallet], n: usize) -> Block {
+    let txs: Vec<ValidTxCore> = (0..n)
+        .map(|i| {
+            let from = &accounts[i % accounts.len()];
+            let to = &accounts[(i + 1) % accounts.len()];
+            let tx = Tx {
+                tx_hash: format!("{:064x}", i),
+                domain_tag: "GLD_TX_v1".into(),
+                chain_id: DEVNET.chain_id.into(),
+                tx_type: "transfer".into(),
+                from: from.address.clone(),
+                to: to.address.clone(),
+                token: "GLD".into(),
+                token_id: GLD,
+                amount: 1,
+                fee: 1,
+                nonce: (i / accounts.len()) as u64 + 1,
+                timestamp: 0,
+                pubkey: String::new(),
+                signature: String::new(),
+                encoding: "pipe_v1".into(),
+                extra: TxExtra::None,
+                memo: String::new(),
+            };
+            return_structured_tx(&tx, &DEVNET).unwrap()
+        })
+        .collect();
+
+    Block {
+        block_id: "bench".into(),
+        header: BlockHeader {
+            version: "0.1".into(),
+            chain_id: DEVNET.chain_id.into(),
+            height: 1,
+            prev_hash: "0".repeat(64),
+            timestamp_ms: 0,
+            tx_count: txs.len() as u32,
+            tx_root: String::new(),
+        },
+        body: BlockBody { txs, rewards: Vec::new() },
+    }
+}
+
+// ─────────────────────── ПРЕЖНИЙ STATE НА СТРОКАХ ───────────────────────
+
+#[derive(Default)]
+struct StringState {
+    balances: HashMap<(String, String), u64>,
+    nonces: HashMap<String, u64>,
+}
+
+#[derive(Default)]
+struct StringUndo {
+    balances: Vec<((String, String), Option<u64>)>,
+    nonces: Vec<(String, Option<u64>)>,
+}
+
+impl StringUndo {
+    fn remember_balance(&mut self, state: &StringState, key: &(String, String)) {
+        if !self.balances.iter().any(|(k, _)| k == key) {
+            self.balances.push((key.clone(), state.balances.get(key).copied()));
+        }
+    }
+
+    fn remember_nonce(&mut self, state: &StringState, addr: &str) {
+        if !self.nonces.iter().any(|(a, _)| a == addr) {
+            self.nonces.push((addr.to_string(), state.nonces.get(addr).copied()));
+        }
+    }
+}
+
+/// apply_block в том виде, в каком он был до бинарных ключей
+#[allow(clippy::type_complexity)]
+fn apply_block_strings(state: &mut StringState, block: &Block) -> (Vec<(String, String, u64)>, Vec<(String, u64)>) {
+    let mut undo = StringUndo::default();
+
+    for tx in &block.body.txs {
+        let from_key = (tx.from.clone(), tx.token.clone());
+        let to_key = (tx.to.clone(), tx.token.clone());
+        let treasury_key = (treasury_addr(&DEVNET), tx.token.clone());
+
+        undo.remember_balance(state, &from_key);
+        undo.remember_balance(state, &to_key);
+        undo.remember_balance(state, &treasury_key);
+        undo.remember_nonce(state, &tx.from);
+
+        let from_balance = state.balances.entry(from_key).or_insert(0);
+        *from_balance -= tx.amount + tx.fee;
+        *state.balances.entry(to_key).or_insert(0) += tx.amount;
+        *state.balances.entry(treasury_key).or_insert(0) += tx.fee;
+
+        let nonce_entry = state.nonces.entry(tx.from.clone()).or_insert(0);
+        if tx.nonce > *nonce_entry {
+            *nonce_entry = tx.nonce;
+        }
+    }
+
+    let balances = undo
+        .balances
+        .into_iter()
+        .map(|(key, _)| {
+            let bal = *state.balances.get(&key).unwrap_or(&0);
+            (key.0, key.1, bal)
+        })
+        .collect();
+    let nonces = undo
+        .nonces
+        .into_iter()
+        .map(|(addr, _)| {
+            let n = *state.nonces.get(&addr).unwrap_or(&0);
+            (addr, n)
+        })
+        .collect();
+    (balances, nonces)
+}
+
+// ─────────────────────── БЕНЧМАРК ───────────────────────
+
+fn bench_apply_block(c: &mut Criterion) {
+    let accounts: Vec<Wallet> = (0..ACCOUNTS).map(|i| Wallet::from_seed(7, i, &DEVNET)).collect();
+
+    // денег хватает на любое число прогонов
+    let funded = u64::MAX / (2 * ACCOUNTS);
+    let genesis: Vec<GenesisBalance> = accounts
+        .iter()
+        .map(|w| GenesisBalance {
+            address: w.address.clone(),
+            token: "GLD".into(),
+            amount: funded,
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("apply_block");
+    for &n in BLOCK_SIZES {
+        let block = make_block(&accounts, n);
+        group.throughput(Throughput::Elements(n as u64));
+
+        group.bench_with_input(BenchmarkId::new("binary_keys", n), &block, |b, block| {
+            // base fee держится на минимуме (1), иначе прогоны упрутся в fee транз;
+            // вся fee в казну и без выпуска — блоку не нужны награды
+            let fees = FeeConfig { min_base_fee: 1, target_block_txs: n as u32, burn_percent: 0, proposer_percent: 0 };
+            let rewards = RewardConfig { block_reward: 0, halving_interval: 0 };
+            // nonce строго следующий — один блок в один state дважды не ляжет,
+            // каждый прогон на свежем генезисе (его сборка в замер не входит)
+            b.iter_batched(
+                || ChainState::genesis(&DEVNET, &genesis, &fees, &rewards),
+                |mut state| apply_block(&mut state, block).unwrap(),
+                BatchSize::LargeInput,
+            );
+        });
+
+        group.bench_with_input(BenchmarkId::new("string_keys", n), &block, |b, block| {
+            let mut state = StringState::default();
+            for g in &genesis {
+                state.balances.insert((g.address.clone(), g.token.clone()), g.amount);
+            }
+            b.iter(|| apply_block_strings(&mut state, block));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_apply_block);
+criterion_main!(benches);
@@ -0,0 +1,126 @@
+use std::fmt;
+
+use bech32::{FromBase32, ToBase32};
+
+use crate::network::NetworkProfile;
+use crate::tx::{bytes_to_hex, sha256};
+
+/// Ядро адреса: 20 байт pubkey без hrp и контрольных сумм.
+/// Внутри ноды (state, undo, diff) адреса живут только в таком виде,
+/// bech32-строки — на входе (транзы, запросы, конфиг) и на выходе (дампы, ответы).
+/// Сеть в ключе не хранится: строку собирают под профиль сети ноды.
+/// Вид аккаунта хранится: у ключа и мультисига разные hrp, и одно ядро
+/// под разными видами — разные аккаунты.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AddrKey {
+    pub kind: AddrKind,
+    pub core: [u8; 20],
+}
+
+/// Чем аккаунт тратится: подписью своего ключа или политикой M-of-N
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AddrKind {
+    Key,
+    Multisig,
+}
+
+impl AddrKey {
+    pub const fn key(core: [u8; 20]) -> Self {
+        AddrKey { kind: AddrKind::Key, core }
+    }
+
+    pub const fn multisig(core: [u8; 20]) -> Self {
+        AddrKey { kind: AddrKind::Multisig, core }
+    }
+
+    pub fn is_multisig(&self) -> bool {
+        self.kind == AddrKind::Multisig
+    }
+}
+
+impl fmt::Debug for AddrKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            AddrKind::Key => write!(f, "{}", bytes_to_hex(&self.core)),
+            AddrKind::Multisig => write!(f, "multisig:{}", bytes_to_hex(&self.core)),
+        }
+    }
+}
+
+/// hrp адресов этого вида в сети
+fn hrp_of(kind: AddrKind, network: &NetworkProfile) -> &'static str {
+    match kind {
+        AddrKind::Key => network.hrp,
+        AddrKind::Multisig => network.multisig_hrp,
+    }
+}
+
+/// Разобрать адрес сети (bech32) с проверкой префикса и контрольной суммы.
+/// По hrp видно, ключ это или мультисиг.
+pub fn decode_address(addr: &str, network: &NetworkProfile) -> Result<AddrKey, String> {
+    // 1) decode bech32
+    let (hrp, data, _variant) =
+        bech32::decode(addr).map_err(|_| "invalid bech32 address".to_string())?;
+
+    // JS: decoded.hrp !== NETWORK.hrp — адрес другой сети
+    let kind = if hrp == network.hrp {
+        AddrKind::Key
+    } else if hrp == network.multisig_hrp {
+        AddrKind::Multisig
+    } else {
+        return Err(format!("invalid address prefix (hrp must be {} or {})", network.hrp, network.multisig_hrp));
+    };
+    let expected_hrp = hrp_of(kind, network);
+
+    // 2) convert 5-bit → 8-bit
+    let full_bytes: Vec<u8> = <Vec<u8> as FromBase32>::from_base32(&data)
+        .map_err(|_| "bech32 invalid base32 payload".to_string())?;
+
+    // JS: if (fullBytes.length < 28) return false;
+    if full_bytes.len() < 28 {
+        return Err(format!("invalid raw address length: {}", full_bytes.len()));
+    }
+
+    // берём первые 28 байт как в JS: const full28 = fullBytes.slice(0, 28);
+    let full28 = &full_bytes[..28];
+
+    // JS: первые 4 байта — тот же hrp
+    if &full28[..4] != expected_hrp.as_bytes() {
+        return Err(format!("address prefix bytes mismatch (expected {})", expected_hrp));
+    }
+
+    // JS: const payload24 = full28.slice(0, 24);
+    //      const checksum = full28.slice(24, 28);
+    let payload24 = &full28[..24];
+    let checksum = &full28[24..28];
+
+    // JS: const hash = sha256.array(payload24);
+    let hash = sha256(payload24);
+
+    // JS: compare first 4 bytes hash vs checksum
+    if &hash[..4] != checksum {
+        return Err("invalid address checksum".into());
+    }
+
+    let mut core = [0u8; 20];
+    core.copy_from_slice(&full28[4..24]);
+    Ok(AddrKey { kind, core })
+}
+
+/// Собрать bech32-адрес сети из ядра (hrp — по виду аккаунта)
+pub fn encode_address(key: &AddrKey, network: &NetworkProfile) -> String {
+    let hrp = hrp_of(key.kind, network);
+
+    // payload24[0..4] = hrp, [4..24] = core
+    let mut full28 = [0u8; 28];
+    full28[..4].copy_from_slice(hrp.as_bytes());
+    full28[4..24].copy_from_slice(&key.core);
+
+    // full28 = payload24 + sha256(payload24)[..4]
+    let hash = sha256(&full28[..24]);
+    full28[24..28].copy_from_slice(&hash[..4]);
+
+    // convertBits(8 -> 5) + bech32(hrp, ...); hrp профилей валидный, ошибки тут не бывает
+    bech32::encode(hrp, full28.to_base32(), bech32::Variant::Bech32)
+        .expect("network address always encodes")
+}
@@ -0,0 +1,278 @@
+use std::path::{Component, Path};
+
+use tracing::{info, warn};
+
+use crate::config::{config_path, load_config};
+use crate::events::{NodeEvent, emit};
+use crate::logging::reload_log_filter;
+use crate::mempool::{dump_mempool, mempool_remove_by_hash};
+use crate::model::{Decoded, decode_admin_response, decode_message, encode_admin_request, make_admin_response};
+use crate::node::Node;
+use crate::p2p::{HandlerFuture, p2p_request};
+use crate::state::{block_info, dump_state};
+
+// ─────────────────────── КОМАНДЫ ───────────────────────
+
+pub const ADMIN_STATUS: u8 = 1;
+pub const ADMIN_PAUSE: u8 = 2;
+pub const ADMIN_RESUME: u8 = 3;
+pub const ADMIN_FORCE_BLOCK: u8 = 4;
+/// args: 32 байта tx_hash
+pub const ADMIN_DROP_TX: u8 = 5;
+/// args: путь к файлу (utf-8) внутри admin_dump_dir
+pub const ADMIN_DUMP_STATE: u8 = 6;
+/// args: путь к файлу (utf-8) внутри admin_dump_dir
+pub const ADMIN_DUMP_MEMPOOL: u8 = 7;
+pub const ADMIN_RELOAD_CONFIG: u8 = 8;
+
+/// Сравнение токенов за время, не зависящее от места расхождения
+fn token_matches(given: &[u8], expected: &[u8]) -> bool {
+    if given.len() != expected.len() {
+        return false;
+    }
+    given
+        .iter()
+        .zip(expected)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+/// Обработать ADMIN-запрос (уже декодированный) и собрать ответ.
+/// Пустой admin_token в конфиге — админка выключена.
+pub fn handle_admin(node: &Node, token: &[u8], cmd: u8, args: &[u8]) -> Vec<u8> {
+    let expected = node.config().admin_token;
+
+    if expected.is_empty() {
+        warn!(cmd, "admin request while admin interface is disabled");
+        return make_admin_response(false, "admin interface disabled");
+    }
+
+    if !token_matches(token, expected.as_bytes()) {
+        warn!(cmd, "admin request with invalid token");
+        return make_admin_response(false, "unauthorized");
+    }
+
+    match run_command(node, cmd, args) {
+        Ok(text) => {
+            info!(cmd, result = %text, "admin command done");
+            make_admin_response(true, &text)
+        }
+        Err(e) => {
+            warn!(cmd, error = %e, "admin command failed");
+            make_admin_response(false, &e)
+        }
+    }
+}
+
+/// Обработчик для run_admin_server: здесь принимаем только ADMIN
+pub fn admin_handler(node: &Node, msg: Vec<u8>) -> HandlerFuture<'_> {
+    let response = match decode_message(&msg) {
+        Ok(Decoded::Admin { token, cmd, args }) => handle_admin(node, &token, cmd, &args),
+        Ok(_) => {
+            warn!("non-admin request on admin port");
+            make_admin_response(false, "only admin requests are served here")
+        }
+        Err(e) => {
+            warn!(error = %e, "failed to decode admin message");
+            b"ERR".to_vec()
+        }
+    };
+    Box::pin(std::future::ready(response))
+}
+
+fn run_command(node: &Node, cmd: u8, args: &[u8]) -> Result<String, String> {
+    match cmd {
+        ADMIN_STATUS => {
+            let (height, hash) = block_info(&node.state.lock().unwrap()).unwrap_or((0, "0".repeat(64)));
+            let mempool_size = node.mempool.lock().unwrap().len();
+            Ok(format!(
+                "height={} hash={} mempool={} paused={}",
+                height,
+                hash,
+                mempool_size,
+                node.control.is_paused()
+            ))
+        }
+
+        ADMIN_PAUSE => {
+            node.control.pause();
+            Ok("block production paused".into())
+        }
+
+        ADMIN_RESUME => {
+            node.control.resume();
+            Ok("block production resumed".into())
+        }
+
+        ADMIN_FORCE_BLOCK => {
+            node.control.force_block();
+            Ok("block requested".into())
+        }
+
+        ADMIN_DROP_TX => {
+            if args.len() != 32 {
+                return Err("drop_tx expects 32-byte tx hash".into());
+            }
+            let tx_hash: String = args.iter().map(|b| format!("{:02x}", b)).collect();
+
+            let removed = {
+                let mut mp = node.mempool.lock().unwrap();
+                mempool_remove_by_hash(&mut mp, &tx_hash)
+            };
+
+            match removed {
+                Some(_) => {
+                    emit(node, NodeEvent::TxEvicted {
+                        tx_hash: tx_hash.clone(),
+                        reason: "dropped by admin".into(),
+                    });
+                    Ok(format!("dropped {}", tx_hash))
+                }
+                None => Err(format!("tx {} not in mempool", tx_hash)),
+            }
+        }
+
+        ADMIN_DUMP_STATE => {
+            let path = dump_path(&node.config().admin_dump_dir, args)?;
+            let n = dump_state(&node.state.lock().unwrap(), &path)?;
+            Ok(format!("wrote {} entries to {}", n, path))
+        }
+
+        ADMIN_DUMP_MEMPOOL => {
+            let path = dump_path(&node.config().admin_dump_dir, args)?;
+            let n = dump_mempool(&node.mempool.lock().unwrap(), node.network, &path)?;
+            Ok(format!("wrote {} txs to {}", n, path))
+        }
+
+        ADMIN_RELOAD_CONFIG => reload_config(node),
+
+        _ => Err(format!("unknown admin command {}", cmd)),
+    }
+}
+
+/// Путь дампа внутри admin_dump_dir. Абсолютные пути и ".." не пускаем:
+/// иначе токен админки давал бы запись в любой файл, доступный ноде.
+fn dump_path(dir: &str, args: &[u8]) -> Result<String, String> {
+    let path = std::str::from_utf8(args).map_err(|_| "invalid utf-8 in path".to_string())?;
+    if path.is_empty() {
+        return Err("path is required".into());
+    }
+    let rel = Path::new(path);
+    if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(format!("{} is outside admin_dump_dir; give a path relative to it", path));
+    }
+
+    let full = Path::new(dir).join(rel);
+    if let Some(parent) = full.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    Ok(full.to_string_lossy().into_owned())
+}
+
+/// Перечитать конфиг с диска.
+/// Интервал блоков, размер блока, адрес индексера, токен, папка дампов и уровни логов
+/// применяются сразу; адреса слушателей — только после рестарта.
+/// Конфиг другой сети не принимаем вовсе: всё в нём посчитано под чужой профиль.
+fn reload_config(node: &Node) -> Result<String, String> {
+    let path = config_path();
+    let new_cfg = load_config(&path)?;
+    let old_cfg = node.config();
+
+    if new_cfg.profile() != node.network {
+        return Err(format!(
+            "{} is for network {}, node runs {}; network change requires restart",
+            path,
+            new_cfg.profile().name,
+            node.network.name
+        ));
+    }
+
+    let mut notes = Vec::new();
+    if new_cfg.p2p_addr != old_cfg.p2p_addr {
+        notes.push("p2p_addr change requires restart".to_string());
+    }
+    if new_cfg.admin_addr != old_cfg.admin_addr || new_cfg.admin_token.is_empty() != old_cfg.admin_token.is_empty() {
+        notes.push("admin_addr change requires restart".to_string());
+    }
+    if new_cfg.metrics_addr != old_cfg.metrics_addr {
+        notes.push("metrics_addr change requires restart".to_string());
+    }
+    if new_cfg.verify_threads != old_cfg.verify_threads || new_cfg.verify_queue != old_cfg.verify_queue {
+        notes.push("verify pool change requires restart".to_string());
+    }
+    // параметры комиссий живут в state с генезиса, на лету их не меняем
+    if new_cfg.fees != old_cfg.fees {
+        notes.push("fees change requires restart".to_string());
+    }
+    // награды и генезис — тоже из state
+    if new_cfg.rewards != old_cfg.rewards {
+        notes.push("rewards change requires restart".to_string());
+    }
+    if new_cfg.genesis != old_cfg.genesis {
+        notes.push("genesis change requires restart".to_string());
+    }
+    if new_cfg.log.format != old_cfg.log.format {
+        notes.push("log.format change requires restart".to_string());
+    }
+    if let Err(e) = reload_log_filter(&new_cfg.log) {
+        notes.push(e);
+    }
+
+    node.set_config(new_cfg);
+
+    let mut text = format!("reloaded {}", path);
+    for note in notes {
+        text.push_str("; ");
+        text.push_str(&note);
+    }
+    Ok(text)
+}
+
+// ─────────────────────── CLI ───────────────────────
+
+const ADMIN_USAGE: &str = "usage: forgex_node admin <status|pause|resume|force-block|drop-tx <hash>|dump-state <path>|dump-mempool <path>|reload>";
+
+/// `forgex_node admin <команда> [аргумент]`
+/// Адрес (admin_addr) и токен — из конфига; токен можно переопределить FORGEX_ADMIN_TOKEN.
+pub async fn run_admin_cli(args: &[String]) -> Result<(), String> {
+    let name = args.first().ok_or_else(|| ADMIN_USAGE.to_string())?;
+    let arg = args.get(1).map(String::as_str);
+
+    let (cmd, payload) = match (name.as_str(), arg) {
+        ("status", None) => (ADMIN_STATUS, Vec::new()),
+        ("pause", None) => (ADMIN_PAUSE, Vec::new()),
+        ("resume", None) => (ADMIN_RESUME, Vec::new()),
+        ("force-block", None) => (ADMIN_FORCE_BLOCK, Vec::new()),
+        ("drop-tx", Some(hash)) => (ADMIN_DROP_TX, parse_tx_hash(hash)?),
+        ("dump-state", Some(path)) => (ADMIN_DUMP_STATE, path.as_bytes().to_vec()),
+        ("dump-mempool", Some(path)) => (ADMIN_DUMP_MEMPOOL, path.as_bytes().to_vec()),
+        ("reload", None) => (ADMIN_RELOAD_CONFIG, Vec::new()),
+        _ => return Err(ADMIN_USAGE.into()),
+    };
+
+    let cfg = load_config(&config_path())?;
+    let token = std::env::var("FORGEX_ADMIN_TOKEN").unwrap_or(cfg.admin_token);
+
+    let request = encode_admin_request(&token, cmd, &payload)?;
+    let response = p2p_request(&cfg.admin_addr, &request)
+        .await
+        .map_err(|e| format!("failed to reach node admin at {}: {}", cfg.admin_addr, e))?;
+
+    let (ok, text) = decode_admin_response(&response)?;
+    if ok {
+        println!("{}", text);
+        Ok(())
+    } else {
+        Err(text)
+    }
+}
+
+fn parse_tx_hash(hex: &str) -> Result<Vec<u8>, String> {
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("tx hash must be 64 hex chars".into());
+    }
+    Ok((0..64)
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect())
+}
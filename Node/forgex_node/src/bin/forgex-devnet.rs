@@ -0,0 +1,412 @@
+//! Локальная тестовая сеть одной командой: нода + индексер + RPC.
+//!
+//!   forgex-devnet [--network NAME] [--accounts N] [--amount N] [--seed N] [--block-interval-ms N] [--keep]
+//!
+//! Порты берём свободные, данные — во временной папке, генезис раздаёт
+//! GLD тестовым ключам. Ctrl-C (или падение любого процесса) гасит всё
+//! и удаляет папку (с --keep — оставляет). --network (по умолчанию devnet)
+//! поднимает ту же связку с chain_id, адресами и параметрами другой сети.
+//!
+//! Нода ищется рядом с этим бинарником, индексер и RPC — в target соседних
+//! крейтов. Пути можно задать FORGEX_NODE_BIN / FORGEX_INDEXER_BIN / FORGEX_RPC_BIN.
+
+use std::fs::File;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{ExitCode, Stdio};
+
+use forgex_node::config::{GenesisBalance, NodeConfig};
+use forgex_node::network::{DEVNET, NetworkProfile, network_by_name, network_names};
+use forgex_node::tx::{bytes_to_hex, sha256};
+use forgex_node::wallet::Wallet;
+use tokio::process::{Child, Command};
+use tokio::time::{Duration, Instant, sleep, timeout};
+
+/// Сколько ждём, пока процесс откроет порт
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Сколько ждём процесс после SIGTERM, потом убиваем
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Options {
+    network: &'static NetworkProfile,
+    accounts: u64,
+    amount: u64,
+    seed: Option<u64>,
+    block_interval_ms: u64,
+    keep: bool,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut opts = Options {
+        network: &DEVNET,
+        accounts: 5,
+        // 1_000_000 GLD на ключ
+        amount: 1_000_000_000_000,
+        seed: None,
+        block_interval_ms: 1000,
+        keep: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+        let num = |v: String| v.parse::<u64>().map_err(|_| format!("invalid number: {}", v));
+
+        match flag.as_str() {
+            "--network" => {
+                let name = value()?;
+                opts.network = network_by_name(&name)
+                    .ok_or_else(|| format!("unknown network {} (known: {})", name, network_names()))?;
+            }
+            "--accounts" => opts.accounts = num(value()?)?,
+            "--amount" => opts.amount = num(value()?)?,
+            "--seed" => opts.seed = Some(num(value()?)?),
+            "--block-interval-ms" => opts.block_interval_ms = num(value()?)?,
+            "--keep" => opts.keep = true,
+            _ => return Err(format!("unknown flag {}", flag)),
+        }
+    }
+
+    if opts.accounts == 0 {
+        return Err("need at least one account".into());
+    }
+    Ok(opts)
+}
+
+/// Свободные порты на loopback. Держим все слушатели до конца,
+/// чтобы ОС не выдала один порт дважды.
+fn free_ports(n: usize) -> Result<Vec<String>, String> {
+    let listeners = (0..n)
+        .map(|_| TcpListener::bind("127.0.0.1:0"))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("failed to allocate port: {}", e))?;
+
+    listeners
+        .iter()
+        .map(|l| l.local_addr().map(|a| a.to_string()))
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("failed to allocate port: {}", e))
+}
+
+/// Временная папка сети, удаляется при выходе (если не --keep)
+struct DataDir {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl DataDir {
+    fn create(keep: bool) -> Result<Self, String> {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!("forgex-devnet-{}-{}", std::process::id(), stamp));
+
+        for sub in ["node", "indexer", "rpc"] {
+            std::fs::create_dir_all(path.join(sub))
+                .map_err(|e| format!("failed to create {}: {}", path.join(sub).display(), e))?;
+        }
+        Ok(DataDir { path, keep })
+    }
+
+    fn sub(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for DataDir {
+    fn drop(&mut self) {
+        if self.keep {
+            println!("data kept in {}", self.path.display());
+        } else if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            eprintln!("failed to remove {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Найти бинарник: переменная окружения, иначе в target/<profile> крейта.
+/// crate_dir — путь крейта от корня репозитория (None — рядом с нами).
+fn find_bin(env_key: &str, name: &str, crate_dir: Option<&str>) -> Result<PathBuf, String> {
+    if let Ok(path) = std::env::var(env_key) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let exe = std::env::current_exe().map_err(|e| format!("current_exe: {}", e))?;
+    let exe_dir = exe.parent().unwrap_or(Path::new("."));
+
+    let candidate = match crate_dir {
+        None => exe_dir.join(name),
+        Some(dir) => {
+            // тот же профиль (debug/release), что и у нас
+            let profile = exe_dir.file_name().and_then(|p| p.to_str()).unwrap_or("debug");
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../..")
+                .join(dir)
+                .join("target")
+                .join(profile)
+                .join(name)
+        }
+    };
+
+    if candidate.exists() {
+        Ok(candidate)
+    } else {
+        Err(format!(
+            "{} not found at {} (build it with `cargo build`{} or set {})",
+            name,
+            candidate.display(),
+            crate_dir.map(|d| format!(" in {}", d)).unwrap_or_default(),
+            env_key
+        ))
+    }
+}
+
+/// Запущенный процесс сети; stdout/stderr — в лог в его папке
+struct Proc {
+    name: &'static str,
+    child: Child,
+    log: PathBuf,
+}
+
+fn spawn(name: &'static str, bin: &Path, dir: &Path, env: &[(&str, &str)]) -> Result<Proc, String> {
+    let log = dir.join(format!("{}.log", name));
+    let out = File::create(&log).map_err(|e| format!("failed to create {}: {}", log.display(), e))?;
+    let err = out.try_clone().map_err(|e| format!("failed to open {}: {}", log.display(), e))?;
+
+    let mut cmd = Command::new(bin);
+    cmd.current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(out))
+        .stderr(Stdio::from(err))
+        // не тянем чужие настройки из окружения
+        .env_remove("FORGEX_NODE_SUBSCRIBE")
+        .env_remove("FORGEX_ADMIN_TOKEN")
+        .kill_on_drop(true);
+    for (k, v) in env {
+        cmd.env(k, v);
+    }
+
+    let child = cmd
+        .spawn()
+        .map_err(|e| format!("failed to start {} ({}): {}", name, bin.display(), e))?;
+    Ok(Proc { name, child, log })
+}
+
+/// Ждём, пока процесс начнёт слушать addr
+async fn wait_listening(proc: &mut Proc, addr: &str) -> Result<(), String> {
+    let deadline = Instant::now() + START_TIMEOUT;
+    loop {
+        if tokio::net::TcpStream::connect(addr).await.is_ok() {
+            return Ok(());
+        }
+        if let Ok(Some(status)) = proc.child.try_wait() {
+            return Err(format!("{} exited with {} (see {})", proc.name, status, proc.log.display()));
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("{} did not open {} in time (see {})", proc.name, addr, proc.log.display()));
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Мягко остановить: SIGTERM (нода сбрасывает данные на диск), потом kill
+async fn stop(proc: &mut Proc) {
+    if let Ok(Some(_)) = proc.child.try_wait() {
+        return;
+    }
+
+    #[cfg(unix)]
+    if let Some(pid) = proc.child.id() {
+        // SAFETY: шлём сигнал своему же дочернему процессу
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = proc.child.start_kill();
+
+    match timeout(STOP_TIMEOUT, proc.child.wait()).await {
+        Ok(_) => println!("{} stopped", proc.name),
+        Err(_) => {
+            eprintln!("{} did not stop in time, killing", proc.name);
+            let _ = proc.child.kill().await;
+        }
+    }
+}
+
+/// Ждём Ctrl-C, SIGTERM или выхода любого процесса
+async fn wait_for_exit(procs: &mut [Proc]) {
+    #[cfg(unix)]
+    let mut term = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()).ok();
+
+    loop {
+        #[cfg(unix)]
+        let term_recv = async {
+            match term.as_mut() {
+                Some(t) => {
+                    t.recv().await;
+                }
+                None => std::future::pending::<()>().await,
+            }
+        };
+        #[cfg(not(unix))]
+        let term_recv = std::future::pending::<()>();
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("\ninterrupted, shutting down devnet");
+                return;
+            }
+            _ = term_recv => {
+                println!("terminated, shutting down devnet");
+                return;
+            }
+            _ = sleep(Duration::from_millis(500)) => {}
+        }
+
+        for proc in procs.iter_mut() {
+            if let Ok(Some(status)) = proc.child.try_wait() {
+                eprintln!("{} exited with {} (see {}), shutting down devnet", proc.name, status, proc.log.display());
+                return;
+            }
+        }
+    }
+}
+
+async fn run(opts: Options) -> Result<(), String> {
+    let node_bin = find_bin("FORGEX_NODE_BIN", "forgex_node", None)?;
+    let indexer_bin = find_bin("FORGEX_INDEXER_BIN", "forgex-indexer", Some("indexer/forgex-indexer"))?;
+    let rpc_bin = find_bin("FORGEX_RPC_BIN", "forgex_rpc_server", Some("rpc_server/forgex_rpc_server"))?;
+
+    // без --seed ключи каждый раз новые; seed печатаем, чтобы сеть можно было повторить
+    let seed = opts.seed.unwrap_or_else(|| {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        nanos ^ u64::from(std::process::id())
+    });
+
+    let network = opts.network.name;
+
+    // адреса ключей — уже в HRP выбранной сети
+    let wallets: Vec<Wallet> = (0..opts.accounts).map(|i| Wallet::from_seed(seed, i, opts.network)).collect();
+    let admin_token = bytes_to_hex(&sha256(format!("forgex-devnet-admin-{}", seed).as_bytes()));
+
+    let ports = free_ports(6)?;
+    let (node_p2p, node_metrics, indexer_p2p, indexer_http, rpc_http, node_admin) =
+        (&ports[0], &ports[1], &ports[2], &ports[3], &ports[4], &ports[5]);
+
+    let data = DataDir::create(opts.keep)?;
+    let node_dir = data.sub("node");
+    let config_path = node_dir.join("forgex_node.toml");
+
+    let cfg = NodeConfig {
+        p2p_addr: node_p2p.clone(),
+        metrics_addr: node_metrics.clone(),
+        indexer_addr: indexer_p2p.clone(),
+        block_interval_ms: opts.block_interval_ms,
+        admin_token: admin_token.clone(),
+        admin_addr: node_admin.clone(),
+        genesis: wallets
+            .iter()
+            .map(|w| GenesisBalance {
+                address: w.address.clone(),
+                token: "GLD".into(),
+                amount: opts.amount,
+            })
+            .collect(),
+        ..NodeConfig::for_network(opts.network)
+    };
+    let text = toml::to_string(&cfg).map_err(|e| format!("failed to encode node config: {}", e))?;
+    std::fs::write(&config_path, text)
+        .map_err(|e| format!("failed to write {}: {}", config_path.display(), e))?;
+    let config_path = config_path.to_string_lossy().into_owned();
+
+    let mut procs: Vec<Proc> = Vec::new();
+
+    // индексер первым: нода пушит в него каждый блок
+    let started = async {
+        procs.push(spawn(
+            "indexer",
+            &indexer_bin,
+            &data.sub("indexer"),
+            &[
+                ("FORGEX_INDEXER_P2P_ADDR", indexer_p2p),
+                ("FORGEX_INDEXER_HTTP_ADDR", indexer_http),
+                ("FORGEX_NODE_ADDR", node_p2p),
+                ("FORGEX_NETWORK", network),
+            ],
+        )?);
+        wait_listening(procs.last_mut().unwrap(), indexer_p2p).await?;
+
+        procs.push(spawn("node", &node_bin, &node_dir, &[("FORGEX_NODE_CONFIG", &config_path)])?);
+        wait_listening(procs.last_mut().unwrap(), node_p2p).await?;
+
+        procs.push(spawn(
+            "rpc",
+            &rpc_bin,
+            &data.sub("rpc"),
+            &[("FORGEX_RPC_ADDR", rpc_http), ("FORGEX_NODE_ADDR", node_p2p), ("FORGEX_NETWORK", network)],
+        )?);
+        wait_listening(procs.last_mut().unwrap(), rpc_http).await
+    }
+    .await;
+
+    if let Err(e) = started {
+        // гасим то, что успело подняться
+        for proc in procs.iter_mut().rev() {
+            stop(proc).await;
+        }
+        return Err(e);
+    }
+
+    println!("forgex devnet is up (network {}, seed {})", network, seed);
+    println!("  node p2p      {}", node_p2p);
+    println!("  node metrics  http://{}/metrics", node_metrics);
+    println!("  indexer p2p   {}", indexer_p2p);
+    println!("  indexer api   http://{}", indexer_http);
+    println!("  rpc           http://{}", rpc_http);
+    println!("  node admin    {}", node_admin);
+    println!("  admin token   {}", admin_token);
+    println!("  node config   {}", config_path);
+    println!("  data dir      {}", data.path.display());
+    println!();
+    println!("funded accounts ({} GLD units each):", opts.amount);
+    for (i, w) in wallets.iter().enumerate() {
+        println!("  #{:<3} {}", i, w.address);
+        println!("       secret {}", w.secret_hex());
+    }
+    println!();
+    println!("press Ctrl-C to stop");
+
+    wait_for_exit(&mut procs).await;
+
+    // сначала RPC, потом нода (допушивает блоки в индексер), индексер последним
+    for proc in procs.iter_mut().rev() {
+        stop(proc).await;
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let opts = match parse_args() {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("usage: forgex-devnet [--network NAME] [--accounts N] [--amount N] [--seed N] [--block-interval-ms N] [--keep]");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(opts).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,459 @@
+//! Генератор нагрузки: сколько transfer в секунду нода принимает и включает в блоки.
+//!
+//!   forgex-loadgen --funder-secret HEX [--network NAME] [--node ADDR] [--accounts N] [--rate TPS]
+//!                  [--duration-ms N] [--drain-ms N] [--concurrency N] [--fee N] [--seed N]
+//!                  [--batch N] [--compact]
+//!
+//! Funder (например, ключ из forgex-devnet) раздаёт GLD тестовым ключам,
+//! затем ключи шлют подписанные transfer по FGX1 (type 1) с заданной частотой.
+//! Включение в блок видим по подписке на квитанции. В конце — задержки
+//! приёма и включения, причины отказов и устойчивый TPS.
+//! С --batch N транзы уходят пачками SEND_TX_BATCH (проверяются на ноде параллельно).
+//! С --compact — в компактном виде (pipe_v2 без pubkey, нода восстанавливает его из подписи).
+//!
+//! Ключ funder'а можно передать и через FORGEX_LOADGEN_FUNDER.
+//! Сеть (по умолчанию devnet) задаёт chain_id, адреса и порт ноды по умолчанию.
+
+use std::collections::{BTreeMap, HashMap};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+
+use forgex_node::envelope::compact_tx;
+use forgex_node::model::{
+    MAX_TX_BATCH, Tx, decode_height_response, decode_nonce_response, decode_receipts_payload,
+    decode_tx_batch_response, decode_tx_response, make_ask_height_message, make_ask_nonce_message,
+    make_send_tx_batch_message, make_send_tx_message, make_subscribe_message,
+};
+use forgex_node::network::{DEVNET, NetworkProfile, network_by_name, network_names};
+use forgex_node::p2p::p2p_request;
+use forgex_node::tx::hex_to_bytes;
+use forgex_node::wallet::Wallet;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+use tokio::time::{Duration, Instant, sleep};
+
+/// Сумма одного transfer под нагрузкой
+const LOAD_AMOUNT: u64 = 1;
+
+/// Сколько ждём включения раздачи funder'а
+const FUNDING_TIMEOUT: Duration = Duration::from_secs(120);
+
+struct Options {
+    network: &'static NetworkProfile,
+    node: String,
+    funder_secret: String,
+    accounts: u64,
+    rate: u64,
+    duration_ms: u64,
+    drain_ms: u64,
+    concurrency: usize,
+    fee: u64,
+    seed: u64,
+    batch: usize,
+    compact: bool,
+}
+
+const USAGE: &str = "usage: forgex-loadgen --funder-secret HEX [--network NAME] [--node ADDR] [--accounts N] [--rate TPS] \
+[--duration-ms N] [--drain-ms N] [--concurrency N] [--fee N] [--seed N] [--batch N] [--compact]";
+
+fn parse_args() -> Result<Options, String> {
+    let mut opts = Options {
+        network: &DEVNET,
+        // пустой — порт ноды по умолчанию для сети
+        node: String::new(),
+        funder_secret: std::env::var("FORGEX_LOADGEN_FUNDER").unwrap_or_default(),
+        accounts: 100,
+        rate: 100,
+        duration_ms: 30_000,
+        drain_ms: 15_000,
+        concurrency: 64,
+        // с запасом над base fee: под нагрузкой он растёт
+        fee: 1000,
+        seed: now_ms(),
+        batch: 1,
+        compact: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+        let num = |v: String| v.parse::<u64>().map_err(|_| format!("invalid number: {}", v));
+
+        match flag.as_str() {
+            "--network" => {
+                let name = value()?;
+                opts.network = network_by_name(&name)
+                    .ok_or_else(|| format!("unknown network {} (known: {})", name, network_names()))?;
+            }
+            "--node" => opts.node = value()?,
+            "--funder-secret" => opts.funder_secret = value()?,
+            "--accounts" => opts.accounts = num(value()?)?,
+            "--rate" => opts.rate = num(value()?)?,
+            "--duration-ms" => opts.duration_ms = num(value()?)?,
+            "--drain-ms" => opts.drain_ms = num(value()?)?,
+            "--concurrency" => opts.concurrency = num(value()?)? as usize,
+            "--fee" => opts.fee = num(value()?)?,
+            "--seed" => opts.seed = num(value()?)?,
+            "--batch" => opts.batch = num(value()?)? as usize,
+            "--compact" => opts.compact = true,
+            _ => return Err(format!("unknown flag {}", flag)),
+        }
+    }
+
+    if opts.node.is_empty() {
+        opts.node = format!("127.0.0.1:{}", opts.network.p2p_port);
+    }
+    if opts.funder_secret.is_empty() {
+        return Err("--funder-secret is required".into());
+    }
+    if opts.accounts == 0 || opts.rate == 0 || opts.concurrency == 0 {
+        return Err("accounts, rate and concurrency must be > 0".into());
+    }
+    if opts.batch == 0 || opts.batch > MAX_TX_BATCH {
+        return Err(format!("batch must be between 1 and {}", MAX_TX_BATCH));
+    }
+    if opts.fee == 0 {
+        return Err("fee must be > 0".into());
+    }
+    Ok(opts)
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+async fn ask_nonce(node: &str, address: &str) -> Result<u64, String> {
+    let resp = p2p_request(node, &make_ask_nonce_message(address)?)
+        .await
+        .map_err(|e| format!("ask nonce: {}", e))?;
+    decode_nonce_response(&resp)
+}
+
+async fn ask_height(node: &str) -> Result<u64, String> {
+    let resp = p2p_request(node, &make_ask_height_message())
+        .await
+        .map_err(|e| format!("ask height: {}", e))?;
+    decode_height_response(&resp)
+}
+
+// ─────────────────────── ВКЛЮЧЕНИЕ В БЛОКИ ───────────────────────
+
+/// Когда какая транза попала в блок (по квитанциям подписки)
+#[derive(Default)]
+struct Inclusions {
+    at: Mutex<HashMap<String, Instant>>,
+    blocks: Mutex<u64>,
+}
+
+/// Подписка на квитанции с высоты from_height; работает до закрытия соединения
+async fn follow_receipts(node: String, from_height: u64, inclusions: Arc<Inclusions>) -> Result<(), String> {
+    let mut stream = TcpStream::connect(&node)
+        .await
+        .map_err(|e| format!("subscribe to {}: {}", node, e))?;
+    tokio::io::AsyncWriteExt::write_all(&mut stream, &make_subscribe_message(from_height, true))
+        .await
+        .map_err(|e| format!("subscribe: {}", e))?;
+
+    loop {
+        // BLOCK и RECEIPTS: FGX1 | type | u32 len | payload
+        let mut header = [0u8; 9];
+        if stream.read_exact(&mut header).await.is_err() {
+            return Ok(());
+        }
+        if &header[0..4] != b"FGX1" {
+            return Err("invalid magic in subscription".into());
+        }
+        let len = u32::from_be_bytes(header[5..9].try_into().unwrap()) as usize;
+        let mut payload = vec![0u8; len];
+        stream
+            .read_exact(&mut payload)
+            .await
+            .map_err(|e| format!("subscription read: {}", e))?;
+
+        if header[4] != 9 {
+            continue;
+        }
+
+        let now = Instant::now();
+        let (_height, entries) = decode_receipts_payload(&payload)?;
+        *inclusions.blocks.lock().unwrap() += 1;
+        let mut at = inclusions.at.lock().unwrap();
+        for (hash, _status) in entries {
+            at.entry(hash).or_insert(now);
+        }
+    }
+}
+
+// ─────────────────────── ОТПРАВКА ───────────────────────
+
+/// Итог одной отправки
+struct Sent {
+    tx_hash: String,
+    sent_at: Instant,
+    /// время до TX_RESPONSE
+    admission: Duration,
+    /// None — принята, Some — причина отказа
+    rejected: Option<String>,
+}
+
+async fn send_tx(node: &str, msg: &[u8], tx_hash: String) -> Sent {
+    send_request(node, msg, vec![tx_hash], false).await.remove(0)
+}
+
+/// Один запрос: SEND_TX или SEND_TX_BATCH (batched). Итог — по транзе.
+async fn send_request(node: &str, msg: &[u8], tx_hashes: Vec<String>, batched: bool) -> Vec<Sent> {
+    let sent_at = Instant::now();
+    let n = tx_hashes.len();
+
+    let rejected: Vec<Option<String>> = match p2p_request(node, msg).await {
+        Ok(resp) if batched => match decode_tx_batch_response(&resp) {
+            Ok(results) if results.len() == n => results,
+            Ok(_) => vec![Some("batch response size mismatch".into()); n],
+            Err(e) => vec![Some(e); n],
+        },
+        Ok(resp) => match decode_tx_response(&resp) {
+            Ok(text) if text == "ACCEPTED" => vec![None],
+            Ok(reason) => vec![Some(reason)],
+            Err(e) => vec![Some(e)],
+        },
+        Err(e) => vec![Some(format!("network: {}", e)); n],
+    };
+
+    let admission = sent_at.elapsed();
+    tx_hashes
+        .into_iter()
+        .zip(rejected)
+        .map(|(tx_hash, rejected)| Sent {
+            tx_hash,
+            sent_at,
+            admission,
+            rejected,
+        })
+        .collect()
+}
+
+// ─────────────────────── ОТЧЁТ ───────────────────────
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+fn print_latency(name: &str, mut values: Vec<Duration>) {
+    values.sort();
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    println!(
+        "  {:<10} p50 {:>8.1} ms  p95 {:>8.1} ms  p99 {:>8.1} ms  max {:>8.1} ms  (n={})",
+        name,
+        ms(percentile(&values, 0.50)),
+        ms(percentile(&values, 0.95)),
+        ms(percentile(&values, 0.99)),
+        ms(values.last().copied().unwrap_or_default()),
+        values.len()
+    );
+}
+
+// ─────────────────────── ПРОГОН ───────────────────────
+
+/// Раздать каждому ключу amount GLD и дождаться, пока всё войдёт в блоки.
+/// Нагрузку не начинаем раньше: транза без покрытия ломает блок целиком.
+async fn fund_accounts(
+    opts: &Options,
+    funder: &Wallet,
+    accounts: &[Wallet],
+    amount: u64,
+    inclusions: &Inclusions,
+) -> Result<(), String> {
+    let mut nonce = ask_nonce(&opts.node, &funder.address).await?;
+    let mut hashes = Vec::with_capacity(accounts.len());
+
+    for acc in accounts {
+        nonce += 1;
+        let tx = funder.transfer(&acc.address, amount, opts.fee, nonce, now_ms())?;
+        let sent = send_tx(&opts.node, &make_send_tx_message(&tx)?, tx.tx_hash.clone()).await;
+        if let Some(reason) = sent.rejected {
+            return Err(format!("funding tx to {} rejected: {}", acc.address, reason));
+        }
+        hashes.push(tx.tx_hash);
+    }
+
+    let deadline = Instant::now() + FUNDING_TIMEOUT;
+    loop {
+        let pending = {
+            let at = inclusions.at.lock().unwrap();
+            hashes.iter().filter(|h| !at.contains_key(*h)).count()
+        };
+        if pending == 0 {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("{} funding txs not included in time", pending));
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+}
+
+async fn run(opts: Options) -> Result<(), String> {
+    let funder = Wallet::from_secret(&hex_to_bytes(&opts.funder_secret)?, opts.network)?;
+    let accounts: Vec<Wallet> = (0..opts.accounts).map(|i| Wallet::from_seed(opts.seed, i, opts.network)).collect();
+
+    let total = opts.rate * opts.duration_ms / 1000;
+    let per_account = total.div_ceil(opts.accounts);
+    // с запасом на пару лишних транз
+    let funding = (per_account + 2) * (LOAD_AMOUNT + opts.fee);
+
+    let inclusions = Arc::new(Inclusions::default());
+    let height = ask_height(&opts.node).await?;
+    let follower = tokio::spawn(follow_receipts(opts.node.clone(), height + 1, inclusions.clone()));
+
+    println!(
+        "funding {} accounts with {} GLD units each from {}",
+        accounts.len(),
+        funding,
+        funder.address
+    );
+    fund_accounts(&opts, &funder, &accounts, funding, &inclusions).await?;
+
+    // все транзы подписываем заранее, чтобы мерить ноду, а не себя
+    println!("signing {} transfers", total);
+    let mut nonces = Vec::with_capacity(accounts.len());
+    for acc in &accounts {
+        nonces.push(ask_nonce(&opts.node, &acc.address).await?);
+    }
+    let mut signed: Vec<Tx> = Vec::with_capacity(total as usize);
+    for i in 0..total as usize {
+        let from = i % accounts.len();
+        let to = &accounts[(from + 1) % accounts.len()].address;
+        nonces[from] += 1;
+        let tx = accounts[from].transfer(to, LOAD_AMOUNT, opts.fee, nonces[from], now_ms())?;
+        signed.push(match opts.compact {
+            // компактная форма есть только у pipe_v2
+            true => compact_tx(accounts[from].with_memo(tx, "")?)?,
+            false => tx,
+        });
+    }
+
+    // по запросу на каждые batch транз
+    let batched = opts.batch > 1;
+    let mut prepared: Vec<(Vec<u8>, Vec<String>)> = Vec::with_capacity(signed.len().div_ceil(opts.batch));
+    for chunk in signed.chunks(opts.batch) {
+        let msg = if batched {
+            make_send_tx_batch_message(chunk)?
+        } else {
+            make_send_tx_message(&chunk[0])?
+        };
+        prepared.push((msg, chunk.iter().map(|tx| tx.tx_hash.clone()).collect()));
+    }
+
+    println!(
+        "sending at {} tx/s for {} ms (concurrency {}, batch {})",
+        opts.rate, opts.duration_ms, opts.concurrency, opts.batch
+    );
+
+    let limit = Arc::new(Semaphore::new(opts.concurrency));
+    let results = Arc::new(Mutex::new(Vec::with_capacity(prepared.len())));
+    let mut tasks = Vec::with_capacity(prepared.len());
+
+    let blocks_before = *inclusions.blocks.lock().unwrap();
+    let start = Instant::now();
+    for (i, (msg, tx_hashes)) in prepared.into_iter().enumerate() {
+        // i-я транза уходит не раньше i / rate секунд от старта
+        let first = (i * opts.batch) as u64;
+        let due = start + Duration::from_micros(first * 1_000_000 / opts.rate);
+        tokio::time::sleep_until(due).await;
+
+        let permit = limit.clone().acquire_owned().await.unwrap();
+        let node = opts.node.clone();
+        let results = results.clone();
+        tasks.push(tokio::spawn(async move {
+            let sent = send_request(&node, &msg, tx_hashes, batched).await;
+            results.lock().unwrap().extend(sent);
+            drop(permit);
+        }));
+    }
+    for t in tasks {
+        let _ = t.await;
+    }
+    let send_elapsed = start.elapsed();
+
+    // ждём, пока принятые войдут в блоки
+    let results = std::mem::take(&mut *results.lock().unwrap());
+    let accepted: Vec<&Sent> = results.iter().filter(|s| s.rejected.is_none()).collect();
+    let drain_deadline = Instant::now() + Duration::from_millis(opts.drain_ms);
+    loop {
+        let pending = {
+            let at = inclusions.at.lock().unwrap();
+            accepted.iter().filter(|s| !at.contains_key(&s.tx_hash)).count()
+        };
+        if pending == 0 || Instant::now() >= drain_deadline {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    follower.abort();
+
+    // ---- Отчёт ----
+    let at = inclusions.at.lock().unwrap();
+    let admission: Vec<Duration> = results.iter().map(|s| s.admission).collect();
+    let inclusion: Vec<Duration> = accepted
+        .iter()
+        .filter_map(|s| at.get(&s.tx_hash).map(|t| t.duration_since(s.sent_at)))
+        .collect();
+    let last_included = accepted.iter().filter_map(|s| at.get(&s.tx_hash)).max().copied();
+
+    let mut reasons: BTreeMap<&str, usize> = BTreeMap::new();
+    for s in &results {
+        if let Some(r) = &s.rejected {
+            *reasons.entry(r.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let secs = |d: Duration| d.as_secs_f64().max(f64::EPSILON);
+
+    println!();
+    println!("sent        {} in {:.2} s ({:.1} tx/s offered)", results.len(), secs(send_elapsed), results.len() as f64 / secs(send_elapsed));
+    println!("accepted    {} ({:.1}%)", accepted.len(), 100.0 * accepted.len() as f64 / results.len().max(1) as f64);
+    println!("included    {} in {} blocks", inclusion.len(), *inclusions.blocks.lock().unwrap() - blocks_before);
+    if let Some(last) = last_included {
+        let window = last.duration_since(start);
+        println!("sustained   {:.1} tx/s included over {:.2} s", inclusion.len() as f64 / secs(window), secs(window));
+    }
+    println!("latency:");
+    print_latency("admission", admission);
+    print_latency("inclusion", inclusion);
+    if !reasons.is_empty() {
+        println!("rejects:");
+        for (reason, n) in reasons {
+            println!("  {:>6}  {}", n, reason);
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let opts = match parse_args() {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(opts).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,17 +1,19 @@
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::sync::Mutex;
+use std::sync::mpsc::{Sender, channel};
 
-use once_cell::sync::Lazy;
+use tracing::error;
 
-use crate::tx::ValidTxCore;
+use crate::events::{NodeEvent, NodeHook};
+use crate::node::Node;
+use crate::tx::{TxExtra, ValidTxCore};
 
 // ─────────── Структуры блока ───────────
 
 #[derive(Clone, Debug)]
 pub struct BlockHeader {
     pub version: String,    // "0.1"
-    pub chain_id: String,   // "gld-dev-1"
+    pub chain_id: String,   // chain_id профиля сети, "gld-dev-1"
     pub height: u64,
     pub prev_hash: String,
     pub timestamp_ms: i64,
@@ -22,6 +24,31 @@ pub struct BlockHeader {
 #[derive(Clone, Debug)]
 pub struct BlockBody {
     pub txs: Vec<ValidTxCore>,
+    /// Награды производителю; apply_block сверяет их с тем, что положено
+    pub rewards: Vec<Reward>,
+}
+
+/// Начисление GLD блоком (не транзой)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reward {
+    pub to: String,
+    /// REWARD_KIND_*
+    pub kind: u8,
+    pub amount: u64,
+}
+
+/// Новые GLD за блок (выпуск)
+pub const REWARD_KIND_ISSUANCE: u8 = 1;
+/// Доля комиссий блока: чаевые и proposer_percent от base fee
+pub const REWARD_KIND_FEES: u8 = 2;
+
+/// Имя вида награды для логов и дампов
+pub fn reward_kind_name(kind: u8) -> &'static str {
+    match kind {
+        REWARD_KIND_ISSUANCE => "issuance",
+        REWARD_KIND_FEES => "fees",
+        _ => "unknown",
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -31,30 +58,152 @@ pub struct Block {
     pub body: BlockBody,
 }
 
-// ─────────── Хранилище блоков в памяти ───────────
+/// Квитанция по транзакции из применённого блока
+#[derive(Clone, Debug)]
+pub struct Receipt {
+    pub tx_hash: String,
+    pub status: u8, // 0 = applied
+    pub fee: u64,
+}
+
+pub const RECEIPT_STATUS_APPLIED: u8 = 0;
 
-pub static BLOCKS: Lazy<Mutex<Vec<Block>>> = Lazy::new(|| Mutex::new(Vec::new()));
+// ─────────── Хранилище блоков ───────────
+
+/// Блоки в памяти + текстовый лог на диске
+#[derive(Debug, Default)]
+pub struct BlockStore {
+    pub blocks: Vec<Block>,
+    /// Кто дописывает блоки в лог. None — только в памяти.
+    log: Option<BlockLogWriter>,
+}
+
+impl BlockStore {
+    /// Пустой путь — лог на диск не пишем
+    pub fn new(log_path: &str) -> Self {
+        BlockStore {
+            blocks: Vec::new(),
+            log: (!log_path.is_empty()).then(|| BlockLogWriter::start(log_path)),
+        }
+    }
+}
+
+enum LogJob {
+    Write(Block),
+    /// Всё, что было в очереди до него, уже записано: fsync и ответ
+    Flush(Sender<Result<(), String>>),
+}
+
+/// Запись лога блоков на своём потоке: хук зовётся из цикла производства
+/// блоков, файловый I/O там держал бы и его, и лок хранилища.
+/// Очередь одна, так что блоки ложатся в файл по порядку.
+#[derive(Debug)]
+struct BlockLogWriter {
+    path: String,
+    jobs: Sender<LogJob>,
+}
+
+impl BlockLogWriter {
+    fn start(path: &str) -> Self {
+        let (jobs, queue) = channel::<LogJob>();
+        let log_path = path.to_string();
+        std::thread::Builder::new()
+            .name("forgex-block-log".into())
+            .spawn(move || {
+                // поток живёт, пока жив BlockStore (отправитель)
+                for job in queue {
+                    match job {
+                        LogJob::Write(block) => {
+                            if let Err(e) = write_block_to_file(&block, &log_path) {
+                                error!(height = block.header.height, error = %e, "failed to write block log");
+                            }
+                        }
+                        LogJob::Flush(done) => {
+                            let _ = done.send(sync_file(&log_path));
+                        }
+                    }
+                }
+            })
+            .expect("failed to start block log writer");
+        BlockLogWriter { path: path.to_string(), jobs }
+    }
+}
 
 // ─────────── Публичные функции ───────────
 
-/// Сохранить блок в память и записать его в txt-файл.
-pub fn save_block(block: Block) -> Result<(), String> {
-    {
-        // кладём в in-memory список блоков
-        let mut blocks = BLOCKS.lock().map_err(|_| "failed to lock BLOCKS".to_string())?;
-        blocks.push(block.clone());
+/// Сохранить блок в память; в txt-лог его допишет поток записи.
+pub fn save_block(store: &mut BlockStore, block: Block) -> Result<(), String> {
+    if let Some(log) = &store.log {
+        log.jobs
+            .send(LogJob::Write(block.clone()))
+            .map_err(|_| format!("block log writer for {} is gone", log.path))?;
     }
 
-    // пишем в файл
-    write_block_to_file(&block, "blocks_log.txt")?;
+    // кладём в in-memory список блоков
+    store.blocks.push(block);
 
     Ok(())
 }
 
-/// Получить последний сохранённый блок (если есть)
-pub fn last_block() -> Option<Block> {
-    let blocks = BLOCKS.lock().ok()?;
-    blocks.last().cloned()
+/// Дождаться, пока все сохранённые блоки реально лягут на диск (запись + fsync).
+pub fn flush_block_store(store: &BlockStore) -> Result<(), String> {
+    let Some(log) = &store.log else {
+        return Ok(());
+    };
+
+    let gone = || format!("block log writer for {} is gone", log.path);
+    let (done, result) = channel();
+    log.jobs.send(LogJob::Flush(done)).map_err(|_| gone())?;
+    result.recv().map_err(|_| gone())?
+}
+
+fn sync_file(path: &str) -> Result<(), String> {
+    let file = match OpenOptions::new().append(true).open(path) {
+        Ok(f) => f,
+        // блоков ещё не было — нечего сбрасывать
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("failed to open {}: {}", path, e)),
+    };
+
+    file.sync_all()
+        .map_err(|e| format!("failed to sync {}: {}", path, e))
+}
+
+/// Все сохранённые блоки начиная с height (включительно), по возрастанию
+pub fn blocks_from(store: &BlockStore, height: u64) -> Vec<Block> {
+    store
+        .blocks
+        .iter()
+        .filter(|b| b.header.height >= height)
+        .cloned()
+        .collect()
+}
+
+/// Квитанции для всех транзакций блока
+pub fn make_receipts(block: &Block) -> Vec<Receipt> {
+    block
+        .body
+        .txs
+        .iter()
+        .map(|tx| Receipt {
+            tx_hash: tx.tx_hash.clone(),
+            status: RECEIPT_STATUS_APPLIED,
+            fee: tx.fee,
+        })
+        .collect()
+}
+
+/// Хук: каждый применённый блок кладём в хранилище (диск — в фоне, см. BlockLogWriter)
+pub struct BlockStoreHook;
+
+impl NodeHook for BlockStoreHook {
+    fn on_event(&self, node: &Node, event: &NodeEvent) {
+        if let NodeEvent::BlockApplied { block } = event
+            && let Err(e) = save_block(&mut node.blocks.lock().unwrap(), block.clone())
+        {
+            error!(height = block.header.height, error = %e, "failed to save block");
+        }
+    }
 }
 
 // ─────────── Внутренняя функция записи в файл ───────────
@@ -94,10 +243,39 @@ fn write_block_to_file(block: &Block, path: &str) -> Result<(), String> {
         out.push_str(&format!("  timestamp : {}\n", tx.timestamp));
         out.push_str(&format!("  pubkey    : {}\n", tx.pubkey));
         out.push_str(&format!("  signature : {}\n", tx.signature));
+        if let TxExtra::CreateToken { decimals, initial_supply } = tx.extra {
+            out.push_str(&format!("  decimals  : {}\n", decimals));
+            out.push_str(&format!("  initial   : {}\n", initial_supply));
+        }
+        if let TxExtra::MultiSend(payouts) = &tx.extra {
+            for (to, amount) in payouts {
+                out.push_str(&format!("  payout    : {} {}\n", to, amount));
+            }
+        }
+        if let TxExtra::Multisig { threshold, pubkeys, signatures } = &tx.extra {
+            out.push_str(&format!("  multisig  : {}-of-{}\n", threshold, pubkeys.len()));
+            for (index, sig) in signatures {
+                let pubkey = pubkeys.get(*index as usize).map_or("?", String::as_str);
+                out.push_str(&format!("  signer {:<2} : {} {}\n", index, pubkey, sig));
+            }
+        }
+        if let TxExtra::Vesting(v) = &tx.extra {
+            out.push_str(&format!(
+                "  vesting   : by {} start {} cliff {} duration {}\n",
+                v.clock.name(), v.start, v.cliff, v.duration
+            ));
+        }
+        if !tx.memo.is_empty() {
+            out.push_str(&format!("  memo      : {}\n", tx.memo));
+        }
         out.push_str("------------------------------------------------------------\n");
     }
 
-    out.push_str("\n");
+    for r in &block.body.rewards {
+        out.push_str(&format!("REWARD   : {} {} {}\n", reward_kind_name(r.kind), r.to, r.amount));
+    }
+
+    out.push('\n');
 
     file.write_all(out.as_bytes())
         .map_err(|e| format!("failed to write block to {}: {}", path, e))?;
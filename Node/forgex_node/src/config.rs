@@ -0,0 +1,270 @@
+use std::net::SocketAddr;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::address::decode_address;
+use crate::envelope::{known_tx_versions, tx_version};
+use crate::network::{DEVNET, NetworkProfile, network_by_name, network_names};
+use crate::state::{TokenRegistry, treasury_addr};
+
+/// Конфиг по умолчанию ищем рядом с бинарником (в рабочей папке)
+pub const DEFAULT_CONFIG_PATH: &str = "forgex_node.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeConfig {
+    /// Профиль сети (devnet, testnet, mainnet). От него — chain_id, HRP адресов
+    /// и значения по умолчанию для портов, генезиса и параметров протокола.
+    pub network: String,
+    /// Адрес P2P (FGX1)
+    pub p2p_addr: String,
+    /// Адрес HTTP /metrics
+    pub metrics_addr: String,
+    /// Куда пушить каждый блок (индексер)
+    pub indexer_addr: String,
+    /// Пауза между попытками собрать блок
+    pub block_interval_ms: u64,
+    /// Сколько транз максимум в блоке
+    pub max_block_txs: usize,
+    /// Куда нода получает награды за свои блоки. Пустой — в казну.
+    pub proposer_address: String,
+    /// Токен для админ-команд. Пустой — админка выключена.
+    pub admin_token: String,
+    /// Адрес админки (FGX1, только ADMIN). Отдельно от P2P и только на loopback.
+    pub admin_addr: String,
+    /// Куда админка пишет дампы: путь в dump-state/dump-mempool — внутри этой папки
+    pub admin_dump_dir: String,
+    /// Текстовый лог блоков. Пустой — блоки только в памяти.
+    pub blocks_log_path: String,
+    /// Куда писать снимок state при остановке
+    pub state_snapshot_path: String,
+    /// Куда писать снимок мемпула при остановке
+    pub mempool_snapshot_path: String,
+    /// Сколько ждать сеть (индексер, пиры) при остановке
+    pub shutdown_timeout_ms: u64,
+    /// Потоки проверки подписей. 0 — по числу ядер.
+    pub verify_threads: usize,
+    /// Сколько транз может ждать проверки; сверх этого отвечаем "busy"
+    pub verify_queue: usize,
+    /// Версии формата транз, которые нода пускает в мемпул (см. envelope::TX_VERSIONS)
+    pub accepted_tx_versions: Vec<u8>,
+    pub log: LogConfig,
+    /// Параметры рынка комиссий. Входят в консенсус: у всех нод сети одинаковые.
+    pub fees: FeeConfig,
+    /// Выпуск GLD за блок. Тоже консенсус.
+    pub rewards: RewardConfig,
+    /// Начальные балансы. Пусто — всё у казны (treasury_gld профиля).
+    pub genesis: Vec<GenesisBalance>,
+}
+
+/// Строка генезиса: [[genesis]] address = "...", token = "GLD", amount = ...
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisBalance {
+    pub address: String,
+    pub token: String,
+    pub amount: u64,
+}
+
+/// [fees]: base fee за транзу подстраивается под заполненность блоков,
+/// как в EIP-1559; fee транзы сверх base fee — чаевые производителю блока
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeeConfig {
+    /// Ниже этого base fee не опускается (он же base fee на генезисе)
+    pub min_base_fee: u64,
+    /// Сколько транз в блоке считается нормой: больше — base fee растёт, меньше — падает
+    pub target_block_txs: u32,
+    /// Какая доля base fee сжигается, в процентах
+    pub burn_percent: u8,
+    /// Какая доля base fee уходит производителю блока; остаток — в казну
+    pub proposer_percent: u8,
+}
+
+/// [rewards]: производитель каждого блока получает block_reward новых GLD,
+/// каждые halving_interval блоков награда делится пополам (0 — без халвинга)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewardConfig {
+    pub block_reward: u64,
+    pub halving_interval: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// Директивы уровней, как в RUST_LOG: "info,forgex_node::p2p=debug"
+    pub filter: String,
+    /// "text" или "json"
+    pub format: String,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        NodeConfig::for_network(&DEVNET)
+    }
+}
+
+impl NodeConfig {
+    /// Конфиг по умолчанию для сети: порты и параметры — из профиля
+    pub fn for_network(profile: &NetworkProfile) -> Self {
+        NodeConfig {
+            network: profile.name.into(),
+            p2p_addr: format!("127.0.0.1:{}", profile.p2p_port),
+            metrics_addr: format!("127.0.0.1:{}", profile.metrics_port),
+            indexer_addr: format!("127.0.0.1:{}", profile.indexer_port),
+            block_interval_ms: profile.block_interval_ms,
+            max_block_txs: profile.max_block_txs,
+            proposer_address: String::new(),
+            admin_token: String::new(),
+            admin_addr: format!("127.0.0.1:{}", profile.admin_port),
+            admin_dump_dir: "dumps".into(),
+            blocks_log_path: "blocks_log.txt".into(),
+            state_snapshot_path: "state_snapshot.txt".into(),
+            mempool_snapshot_path: "mempool_snapshot.txt".into(),
+            shutdown_timeout_ms: 5000,
+            verify_threads: 0,
+            verify_queue: 1024,
+            accepted_tx_versions: known_tx_versions(),
+            log: LogConfig::default(),
+            fees: profile.fees.clone(),
+            rewards: profile.rewards.clone(),
+            genesis: Vec::new(),
+        }
+    }
+
+    /// Профиль сети (имя уже проверил load_config)
+    pub fn profile(&self) -> &'static NetworkProfile {
+        network_by_name(&self.network).unwrap_or(&DEVNET)
+    }
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        DEVNET.fees.clone()
+    }
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        // 1 GLD за блок, половина — раз в миллион блоков
+        DEVNET.rewards.clone()
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            filter: "info".into(),
+            format: "text".into(),
+        }
+    }
+}
+
+impl NodeConfig {
+    /// Адрес для наград за блоки (proposer_address или казна)
+    pub fn proposer(&self) -> String {
+        if self.proposer_address.is_empty() {
+            treasury_addr(self.profile())
+        } else {
+            self.proposer_address.clone()
+        }
+    }
+}
+
+/// Путь к конфигу: FORGEX_NODE_CONFIG или forgex_node.toml
+pub fn config_path() -> String {
+    std::env::var("FORGEX_NODE_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.into())
+}
+
+/// Прочитать конфиг. Нет файла — берём значения по умолчанию.
+pub fn load_config(path: &str) -> Result<NodeConfig, String> {
+    if !Path::new(path).exists() {
+        return Ok(NodeConfig::default());
+    }
+
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path, e))?;
+
+    let table: toml::Table =
+        toml::from_str(&text).map_err(|e| format!("failed to parse {}: {}", path, e))?;
+
+    // сначала сеть: чего нет в файле, берём из её профиля
+    let name = match table.get("network") {
+        None => DEVNET.name,
+        Some(v) => v.as_str().ok_or_else(|| format!("{}: network must be a string", path))?,
+    };
+    let profile = network_by_name(name)
+        .ok_or_else(|| format!("{}: unknown network {} (known: {})", path, name, network_names()))?;
+
+    let mut merged = toml::Table::try_from(NodeConfig::for_network(profile))
+        .map_err(|e| format!("failed to encode defaults for {}: {}", profile.name, e))?;
+    merge_toml(&mut merged, table);
+    let cfg: NodeConfig = merged
+        .try_into()
+        .map_err(|e| format!("failed to parse {}: {}", path, e))?;
+
+    // генезис проверяем здесь, чтобы нода не стартовала с молча пропавшими балансами;
+    // адреса — той же сети, токены — только встроенные (GLD)
+    let tokens = TokenRegistry::default();
+    for g in &cfg.genesis {
+        decode_address(&g.address, profile)
+            .map_err(|e| format!("{}: genesis address {}: {}", path, g.address, e))?;
+        if tokens.id_of(&g.token).is_none() {
+            return Err(format!("{}: genesis token {} is unknown", path, g.token));
+        }
+    }
+
+    let fees = &cfg.fees;
+    if fees.min_base_fee == 0 {
+        return Err(format!("{}: fees.min_base_fee must be > 0", path));
+    }
+    if fees.target_block_txs == 0 {
+        return Err(format!("{}: fees.target_block_txs must be > 0", path));
+    }
+    if fees.burn_percent as u16 + fees.proposer_percent as u16 > 100 {
+        return Err(format!("{}: fees.burn_percent + fees.proposer_percent must be 0..=100", path));
+    }
+    if !cfg.proposer_address.is_empty() {
+        decode_address(&cfg.proposer_address, profile)
+            .map_err(|e| format!("{}: proposer_address {}: {}", path, cfg.proposer_address, e))?;
+    }
+    // админка снаружи недоступна: токен — единственная защита, по сети его не шлём
+    let admin_addr: SocketAddr = cfg
+        .admin_addr
+        .parse()
+        .map_err(|_| format!("{}: admin_addr {} is not ip:port", path, cfg.admin_addr))?;
+    if !admin_addr.ip().is_loopback() {
+        return Err(format!("{}: admin_addr {} must be a loopback address", path, cfg.admin_addr));
+    }
+    if cfg.admin_addr == cfg.p2p_addr {
+        return Err(format!("{}: admin_addr must differ from p2p_addr", path));
+    }
+    if cfg.admin_dump_dir.is_empty() {
+        return Err(format!("{}: admin_dump_dir must not be empty", path));
+    }
+    // с пустой очередью проверки любая транза получала бы busy навсегда
+    if cfg.verify_queue == 0 {
+        return Err(format!("{}: verify_queue must be > 0", path));
+    }
+    if cfg.accepted_tx_versions.is_empty() {
+        return Err(format!("{}: accepted_tx_versions must not be empty", path));
+    }
+    if let Some(v) = cfg.accepted_tx_versions.iter().find(|v| tx_version(**v).is_none()) {
+        return Err(format!("{}: accepted_tx_versions: unknown tx version {}", path, v));
+    }
+
+    Ok(cfg)
+}
+
+/// Наложить значения из файла на значения по умолчанию; вложенные таблицы — по ключам
+fn merge_toml(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(o)) => merge_toml(b, o),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::Notify;
+
+/// Ручки управления циклом ноды (их дёргает админка)
+#[derive(Default)]
+pub struct NodeControl {
+    paused: AtomicBool,
+    force_block: Notify,
+}
+
+impl NodeControl {
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Попросить цикл ноды собрать блок прямо сейчас (даже на паузе)
+    pub fn force_block(&self) {
+        self.force_block.notify_one();
+    }
+
+    /// Ждать, пока кто-то не вызовет force_block
+    pub async fn force_block_requested(&self) {
+        self.force_block.notified().await;
+    }
+}
@@ -0,0 +1,198 @@
+//! Версии формата транз и конверт для них.
+//!
+//! Конверт: TX_ENVELOPE_MARKER (u8) | version (u8) | тело. Тело — raw tx
+//! своей версии; tx_hash считается по телу, так что транза в конверте и без
+//! него — одна и та же транза.
+//!
+//! Старые кошельки шлют raw tx без конверта (он начинается с ASCII domain_tag),
+//! версию тогда берём по полю encoding. Каждая версия разбирается и проверяется
+//! своими правилами из TX_VERSIONS, а какие версии нода пускает в мемпул,
+//! решает accepted_tx_versions в конфиге.
+//!
+//! Компактная версия (TX_VERSION_COMPACT) — pipe_v2 без pubkey: нода
+//! восстанавливает его из подписи r|s|v и по нему проверяет from. Бывает
+//! только в конверте; tx_hash у неё считается по полной форме.
+
+use crate::model::{RAW_TX_BASE_LEN, Tx, compact_raw_tx_len, parse_compact_raw_tx, parse_raw_tx, raw_tx_len};
+use crate::tx::{MAX_MEMO_LEN, PIPE_V1, PIPE_V2, build_compact_raw_tx, build_raw_tx_from_struct};
+
+/// Первый байт конверта: в UTF-8 его не бывает, с domain_tag не спутать
+pub const TX_ENVELOPE_MARKER: u8 = 0xFE;
+
+pub const TX_VERSION_PIPE_V1: u8 = 1;
+pub const TX_VERSION_PIPE_V2: u8 = 2;
+pub const TX_VERSION_COMPACT: u8 = 3;
+
+/// Версия формата: как разобрать тело и что проверить сверх общих правил
+pub struct TxVersion {
+    pub version: u8,
+    /// Поле encoding в raw tx этой версии
+    pub encoding: &'static str,
+    /// Есть ли в теле pubkey (нет — восстанавливаем из подписи)
+    pub has_pubkey: bool,
+    pub len: fn(&[u8]) -> Result<usize, String>,
+    pub parse: fn(&[u8]) -> Result<Tx, String>,
+    pub build: fn(&Tx) -> Result<Vec<u8>, String>,
+    pub validate: fn(&Tx) -> Result<(), String>,
+}
+
+/// Реестр версий. Новый формат — новая строка здесь.
+pub const TX_VERSIONS: &[TxVersion] = &[
+    TxVersion {
+        version: TX_VERSION_PIPE_V1,
+        encoding: PIPE_V1,
+        has_pubkey: true,
+        len: raw_tx_len,
+        parse: parse_raw_tx,
+        build: build_raw_tx_from_struct,
+        validate: validate_pipe_v1,
+    },
+    TxVersion {
+        version: TX_VERSION_PIPE_V2,
+        encoding: PIPE_V2,
+        has_pubkey: true,
+        len: raw_tx_len,
+        parse: parse_raw_tx,
+        build: build_raw_tx_from_struct,
+        validate: validate_pipe_v2,
+    },
+    TxVersion {
+        version: TX_VERSION_COMPACT,
+        encoding: PIPE_V2,
+        has_pubkey: false,
+        len: compact_raw_tx_len,
+        parse: parse_compact_raw_tx,
+        build: build_compact_raw_tx,
+        validate: validate_pipe_v2,
+    },
+];
+
+/// Все версии из реестра (по умолчанию нода принимает их все)
+pub fn known_tx_versions() -> Vec<u8> {
+    TX_VERSIONS.iter().map(|v| v.version).collect()
+}
+
+pub fn tx_version(version: u8) -> Option<&'static TxVersion> {
+    TX_VERSIONS.iter().find(|v| v.version == version)
+}
+
+/// Версия уже разобранной транзы — по её encoding и тому, есть ли у неё pubkey
+pub fn tx_version_of(tx: &Tx) -> Result<&'static TxVersion, String> {
+    let has_pubkey = !tx.pubkey.is_empty();
+    TX_VERSIONS
+        .iter()
+        .find(|v| v.encoding == tx.encoding && v.has_pubkey == has_pubkey)
+        .ok_or_else(|| match has_pubkey {
+            true => format!("unsupported encoding {}", tx.encoding),
+            false => format!("encoding {} requires pubkey", tx.encoding),
+        })
+}
+
+/// Правила версии транзы (domain_tag, encoding, memo)
+pub fn validate_tx_version(tx: &Tx) -> Result<(), String> {
+    (tx_version_of(tx)?.validate)(tx)
+}
+
+/// Пускает ли нода транзу этой версии (accepted_tx_versions из конфига)
+pub fn check_tx_version_accepted(tx: &Tx, accepted: &[u8]) -> Result<(), String> {
+    let version = tx_version_of(tx)?.version;
+    if !accepted.contains(&version) {
+        return Err(format!("tx version {} is not accepted", version));
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------
+// КОНВЕРТ
+// -------------------------------------------------------
+
+/// Версия и тело транзы, которая начинается в data (в конверте или без)
+fn open_envelope(data: &[u8]) -> Result<(&'static TxVersion, &[u8]), String> {
+    if data.first() == Some(&TX_ENVELOPE_MARKER) {
+        let version = *data.get(1).ok_or_else(|| "not enough bytes for tx version".to_string())?;
+        let v = tx_version(version).ok_or_else(|| format!("unknown tx version {}", version))?;
+        return Ok((v, &data[2..]));
+    }
+
+    // без конверта: версия по полю encoding (последние 7 байт базы)
+    if data.len() < RAW_TX_BASE_LEN {
+        return Err(format!("raw_tx must be at least {} bytes, got {}", RAW_TX_BASE_LEN, data.len()));
+    }
+    let encoding = &data[RAW_TX_BASE_LEN - 7..RAW_TX_BASE_LEN];
+    let v = TX_VERSIONS
+        .iter()
+        .find(|v| v.has_pubkey && v.encoding.as_bytes() == encoding)
+        .ok_or_else(|| format!("unsupported encoding {}", String::from_utf8_lossy(encoding)))?;
+    Ok((v, data))
+}
+
+/// Длина транзы (вместе с конвертом), которая начинается в data — для пачек
+pub fn enveloped_tx_len(data: &[u8]) -> Result<usize, String> {
+    let (v, body) = open_envelope(data)?;
+    Ok(data.len() - body.len() + (v.len)(body)?)
+}
+
+/// Разобрать транзу в конверте или без; версия из конверта должна совпасть с телом
+pub fn decode_tx(data: &[u8]) -> Result<Tx, String> {
+    let (v, body) = open_envelope(data)?;
+    let tx = (v.parse)(body)?;
+    if tx.encoding != v.encoding {
+        return Err(format!("tx version {} requires encoding {}, got {}", v.version, v.encoding, tx.encoding));
+    }
+    Ok(tx)
+}
+
+/// Транза в конверте: marker | version | raw tx
+pub fn encode_tx(tx: &Tx) -> Result<Vec<u8>, String> {
+    let v = tx_version_of(tx)?;
+    let body = (v.build)(tx)?;
+    let mut buf = Vec::with_capacity(2 + body.len());
+    buf.push(TX_ENVELOPE_MARKER);
+    buf.push(v.version);
+    buf.extend_from_slice(&body);
+    Ok(buf)
+}
+
+/// Та же транза для отправки в компактном виде: без pubkey, его восстановит нода.
+/// tx_hash не меняется — он посчитан по полной форме.
+pub fn compact_tx(tx: Tx) -> Result<Tx, String> {
+    if tx.encoding != PIPE_V2 {
+        return Err(format!("compact tx requires encoding {}, got {}", PIPE_V2, tx.encoding));
+    }
+    Ok(Tx { pubkey: String::new(), ..tx })
+}
+
+// -------------------------------------------------------
+// ПРАВИЛА ВЕРСИЙ
+// -------------------------------------------------------
+
+fn validate_domain_tag(tx: &Tx) -> Result<(), String> {
+    if tx.domain_tag.is_empty() {
+        return Err("domain_tag is empty".into());
+    }
+    if tx.domain_tag != "GLD_TX_v1" {
+        return Err("domain_tag must be GLD_TX_v1".into());
+    }
+    Ok(())
+}
+
+/// pipe_v1: исходный формат, без memo
+fn validate_pipe_v1(tx: &Tx) -> Result<(), String> {
+    validate_domain_tag(tx)?;
+    if !tx.memo.is_empty() {
+        return Err("memo requires pipe_v2 encoding".into());
+    }
+    Ok(())
+}
+
+/// pipe_v2: memo до MAX_MEMO_LEN байт без управляющих символов
+fn validate_pipe_v2(tx: &Tx) -> Result<(), String> {
+    validate_domain_tag(tx)?;
+    if tx.memo.len() > MAX_MEMO_LEN {
+        return Err(format!("memo must be at most {} bytes", MAX_MEMO_LEN));
+    }
+    if tx.memo.chars().any(char::is_control) {
+        return Err("memo must not contain control characters".into());
+    }
+    Ok(())
+}
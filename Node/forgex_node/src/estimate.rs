@@ -0,0 +1,89 @@
+//! Оценка комиссии: сколько ставить fee, чтобы транза попала в блок
+//! в пределах target_blocks.
+//!
+//! Комиссия у нас за транзу, а не за байт, так что оценка одна на любой
+//! обычный transfer. Смотрим на три вещи:
+//!   - fee в последних FEE_HISTORY_BLOCKS блоках (перцентили 25/50/90);
+//!   - мемпул: если впереди больше транз, чем влезет в target_blocks блоков,
+//!     надо перебить последнюю, что влезает;
+//!   - base fee: он может расти на 1/8 за полный блок, low берёт текущий,
+//!     medium — через блок, high — через target_blocks блоков.
+
+use crate::node::Node;
+use crate::state::next_base_fee;
+
+/// Сколько последних блоков смотрим
+pub const FEE_HISTORY_BLOCKS: usize = 20;
+
+/// Дальше этого горизонта оценку не даём
+pub const MAX_TARGET_BLOCKS: u16 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub target_blocks: u16,
+    /// base fee следующего блока
+    pub base_fee: u64,
+    pub low: u64,
+    pub medium: u64,
+    pub high: u64,
+}
+
+/// Оценка fee для попадания в ближайшие target_blocks блоков (1..=MAX_TARGET_BLOCKS)
+pub fn estimate_fee(node: &Node, target_blocks: u16) -> Result<FeeEstimate, String> {
+    if target_blocks == 0 || target_blocks > MAX_TARGET_BLOCKS {
+        return Err(format!("target_blocks must be 1..={}", MAX_TARGET_BLOCKS));
+    }
+    let max_block_txs = node.config().max_block_txs;
+
+    let (base_fee, fees) = {
+        let state = node.state.lock().unwrap();
+        (state.base_fee, state.fees.clone())
+    };
+
+    let mut recent: Vec<u64> = {
+        let store = node.blocks.lock().unwrap();
+        let from = store.blocks.len().saturating_sub(FEE_HISTORY_BLOCKS);
+        store.blocks[from..].iter().flat_map(|b| b.body.txs.iter().map(|tx| tx.fee)).collect()
+    };
+    recent.sort_unstable();
+
+    // транзы ниже base fee в блок сейчас не идут и нам не конкуренты
+    let mut pending: Vec<u64> = node
+        .mempool
+        .lock()
+        .unwrap()
+        .values()
+        .map(|tx| tx.fee)
+        .filter(|fee| *fee >= base_fee)
+        .collect();
+    pending.sort_unstable_by(|a, b| b.cmp(a));
+
+    // в target_blocks блоков влезет capacity транз из мемпула, лишние ждут
+    let capacity = max_block_txs.saturating_mul(target_blocks as usize);
+    let cutoff = match capacity {
+        0 => 0,
+        n => pending.get(n - 1).map_or(0, |fee| fee.saturating_add(1)),
+    };
+
+    // base fee, если все блоки до нашего будут полными
+    let next_block = next_base_fee(base_fee, max_block_txs, &fees);
+    let worst = (1..target_blocks).fold(next_block, |fee, _| next_base_fee(fee, max_block_txs, &fees));
+
+    let floor = base_fee.max(cutoff);
+    Ok(FeeEstimate {
+        target_blocks,
+        base_fee,
+        low: floor.max(percentile(&recent, 25)),
+        medium: floor.max(next_block).max(percentile(&recent, 50)),
+        high: floor.max(worst).max(percentile(&recent, 90)),
+    })
+}
+
+/// Перцентиль p по возрастающему списку (nearest-rank); пустой — 0
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
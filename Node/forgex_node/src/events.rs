@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use tracing::{debug, info};
+
+use crate::block::Block;
+use crate::node::Node;
+use crate::state::StateDiff;
+use crate::tx::ValidTxCore;
+
+// ─────────────────────── СОБЫТИЯ НОДЫ ───────────────────────
+
+#[derive(Debug, Clone)]
+pub enum NodeEvent {
+    /// Транза прошла проверку и легла в мемпул
+    TxAdmitted { tx: ValidTxCore },
+    /// Транза отклонена при приёме (tx_hash может быть пустым, если не дошли до него)
+    TxRejected { tx_hash: String, reason: String },
+    /// Транза выкинута из мемпула, так и не попав в блок
+    TxEvicted { tx_hash: String, reason: String },
+    /// Нода собрала блок и он применился к state (свои блоки; чужие — только BlockApplied)
+    BlockProduced { block: Block },
+    /// Блок применён к state
+    BlockApplied { block: Block },
+    /// Что поменялось в state после блока
+    StateChanged { height: u64, diff: StateDiff },
+}
+
+/// Расширение ноды. Хук вызывается синхронно прямо из цикла ноды,
+/// поэтому долгую работу (сеть, диск) хук должен уносить в свою таску.
+/// Хук получает ноду, от которой пришло событие.
+pub trait NodeHook: Send + Sync {
+    fn on_event(&self, node: &Node, event: &NodeEvent);
+}
+
+// ─────────────────────── ШИНА ───────────────────────
+
+/// Зарегистрировать хук на ноде. Хуки вызываются в порядке регистрации.
+pub fn register_hook(node: &Node, hook: Arc<dyn NodeHook>) {
+    node.hooks.write().unwrap().push(hook);
+}
+
+/// Разослать событие всем хукам ноды
+pub fn emit(node: &Node, event: NodeEvent) {
+    let hooks = node.hooks.read().unwrap().clone();
+    for hook in hooks {
+        hook.on_event(node, &event);
+    }
+}
+
+// ─────────────────────── ВСТРОЕННЫЙ ЛОГ ───────────────────────
+
+/// Хук: пишет каждое событие в лог со структурными полями
+pub struct LogHook;
+
+impl NodeHook for LogHook {
+    fn on_event(&self, _node: &Node, event: &NodeEvent) {
+        match event {
+            NodeEvent::TxAdmitted { tx } => {
+                info!(tx_hash = %tx.tx_hash, from = %tx.from, nonce = tx.nonce, fee = tx.fee, "tx admitted");
+            }
+            NodeEvent::TxRejected { tx_hash, reason } => {
+                info!(tx_hash = %tx_hash, reason = %reason, "tx rejected");
+            }
+            NodeEvent::TxEvicted { tx_hash, reason } => {
+                info!(tx_hash = %tx_hash, reason = %reason, "tx evicted");
+            }
+            NodeEvent::BlockProduced { block } => {
+                debug!(height = block.header.height, txs = block.body.txs.len(), "block produced");
+            }
+            NodeEvent::BlockApplied { block } => {
+                info!(height = block.header.height, block_id = %block.block_id, txs = block.body.txs.len(), "block applied");
+            }
+            NodeEvent::StateChanged { height, diff } => {
+                debug!(
+                    height = *height,
+                    balances = diff.balances.len(),
+                    nonces = diff.nonces.len(),
+                    tokens = diff.tokens.len(),
+                    locked = diff.locked.len(),
+                    bonds = diff.bonds.len(),
+                    base_fee = diff.base_fee,
+                    "state changed"
+                );
+                if let Some(set) = &diff.validator_set {
+                    info!(epoch = set.epoch, validators = set.validators.len(), "validator set updated");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use tracing::{debug, info_span, warn};
+
+use crate::address::decode_address;
+use crate::envelope::check_tx_version_accepted;
+use crate::estimate::estimate_fee;
+use crate::events::{NodeEvent, emit};
+use crate::mempool::{MempoolAdd, mempool_add_tx};
+use crate::model::{Decoded, Tx, decode_message, make_tx_response, make_balance_response, make_nonce_response, make_tx_reject_response, make_height_response, make_tx_batch_response, make_token_response, make_validators_response, make_fees_response, make_fee_estimate_response, make_admin_response};
+use crate::node::Node;
+use crate::p2p::HandlerFuture;
+use crate::state::{ChainState, balance_at, nonce, block_info, supply_of};
+use crate::tx::{ValidTxCore, validate_tx};
+use crate::verify::{BUSY_REASON, Busy};
+
+/// Обработчик запросов P2P: сырые байты FGX1 -> ответ.
+/// Транзы проверяются прямо здесь, на вызывающем потоке (так работает
+/// симулятор). P2P-сервер ходит через handle_message_async.
+pub fn handle_message(node: &Node, msg: Vec<u8>) -> Vec<u8> {
+    match decode_message(&msg) {
+        // ─────────────── ТРАНЗАКЦИЯ ───────────────
+        Ok(Decoded::Tx(mut tx)) => {
+            if node.shutdown.is_requested() {
+                return make_tx_reject_response("node is shutting down");
+            }
+            let resolved = check_tx_version_accepted(&tx, &node.config().accepted_tx_versions)
+                .and_then(|_| prepare_tx(&node.state.lock().unwrap(), &mut tx));
+            let checked = resolved.and_then(|_| validate_tx(&tx, node.clock.now_ms(), node.network));
+            match admit_checked(node, &tx.tx_hash, checked) {
+                None => make_tx_response(),
+                Some(reason) => make_tx_reject_response(&reason),
+            }
+        }
+
+        // ─────────────── ПАЧКА ТРАНЗАКЦИЙ ───────────────
+        Ok(Decoded::TxBatch(mut txs)) => {
+            if node.shutdown.is_requested() {
+                return make_tx_batch_response(&vec![Some("node is shutting down".into()); txs.len()]);
+            }
+            let now_ms = node.clock.now_ms();
+            let resolved = prepare_txs(node, &mut txs);
+            let results: Vec<_> = txs
+                .iter()
+                .zip(resolved)
+                .map(|(tx, r)| admit_checked(node, &tx.tx_hash, r.and_then(|_| validate_tx(tx, now_ms, node.network))))
+                .collect();
+            make_tx_batch_response(&results)
+        }
+
+        Ok(other) => handle_query(node, other),
+
+        // ─────────────── ОШИБКА ДЕКОДА ───────────────
+        Err(e) => {
+            warn!(error = %e, "failed to decode message");
+            b"ERR".to_vec()
+        }
+    }
+}
+
+/// То же, что handle_message, но проверка транз (подписи, bech32) уходит
+/// на пул верификации ноды, а воркер Tokio в это время свободен.
+/// Если очередь пула заполнена — отвечаем "busy", транзу не смотрим.
+pub async fn handle_message_async(node: &Node, msg: Vec<u8>) -> Vec<u8> {
+    match decode_message(&msg) {
+        Ok(Decoded::Tx(mut tx)) => {
+            if node.shutdown.is_requested() {
+                return make_tx_reject_response("node is shutting down");
+            }
+            let tx_hash = tx.tx_hash.clone();
+            let resolved = check_tx_version_accepted(&tx, &node.config().accepted_tx_versions)
+                .and_then(|_| prepare_tx(&node.state.lock().unwrap(), &mut tx));
+            if let Err(e) = resolved {
+                admit_checked(node, &tx_hash, Err(e.clone()));
+                return make_tx_reject_response(&e);
+            }
+            match node.verifier.verify(*tx, node.clock.now_ms(), node.network).await {
+                Ok(checked) => match admit_checked(node, &tx_hash, checked) {
+                    None => make_tx_response(),
+                    Some(reason) => make_tx_reject_response(&reason),
+                },
+                Err(Busy) => {
+                    debug!(tx_hash = %tx_hash, "verify queue full");
+                    node.metrics.tx_busy(1);
+                    make_tx_reject_response(BUSY_REASON)
+                }
+            }
+        }
+
+        Ok(Decoded::TxBatch(mut txs)) => {
+            if node.shutdown.is_requested() {
+                return make_tx_batch_response(&vec![Some("node is shutting down".into()); txs.len()]);
+            }
+            let hashes: Vec<String> = txs.iter().map(|tx| tx.tx_hash.clone()).collect();
+
+            // на пул идут только транзы с известным токеном
+            let resolved = prepare_txs(node, &mut txs);
+            let ready: Vec<Tx> = txs
+                .into_iter()
+                .zip(&resolved)
+                .filter(|(_, r)| r.is_ok())
+                .map(|(tx, _)| tx)
+                .collect();
+
+            match node.verifier.verify_batch(ready, node.clock.now_ms(), node.network).await {
+                Ok(checked) => {
+                    let mut checked = checked.into_iter();
+                    let results: Vec<_> = hashes
+                        .iter()
+                        .zip(resolved)
+                        .map(|(hash, r)| {
+                            let c = match r {
+                                Ok(()) => checked.next().unwrap_or_else(|| Err("internal verification error".into())),
+                                Err(e) => Err(e),
+                            };
+                            admit_checked(node, hash, c)
+                        })
+                        .collect();
+                    make_tx_batch_response(&results)
+                }
+                Err(Busy) => {
+                    debug!(txs = hashes.len(), "verify queue full for batch");
+                    node.metrics.tx_busy(hashes.len() as u64);
+                    make_tx_batch_response(&vec![Some(BUSY_REASON.into()); hashes.len()])
+                }
+            }
+        }
+
+        Ok(other) => handle_query(node, other),
+
+        Err(e) => {
+            warn!(error = %e, "failed to decode message");
+            b"ERR".to_vec()
+        }
+    }
+}
+
+/// Обработчик для run_p2p_server
+pub fn p2p_handler(node: &Node, msg: Vec<u8>) -> HandlerFuture<'_> {
+    Box::pin(handle_message_async(node, msg))
+}
+
+/// prepare_tx для каждой транзы пачки; state лочим один раз на всю пачку.
+/// Версии, которых нет в accepted_tx_versions, отсекаем сразу.
+fn prepare_txs(node: &Node, txs: &mut [Tx]) -> Vec<Result<(), String>> {
+    let accepted = node.config().accepted_tx_versions;
+    let state = node.state.lock().unwrap();
+    txs.iter_mut()
+        .map(|tx| check_tx_version_accepted(tx, &accepted).and_then(|_| prepare_tx(&state, tx)))
+        .collect()
+}
+
+/// Символ токена по коду из реестра: он входит в подпись, а в raw tx только код
+fn resolve_token(state: &ChainState, tx: &mut Tx) -> Result<(), String> {
+    if matches!(
+        tx.tx_type.as_str(),
+        "transfer" | "mint" | "burn" | "multi_send" | "multisig_transfer" | "vesting_transfer" | "stake" | "unstake" | "delegate"
+    ) {
+        let token = state
+            .tokens
+            .get(tx.token_id)
+            .ok_or_else(|| format!("unknown token code {}", tx.token_id))?;
+        tx.token = token.symbol.clone();
+    }
+    // у create_token символ уже в хвосте транзы, остальное отсеет validate_tx
+    Ok(())
+}
+
+/// Отсекаем то, что apply_block всё равно не примет при текущем state:
+/// fee ниже base fee, чужой mint, mint сверх max_supply, занятый символ
+fn check_against_state(state: &ChainState, tx: &Tx) -> Result<(), String> {
+    if tx.fee < state.base_fee {
+        return Err(format!("fee {} is below base fee {}", tx.fee, state.base_fee));
+    }
+
+    match tx.tx_type.as_str() {
+        "mint" => {
+            let token = state
+                .tokens
+                .get(tx.token_id)
+                .ok_or_else(|| format!("unknown token code {}", tx.token_id))?;
+            if decode_address(&tx.from, state.network).ok() != Some(token.issuer) {
+                return Err(format!("only issuer can mint {}", token.symbol));
+            }
+            if supply_of(state, tx.token_id).checked_add(tx.amount).is_none_or(|s| s > token.max_supply) {
+                return Err(format!("mint exceeds max supply of {}", token.symbol));
+            }
+            Ok(())
+        }
+        "create_token" if state.tokens.id_of(&tx.token).is_some() => Err(format!("token {} already exists", tx.token)),
+        _ => Ok(()),
+    }
+}
+
+/// Всё, что до подписи проверяется по state: символ токена и check_against_state
+fn prepare_tx(state: &ChainState, tx: &mut Tx) -> Result<(), String> {
+    resolve_token(state, tx)?;
+    check_against_state(state, tx)
+}
+
+/// Проверенную транзу кладём в мемпул.
+/// None — принята, Some — причина отказа (события уже разосланы).
+fn admit_checked(node: &Node, tx_hash: &str, checked: Result<ValidTxCore, String>) -> Option<String> {
+    let _span = info_span!("tx", tx_hash = %tx_hash).entered();
+
+    let valid_tx = match checked {
+        Ok(valid_tx) => valid_tx,
+        Err(e) => {
+            emit(node, NodeEvent::TxRejected {
+                tx_hash: tx_hash.to_string(),
+                reason: e.clone(),
+            });
+            return Some(e);
+        }
+    };
+
+    debug!(from = %valid_tx.from, nonce = valid_tx.nonce, "tx valid");
+
+    // события шлём уже после отпускания лока мемпула
+    let added = {
+        let mut mp = node.mempool.lock().unwrap();
+        let added = mempool_add_tx(&mut mp, valid_tx.clone());
+        debug!(mempool_size = mp.len(), "mempool updated");
+        added
+    };
+
+    match added {
+        MempoolAdd::Added => {
+            emit(node, NodeEvent::TxAdmitted { tx: valid_tx });
+            None
+        }
+        MempoolAdd::Replaced { evicted } => {
+            emit(node, NodeEvent::TxEvicted {
+                tx_hash: evicted,
+                reason: "replaced by higher fee".into(),
+            });
+            emit(node, NodeEvent::TxAdmitted { tx: valid_tx });
+            None
+        }
+        MempoolAdd::Duplicate => None,
+        MempoolAdd::Underpriced => {
+            let reason = "replacement fee too low".to_string();
+            emit(node, NodeEvent::TxRejected {
+                tx_hash: valid_tx.tx_hash,
+                reason: reason.clone(),
+            });
+            Some(reason)
+        }
+    }
+}
+
+/// Запросы без транз: балансы, токены, комиссии, nonce, высота, админка
+fn handle_query(node: &Node, decoded: Decoded) -> Vec<u8> {
+    match decoded {
+        Decoded::Tx(_) | Decoded::TxBatch(_) => {
+            warn!("tx reached query handler");
+            b"ERR".to_vec()
+        }
+
+        // ─────────────── ЗАПРОС БАЛАНСА ───────────────
+        Decoded::AskBalance(addr, token) => {
+            debug!(address = %addr, token = %token, "ask balance");
+
+            // Берём баланс из in-memory стейта: свободный и залоченный отдельно,
+            // вестинг — как он откроется в следующем блоке
+            let state = node.state.lock().unwrap();
+            let next_height = block_info(&state).map_or(1, |(h, _)| h + 1);
+            let (free, locked) = balance_at(&state, &addr, &token, next_height, node.clock.now_ms());
+            make_balance_response(free, &addr, locked)
+        }
+
+        // ─────────────── ЗАПРОС ТОКЕНА ───────────────
+        Decoded::AskToken(symbol) => {
+            debug!(token = %symbol, "ask token");
+
+            let state = node.state.lock().unwrap();
+            let token = state
+                .tokens
+                .id_of(&symbol)
+                .and_then(|id| state.tokens.get(id).map(|t| (id, t, supply_of(&state, id))));
+            make_token_response(token, state.network)
+        }
+
+        // ─────────────── ЗАПРОС НАБОРА ВАЛИДАТОРОВ ───────────────
+        Decoded::AskValidators => {
+            debug!("ask validators");
+
+            make_validators_response(&node.state.lock().unwrap().validator_set, node.network)
+        }
+
+        // ─────────────── ЗАПРОС BASE FEE ───────────────
+        Decoded::AskFees => {
+            debug!("ask fees");
+
+            let state = node.state.lock().unwrap();
+            let next_height = block_info(&state).map_or(1, |(h, _)| h + 1);
+            make_fees_response(next_height, state.base_fee, &state.fees)
+        }
+
+        // ─────────────── ОЦЕНКА КОМИССИИ ───────────────
+        Decoded::AskFeeEstimate(target_blocks) => {
+            debug!(target_blocks, "ask fee estimate");
+
+            make_fee_estimate_response(&estimate_fee(node, target_blocks))
+        }
+
+        // ─────────────── ЗАПРОС NONCE ───────────────
+        Decoded::AskNonce(addr) => {
+            debug!(address = %addr, "ask nonce");
+
+            let n = nonce(&node.state.lock().unwrap(), &addr);
+            make_nonce_response(n, &addr)
+        }
+
+        // ─────────────── ЗАПРОС ВЫСОТЫ ───────────────
+        Decoded::AskHeight => {
+            let height = block_info(&node.state.lock().unwrap()).map(|(h, _)| h).unwrap_or(0);
+            make_height_response(height)
+        }
+
+        // ─────────────── ПОДПИСКА ───────────────
+        // обрабатывается в p2p (соединение становится потоком блоков)
+        Decoded::Subscribe { .. } => {
+            warn!("subscribe reached request/response handler");
+            b"ERR".to_vec()
+        }
+
+        // ─────────────── АДМИНКА ───────────────
+        // только на своём слушателе (admin_addr), по P2P не отвечаем даже на верный токен
+        Decoded::Admin { cmd, .. } => {
+            warn!(cmd, "admin request on P2P port");
+            make_admin_response(false, "admin is served on admin_addr only")
+        }
+    }
+}
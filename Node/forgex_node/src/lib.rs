@@ -0,0 +1,25 @@
+pub mod address;
+pub mod admin;
+pub mod block;
+pub mod clock;
+pub mod config;
+pub mod control;
+pub mod envelope;
+pub mod estimate;
+pub mod events;
+pub mod handler;
+pub mod logging;
+pub mod mempool;
+pub mod multisig;
+pub mod metrics;
+pub mod model;
+pub mod network;
+pub mod node;
+pub mod p2p;
+pub mod shutdown;
+pub mod sim;
+pub mod state;
+pub mod subscribe;
+pub mod tx;
+pub mod verify;
+pub mod wallet;
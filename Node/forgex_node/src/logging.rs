@@ -0,0 +1,62 @@
+use once_cell::sync::OnceCell;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry, fmt, reload};
+
+use crate::config::LogConfig;
+
+/// Ручка для смены уровней на лету (reload конфига)
+static FILTER_HANDLE: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+/// Уровни из окружения, если заданы (они важнее конфига)
+fn env_filter_override() -> Option<String> {
+    std::env::var("FORGEX_LOG")
+        .or_else(|_| std::env::var("RUST_LOG"))
+        .ok()
+}
+
+/// Поднять tracing-подписчик.
+///
+/// Уровни: FORGEX_LOG, иначе RUST_LOG, иначе `log.filter` из конфига.
+/// Формат: FORGEX_LOG_FORMAT, иначе `log.format` ("text" | "json").
+pub fn init_logging(cfg: &LogConfig) {
+    let filter = env_filter_override().unwrap_or_else(|| cfg.filter.clone());
+
+    let filter = EnvFilter::try_new(&filter).unwrap_or_else(|e| {
+        eprintln!("invalid log filter {:?}: {}, falling back to info", filter, e);
+        EnvFilter::new("info")
+    });
+
+    let format = std::env::var("FORGEX_LOG_FORMAT").unwrap_or_else(|_| cfg.format.clone());
+
+    let (filter_layer, handle) = reload::Layer::new(filter);
+    let _ = FILTER_HANDLE.set(handle);
+
+    let fmt_layer = if format == "json" {
+        fmt::layer().json().with_current_span(true).with_span_list(true).boxed()
+    } else {
+        fmt::layer().boxed()
+    };
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(fmt_layer)
+        .init();
+}
+
+/// Применить новые уровни из конфига.
+/// Если уровни заданы через окружение — оставляем их.
+pub fn reload_log_filter(cfg: &LogConfig) -> Result<(), String> {
+    if env_filter_override().is_some() {
+        return Err("log filter is pinned by FORGEX_LOG/RUST_LOG".into());
+    }
+
+    let filter = EnvFilter::try_new(&cfg.filter)
+        .map_err(|e| format!("invalid log filter {:?}: {}", cfg.filter, e))?;
+
+    FILTER_HANDLE
+        .get()
+        .ok_or_else(|| "logging not initialized".to_string())?
+        .reload(filter)
+        .map_err(|e| format!("failed to reload log filter: {}", e))
+}
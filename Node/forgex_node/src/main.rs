@@ -1,76 +1,133 @@
-mod p2p;
-mod model;
-mod tx;
-mod mempool;
-mod state;
-mod block;
-mod node;
+use std::sync::Arc;
 
 use anyhow::Result;
-use crate::model::{Decoded, decode_message, make_tx_response, make_balance_response, make_nonce_response, make_tx_reject_response};
-use crate::tx::validate_tx;
-use crate::mempool::{MEMPOOL, mempool_add_tx};
-use crate::state::{balance, nonce};
-use crate::node::run_node_loop;
-
-fn handle_message(msg: Vec<u8>) -> Vec<u8> {
-    match decode_message(&msg) {
-        // ─────────────── ТРАНЗАКЦИЯ ───────────────
-        Ok(Decoded::Tx(tx)) => {
-            match validate_tx(&tx) {
-                Ok(valid_tx) => {
-                    let mut mp = MEMPOOL.lock().unwrap();
-                    println!("TX VALID: {:?}", valid_tx);
-
-                    mempool_add_tx(&mut mp, valid_tx);
-                    println!("MEMPOOL SIZE: {}", mp.len());
-
-                    make_tx_response()
-                }
-                Err(e) => {
-                    println!("TX INVALID: {}", e);
-                    make_tx_reject_response(&e)
-                }
-            }
-        }
+use tokio::time::{Duration, timeout};
+use tracing::{error, info, warn};
 
-        // ─────────────── ЗАПРОС БАЛАНСА ───────────────
-        Ok(Decoded::AskBalance(addr, token)) => {
-            println!("ASK_BALANCE: {} {}", addr, token);
+use forgex_node::admin::{admin_handler, run_admin_cli};
+use forgex_node::block::BlockStoreHook;
+use forgex_node::config::{config_path, load_config};
+use forgex_node::events::{LogHook, register_hook};
+use forgex_node::handler::p2p_handler;
+use forgex_node::logging::init_logging;
+use forgex_node::metrics::{MetricsHook, run_metrics_server};
+use forgex_node::multisig::run_multisig_cli;
+use forgex_node::node::{Node, run_node_loop};
+use forgex_node::p2p::{IndexerPushHook, run_admin_server, run_p2p_server};
+use forgex_node::shutdown::{flush_to_disk, wait_for_signal};
+use forgex_node::subscribe::SubscriptionHook;
 
-            // Берём баланс из in-memory стейта
-            let bal = balance(&addr, &token);
-            make_balance_response(bal, &addr)
+#[tokio::main]
+async fn main() -> Result<()> {
+    // `forgex_node admin ...` — клиент к админке запущенной ноды
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("admin") {
+        if let Err(e) = run_admin_cli(&args[1..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
+        return Ok(());
+    }
 
-        // ─────────────── ЗАПРОС NONCE ───────────────
-        Ok(Decoded::AskNonce(addr)) => {
-            println!("ASK_NONCE: {}", addr);
-
-            let n = nonce(&addr);
-            make_nonce_response(n, &addr)
+    // `forgex_node multisig ...` — адрес мультисига и сбор подписей вне сети
+    if args.first().map(String::as_str) == Some("multisig") {
+        // адреса и chain_id — той сети, что в конфиге; без него не угадываем
+        let cfg = match load_config(&config_path()) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+        if let Err(e) = run_multisig_cli(&args[1..], &cfg).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
+        return Ok(());
+    }
 
-        // ─────────────── ОШИБКА ДЕКОДА ───────────────
+    // файла нет — значения по умолчанию (это делает load_config);
+    // битый конфиг — не стартуем, а не молча уезжаем в devnet
+    let cfg = match load_config(&config_path()) {
+        Ok(cfg) => cfg,
         Err(e) => {
-            println!("DECODE ERROR: {}", e);
-            b"ERR".to_vec()
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
+    };
+    init_logging(&cfg.log);
+    info!(network = %cfg.network, chain_id = %cfg.profile().chain_id, "network selected");
+
+    let node = Arc::new(Node::new(cfg.clone()));
+
+    // порядок важен: сначала блок в хранилище, потом подписчикам
+    let indexer_push = Arc::new(IndexerPushHook::default());
+    register_hook(&node, Arc::new(LogHook));
+    register_hook(&node, Arc::new(MetricsHook));
+    register_hook(&node, Arc::new(BlockStoreHook));
+    register_hook(&node, Arc::new(SubscriptionHook));
+    register_hook(&node, indexer_push.clone());
+
+    {
+        let node = node.clone();
+        tokio::spawn(async move { wait_for_signal(&node).await });
     }
-}
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let addr = "127.0.0.1:5050";
-
-    tokio::join!(
-        async {
-            p2p::run_p2p_server(addr, handle_message).await.unwrap();
-        },
-        async {
-            run_node_loop().await;
-        }
-    );
+    let p2p_server = {
+        let node = node.clone();
+        tokio::spawn(async move {
+            if let Err(e) = run_p2p_server(node.clone(), &cfg.p2p_addr, p2p_handler).await {
+                error!(error = %e, "P2P server failed");
+                node.shutdown.request();
+            }
+        })
+    };
+
+    // без токена админки нет и слушателя
+    let admin_server = (!cfg.admin_token.is_empty()).then(|| {
+        let node = node.clone();
+        let addr = cfg.admin_addr.clone();
+        tokio::spawn(async move {
+            if let Err(e) = run_admin_server(node, &addr, admin_handler).await {
+                error!(error = %e, "admin server failed");
+            }
+        })
+    });
+
+    {
+        let node = node.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                res = run_metrics_server(node.clone(), &cfg.metrics_addr) => {
+                    if let Err(e) = res {
+                        error!(error = %e, "metrics server failed");
+                    }
+                }
+                _ = node.shutdown.requested() => {}
+            }
+        });
+    }
+
+    // выходит только при остановке, причём между блоками
+    run_node_loop(node.clone()).await;
+
+    // ---- Остановка: доотправляем, закрываем пиров, сбрасываем на диск ----
+    let wait = Duration::from_millis(node.config().shutdown_timeout_ms);
+
+    indexer_push.wait_pending(wait).await;
+
+    node.shutdown.close_peers();
+    if timeout(wait, p2p_server).await.is_err() {
+        warn!("gave up waiting for peer connections");
+    }
+    if let Some(admin_server) = admin_server
+        && timeout(wait, admin_server).await.is_err()
+    {
+        warn!("gave up waiting for admin connections");
+    }
+
+    flush_to_disk(&node);
+    info!("node stopped");
 
     Ok(())
-}
\ No newline at end of file
+}
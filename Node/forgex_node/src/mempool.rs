@@ -1,19 +1,34 @@
 use std::collections::HashMap;
-use std::sync::Mutex;
-use once_cell::sync::Lazy;
 
-use crate::tx::ValidTxCore;
+use tracing::warn;
+
+use crate::envelope::{decode_tx, encode_tx};
+use crate::network::NetworkProfile;
+use crate::state::{ChainState, write_lines_atomic};
+use crate::tx::{ValidTxCore, build_raw_tx_from_struct, bytes_to_hex, hex_to_bytes, sha256, tx_from_core, validate_tx};
 
 pub type Mempool = HashMap<String, ValidTxCore>;
 
-pub static MEMPOOL: Lazy<Mutex<Mempool>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Результат добавления транзы в мемпул
+#[derive(Debug, Clone, PartialEq)]
+pub enum MempoolAdd {
+    Added,
+    /// Вытеснила транзу с тем же (from, nonce) и меньшей комиссией
+    Replaced { evicted: String },
+    /// Такая транза уже лежит в мемпуле
+    Duplicate,
+    /// Уже есть транза с тем же (from, nonce) и не меньшей комиссией
+    Underpriced,
+}
 
-pub fn mempool_add_tx(m: &mut Mempool, tx: ValidTxCore) {
+pub fn mempool_add_tx(m: &mut Mempool, tx: ValidTxCore) -> MempoolAdd {
 
     if m.contains_key(&tx.tx_hash) {
-        return;
+        return MempoolAdd::Duplicate;
     }
 
+    let mut evicted = None;
+
     if let Some((existing_hash, existing_fee)) = m
         .iter()
         .find(|(_, existing)| existing.from == tx.from && existing.nonce == tx.nonce)
@@ -21,27 +36,83 @@ pub fn mempool_add_tx(m: &mut Mempool, tx: ValidTxCore) {
     {
         if tx.fee <= existing_fee {
 
-            return;
+            return MempoolAdd::Underpriced;
         } else {
 
             m.remove(&existing_hash);
+            evicted = Some(existing_hash);
         }
     }
 
     m.insert(tx.tx_hash.clone(), tx);
+
+    match evicted {
+        Some(evicted) => MempoolAdd::Replaced { evicted },
+        None => MempoolAdd::Added,
+    }
 }
 
 pub fn mempool_get_top(m: &Mempool, count: usize) -> Vec<ValidTxCore> {
 
     let mut txs: Vec<ValidTxCore> = m.values().cloned().collect();
 
-    txs.sort_by(|a, b| b.fee.cmp(&a.fee));
+    // при равной комиссии — по хешу, чтобы порядок не зависел от HashMap
+    txs.sort_by(|a, b| b.fee.cmp(&a.fee).then_with(|| a.tx_hash.cmp(&b.tx_hash)));
 
     let real_count = std::cmp::min(count, txs.len());
     txs.into_iter().take(real_count).collect()
 }
 
+/// Как mempool_get_top, но только транзы с fee не ниже base_fee блока.
+/// Остальные остаются в мемпуле и ждут, пока base fee не опустится.
+pub fn mempool_get_payable(m: &Mempool, count: usize, base_fee: u64) -> Vec<ValidTxCore> {
+    let mut txs: Vec<ValidTxCore> = m.values().filter(|tx| tx.fee >= base_fee).cloned().collect();
+
+    // порядок по fee — он же порядок по чаевым сверх base fee
+    txs.sort_by(|a, b| b.fee.cmp(&a.fee).then_with(|| a.tx_hash.cmp(&b.tx_hash)));
+    txs.truncate(count);
+    txs
+}
+
 pub fn mempool_remove_by_hash(m: &mut Mempool, tx_hash: &str) -> Option<ValidTxCore> {
     m.remove(tx_hash)
 }
-// mempool.rs ends here
\ No newline at end of file
+
+/// Сбросить мемпул в текстовый файл, по строке на транзу: raw tx в конверте
+/// версии, в hex — ровно то, что подписал отправитель, load_mempool читает его
+/// обратно без потерь. Порядок — как при сборке блока (по fee). Возвращает число транз.
+pub fn dump_mempool(m: &Mempool, network: &NetworkProfile, path: &str) -> Result<usize, String> {
+    let lines = mempool_get_top(m, m.len())
+        .iter()
+        .map(|tx| encode_tx(&tx_from_core(tx, network)).map(|raw| bytes_to_hex(&raw)))
+        .collect::<Result<Vec<String>, String>>()?;
+
+    write_lines_atomic(path, &lines)?;
+    Ok(lines.len())
+}
+
+/// Прочитать снимок dump_mempool. Каждая транза проверяется заново
+/// (подпись, timestamp на now_ms): протухшие и битые пропускаются с warn.
+/// Символ токена берётся из реестра state — в raw tx только код.
+pub fn load_mempool(path: &str, state: &ChainState, now_ms: u64) -> Result<Mempool, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    let mut m = Mempool::new();
+
+    for (i, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let loaded = hex_to_bytes(line.trim()).and_then(|data| {
+            let mut tx = decode_tx(&data)?;
+            tx.tx_hash = bytes_to_hex(&sha256(&build_raw_tx_from_struct(&tx)?));
+            if let Some(token) = state.tokens.get(tx.token_id) {
+                tx.token = token.symbol.clone();
+            }
+            validate_tx(&tx, now_ms, state.network)
+        });
+        match loaded {
+            Ok(tx) => {
+                mempool_add_tx(&mut m, tx);
+            }
+            Err(e) => warn!(path, line = i + 1, error = %e, "skipping mempool snapshot entry"),
+        }
+    }
+    Ok(m)
+}
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use anyhow::Result;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::info;
+
+use crate::events::{NodeEvent, NodeHook};
+use crate::node::Node;
+use crate::tx::{TxExtra, ValidTxCore};
+
+// ─────────────────────── ПРИМИТИВЫ ───────────────────────
+
+/// Гистограмма в стиле Prometheus (кумулятивные бакеты считаются при выводе)
+pub struct Histogram {
+    bounds: &'static [f64],
+    inner: Mutex<HistogramInner>,
+}
+
+struct HistogramInner {
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Histogram {
+            bounds,
+            inner: Mutex::new(HistogramInner {
+                counts: vec![0; bounds.len()],
+                sum: 0.0,
+                count: 0,
+            }),
+        }
+    }
+
+    pub fn observe(&self, v: f64) {
+        let mut h = self.inner.lock().unwrap();
+        if let Some(i) = self.bounds.iter().position(|b| v <= *b) {
+            h.counts[i] += 1;
+        }
+        h.sum += v;
+        h.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let h = self.inner.lock().unwrap();
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        let mut cumulative = 0;
+        for (bound, c) in self.bounds.iter().zip(&h.counts) {
+            cumulative += c;
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, h.count);
+        let _ = writeln!(out, "{}_sum {}", name, h.sum);
+        let _ = writeln!(out, "{}_count {}", name, h.count);
+    }
+}
+
+fn render_value(out: &mut String, name: &str, kind: &str, help: &str, v: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "{} {}", name, v);
+}
+
+// ─────────────────────── МЕТРИКИ НОДЫ ───────────────────────
+
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+const TXS_PER_BLOCK_BUCKETS: &[f64] = &[1.0, 2.0, 5.0, 10.0, 15.0, 20.0, 25.0];
+
+pub struct NodeMetrics {
+    block_height: AtomicU64,
+    block_latency: Histogram,
+    txs_per_block: Histogram,
+    tx_accepted: AtomicU64,
+    tx_rejected: Mutex<HashMap<String, u64>>,
+    tx_evicted: AtomicU64,
+    tx_busy: AtomicU64,
+    p2p_connections: AtomicU64,
+    p2p_connections_total: AtomicU64,
+    /// height -> когда блок начали собирать (BlockProduced)
+    pending_blocks: Mutex<HashMap<u64, Instant>>,
+}
+
+impl Default for NodeMetrics {
+    fn default() -> Self {
+        NodeMetrics {
+            block_height: AtomicU64::new(0),
+            block_latency: Histogram::new(LATENCY_BUCKETS),
+            txs_per_block: Histogram::new(TXS_PER_BLOCK_BUCKETS),
+            tx_accepted: AtomicU64::new(0),
+            tx_rejected: Mutex::new(HashMap::new()),
+            tx_evicted: AtomicU64::new(0),
+            tx_busy: AtomicU64::new(0),
+            p2p_connections: AtomicU64::new(0),
+            p2p_connections_total: AtomicU64::new(0),
+            pending_blocks: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl NodeMetrics {
+    /// Соединение P2P открылось
+    pub fn p2p_connection_opened(&self) {
+        self.p2p_connections.fetch_add(1, Ordering::Relaxed);
+        self.p2p_connections_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Транзу не приняли на проверку: очередь заполнена
+    pub fn tx_busy(&self, n: u64) {
+        self.tx_busy.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Соединение P2P закрылось
+    pub fn p2p_connection_closed(&self) {
+        self.p2p_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Причину отказа сводим к короткой метке без чисел,
+/// чтобы "field must be 9 bytes, got 8" не плодило новые серии
+fn reject_reason_label(reason: &str) -> String {
+    let head = reason.split([':', ',']).next().unwrap_or("");
+    let label: String = head
+        .chars()
+        .filter(|c| !c.is_ascii_digit())
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    let label = label.trim_matches('_').to_string();
+    if label.is_empty() { "other".into() } else { label }
+}
+
+/// Примерный размер транзы в мемпуле (как она уходит в блок)
+fn tx_size_bytes(tx: &ValidTxCore) -> usize {
+    // tx_hash + tx_type + from/to/token с длинами + 4 * u64 + pubkey + signature
+    let base = 32 + 1 + (2 + tx.from.len()) + (2 + tx.to.len()) + (2 + tx.token.len()) + 32 + 33 + 65;
+    // + поля по типу
+    base + match &tx.extra {
+        TxExtra::None => 0,
+        TxExtra::CreateToken { .. } => 1 + 8,
+        TxExtra::MultiSend(payouts) => 2 + payouts.iter().map(|(to, _)| 2 + to.len() + 8).sum::<usize>(),
+        TxExtra::Multisig { pubkeys, signatures, .. } => 3 + pubkeys.len() * 33 + signatures.len() * 66,
+        TxExtra::Vesting(_) => 1 + 8 * 3,
+    }
+}
+
+/// Хук: обновляет метрики по событиям ноды
+pub struct MetricsHook;
+
+impl NodeHook for MetricsHook {
+    fn on_event(&self, node: &Node, event: &NodeEvent) {
+        let m = &node.metrics;
+        match event {
+            NodeEvent::TxAdmitted { .. } => {
+                m.tx_accepted.fetch_add(1, Ordering::Relaxed);
+            }
+            NodeEvent::TxRejected { reason, .. } => {
+                let mut rejected = m.tx_rejected.lock().unwrap();
+                *rejected.entry(reject_reason_label(reason)).or_insert(0) += 1;
+            }
+            NodeEvent::TxEvicted { .. } => {
+                m.tx_evicted.fetch_add(1, Ordering::Relaxed);
+            }
+            NodeEvent::BlockProduced { block } => {
+                m
+                    .pending_blocks
+                    .lock()
+                    .unwrap()
+                    .insert(block.header.height, Instant::now());
+            }
+            NodeEvent::BlockApplied { block } => {
+                let height = block.header.height;
+                m.block_height.store(height, Ordering::Relaxed);
+                m.txs_per_block.observe(block.body.txs.len() as f64);
+
+                let mut pending = m.pending_blocks.lock().unwrap();
+                if let Some(started) = pending.remove(&height) {
+                    m.block_latency.observe(started.elapsed().as_secs_f64());
+                }
+                // блоки, которые так и не применились, не копим
+                pending.retain(|h, _| *h > height);
+            }
+            NodeEvent::StateChanged { .. } => {}
+        }
+    }
+}
+
+/// Все метрики ноды в текстовом формате Prometheus
+pub fn render_metrics(node: &Node) -> String {
+    let m = &node.metrics;
+    let mut out = String::new();
+
+    let (mempool_size, mempool_bytes) = {
+        let mp = node.mempool.lock().unwrap();
+        let bytes: usize = mp.values().map(tx_size_bytes).sum();
+        (mp.len() as u64, bytes as u64)
+    };
+
+    render_value(&mut out, "forgex_node_mempool_size", "gauge", "Transactions in the mempool", mempool_size);
+    render_value(&mut out, "forgex_node_mempool_bytes", "gauge", "Approximate encoded size of the mempool in bytes", mempool_bytes);
+    render_value(&mut out, "forgex_node_block_height", "gauge", "Height of the latest applied block", m.block_height.load(Ordering::Relaxed));
+    m.block_latency.render(&mut out, "forgex_node_block_production_seconds", "Time from block assembly to applied state");
+    m.txs_per_block.render(&mut out, "forgex_node_block_txs", "Transactions per produced block");
+    render_value(&mut out, "forgex_node_tx_accepted_total", "counter", "Transactions admitted to the mempool", m.tx_accepted.load(Ordering::Relaxed));
+
+    let _ = writeln!(out, "# HELP forgex_node_tx_rejected_total Transactions rejected at admission by reason");
+    let _ = writeln!(out, "# TYPE forgex_node_tx_rejected_total counter");
+    {
+        let rejected = m.tx_rejected.lock().unwrap();
+        let mut reasons: Vec<_> = rejected.iter().collect();
+        reasons.sort();
+        for (reason, n) in reasons {
+            let _ = writeln!(out, "forgex_node_tx_rejected_total{{reason=\"{}\"}} {}", reason, n);
+        }
+    }
+
+    render_value(&mut out, "forgex_node_tx_evicted_total", "counter", "Transactions evicted from the mempool", m.tx_evicted.load(Ordering::Relaxed));
+    render_value(&mut out, "forgex_node_tx_busy_total", "counter", "Transactions turned away because the verify queue was full", m.tx_busy.load(Ordering::Relaxed));
+    render_value(&mut out, "forgex_node_verify_queue", "gauge", "Transactions waiting for or under signature verification", node.verifier.queued() as u64);
+    render_value(&mut out, "forgex_node_p2p_connections", "gauge", "Open P2P connections", m.p2p_connections.load(Ordering::Relaxed));
+    render_value(&mut out, "forgex_node_p2p_connections_total", "counter", "Accepted P2P connections", m.p2p_connections_total.load(Ordering::Relaxed));
+
+    out
+}
+
+// ─────────────────────── HTTP /metrics ───────────────────────
+
+/// Минимальный HTTP-сервер только под GET /metrics
+pub async fn run_metrics_server(node: Arc<Node>, addr: &str) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    info!(addr = %addr, "metrics listening");
+
+    loop {
+        let (mut socket, _) = listener.accept().await?;
+
+        let node = node.clone();
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 1024];
+            let n = match socket.read(&mut buf).await {
+                Ok(n) => n,
+                Err(_) => return,
+            };
+
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let response = if request.starts_with("GET /metrics") {
+                let body = render_metrics(&node);
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            };
+
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+    }
+}
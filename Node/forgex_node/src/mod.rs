@@ -1,7 +0,0 @@
-pub mod block;
-pub mod mempool;
-pub mod node;
-pub mod p2p;
-pub mod state;
-pub mod tx;
-pub mod model;
\ No newline at end of file
@@ -1,8 +1,43 @@
-use crate::block::Block;
-use crate::tx::ValidTxCore;
+use crate::address::{decode_address, encode_address};
+use crate::block::{Block, Receipt};
+use crate::config::FeeConfig;
+use crate::estimate::FeeEstimate;
+use crate::network::NetworkProfile;
+use crate::state::{TokenId, TokenInfo, ValidatorSet, VestingClock, VestingSchedule};
+use crate::envelope::{decode_tx, encode_tx, enveloped_tx_len};
+use crate::tx::{PIPE_V2, TxExtra, ValidTxCore};
 
 const MAGIC: &[u8; 4] = b"FGX1";
 const MSG_TYPE_BLOCK: u8 = 7;
+pub const MSG_TYPE_SUBSCRIBE: u8 = 8;
+const MSG_TYPE_RECEIPTS: u8 = 9;
+const MSG_TYPE_ASK_HEIGHT: u8 = 10;
+const MSG_TYPE_HEIGHT: u8 = 11;
+const MSG_TYPE_ADMIN: u8 = 12;
+const MSG_TYPE_ADMIN_RESPONSE: u8 = 13;
+pub const MSG_TYPE_SEND_TX_BATCH: u8 = 14;
+const MSG_TYPE_TX_BATCH_RESPONSE: u8 = 15;
+const MSG_TYPE_ASK_TOKEN: u8 = 16;
+const MSG_TYPE_TOKEN: u8 = 17;
+const MSG_TYPE_ASK_VALIDATORS: u8 = 18;
+const MSG_TYPE_VALIDATORS: u8 = 19;
+const MSG_TYPE_ASK_FEES: u8 = 20;
+const MSG_TYPE_FEES: u8 = 21;
+const MSG_TYPE_ASK_FEE_ESTIMATE: u8 = 22;
+const MSG_TYPE_FEE_ESTIMATE: u8 = 23;
+
+/// Raw tx без хвостов по типу
+pub const RAW_TX_BASE_LEN: usize = 269;
+/// Где в raw tx лежит pubkey (33 байта)
+pub const RAW_TX_PUBKEY_AT: usize = 164;
+/// Компактный raw tx (без pubkey) без хвостов по типу
+pub const COMPACT_RAW_TX_BASE_LEN: usize = RAW_TX_BASE_LEN - 33;
+
+/// Сколько транз максимум в SEND_TX_BATCH (влезает в u16 длину кадра)
+pub const MAX_TX_BATCH: usize = 200;
+
+/// Флаг в SUBSCRIBE: кроме блоков слать ещё и квитанции
+pub const SUBSCRIBE_FLAG_RECEIPTS: u8 = 0x01;
 
 // -------------------------------------------------------
 // SIMPLE TX STRUCT
@@ -17,6 +52,8 @@ pub struct Tx {
     pub from: String,
     pub to: String,
     pub token: String,
+    /// Код токена из raw tx; символ (token) нода берёт по нему из реестра
+    pub token_id: TokenId,
     pub amount: u64,
     pub fee: u64,
     pub nonce: u64,
@@ -24,32 +61,53 @@ pub struct Tx {
     pub pubkey: String,
     pub signature: String,
     pub encoding: String,
+    pub extra: TxExtra,
+    /// Memo (pipe_v2); пустая строка — без memo
+    pub memo: String,
 }
 
 // -------------------------------------------------------
 // WHAT DECODER RETURNS
 // -------------------------------------------------------
 pub enum Decoded {
-    Tx(Tx),
+    Tx(Box<Tx>),
+    TxBatch(Vec<Tx>),
     AskBalance(String, String),
     AskNonce(String),
+    AskToken(String),
+    AskValidators,
+    AskFees,
+    AskFeeEstimate(u16),
+    Subscribe { from_height: u64, with_receipts: bool },
+    AskHeight,
+    Admin { token: Vec<u8>, cmd: u8, args: Vec<u8> },
 }
 
 // -------------------------------------------------------
-// PARSE FIXED UTF-8 FIELD
+// CHECKED READS (курсор сдвигается за прочитанное)
 // -------------------------------------------------------
-fn parse_fixed_utf8<'a>(
-    data: &'a [u8],
-    len: usize,
-    field: &str,
-) -> Result<(String, &'a [u8]), String> {
-    if data.len() < len {
+fn take<'a>(cur: &mut &'a [u8], len: usize, field: &str) -> Result<&'a [u8], String> {
+    if cur.len() < len {
         return Err(format!("not enough bytes for {}", field));
     }
-    let (chunk, rest) = data.split_at(len);
-    let s = String::from_utf8(chunk.to_vec())
-        .map_err(|_| format!("invalid utf-8 in {}", field))?;
-    Ok((s, rest))
+    let (chunk, rest) = cur.split_at(len);
+    *cur = rest;
+    Ok(chunk)
+}
+
+fn take_u8(cur: &mut &[u8], field: &str) -> Result<u8, String> {
+    Ok(take(cur, 1, field)?[0])
+}
+
+fn take_u64(cur: &mut &[u8], field: &str) -> Result<u64, String> {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(take(cur, 8, field)?);
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Поле фиксированной длины в UTF-8
+fn take_utf8(cur: &mut &[u8], len: usize, field: &str) -> Result<String, String> {
+    String::from_utf8(take(cur, len, field)?.to_vec()).map_err(|_| format!("invalid utf-8 in {}", field))
 }
 
 // -------------------------------------------------------
@@ -58,17 +116,19 @@ fn parse_fixed_utf8<'a>(
 fn tx_type_from_byte(b: u8) -> String {
     match b {
         1 => "transfer".into(),
+        2 => "create_token".into(),
+        3 => "mint".into(),
+        4 => "burn".into(),
+        5 => "multi_send".into(),
+        6 => "multisig_transfer".into(),
+        7 => "vesting_transfer".into(),
+        8 => "stake".into(),
+        9 => "unstake".into(),
+        10 => "delegate".into(),
         _ => "unknown".into(),
     }
 }
 
-fn token_from_byte(b: u8) -> String {
-    match b {
-        1 => "GLD".into(),
-        _ => "UNKNOWN".into(),
-    }
-}
-
 // -------------------------------------------------------
 // HELPER (hex)
 // -------------------------------------------------------
@@ -77,68 +137,165 @@ fn bytes_to_hex(bytes: &[u8]) -> String {
 }
 
 // -------------------------------------------------------
-// PARSE RAW TX (269 bytes)
+// RAW TX LENGTH (269 bytes + хвост по типу)
 // -------------------------------------------------------
-pub fn parse_raw_tx(raw: &[u8]) -> Result<Tx, String> {
-    use std::convert::TryInto;
+/// Длина raw tx, который начинается в data (для разбора пачек)
+pub fn raw_tx_len(data: &[u8]) -> Result<usize, String> {
+    tx_len_with_base(data, RAW_TX_BASE_LEN)
+}
+
+/// Длина компактного raw tx (без pubkey), который начинается в data
+pub fn compact_raw_tx_len(data: &[u8]) -> Result<usize, String> {
+    tx_len_with_base(data, COMPACT_RAW_TX_BASE_LEN)
+}
+
+/// Хвосты по типу и encoding стоят сразу за базой, так что формы с pubkey
+/// и без него отличаются только её длиной
+fn tx_len_with_base(data: &[u8], base: usize) -> Result<usize, String> {
+    if data.len() < base {
+        return Err(format!("raw_tx must be at least {} bytes, got {}", base, data.len()));
+    }
+    // tx_type стоит сразу за domain_tag и chain_id
+    let len = match data[18] {
+        // create_token: symbol_len (u8) | symbol | decimals (u8) | initial_supply (u64)
+        2 => {
+            let symbol_len = *data
+                .get(base)
+                .ok_or_else(|| "not enough bytes for create_token params".to_string())?
+                as usize;
+            base + 1 + symbol_len + 1 + 8
+        }
+        // multi_send: count (u16) | count * (to (56) | amount (u64))
+        5 => {
+            let count = data
+                .get(base..base + 2)
+                .ok_or_else(|| "not enough bytes for multi_send count".to_string())?;
+            let count = u16::from_be_bytes([count[0], count[1]]) as usize;
+            base + 2 + count * (56 + 8)
+        }
+        // multisig_transfer: threshold (u8) | n (u8) | n * pubkey (33) | m (u8) | m * (index (u8) | sig (65))
+        6 => {
+            let n = *data
+                .get(base + 1)
+                .ok_or_else(|| "not enough bytes for multisig pubkeys".to_string())?
+                as usize;
+            let sigs_at = base + 2 + n * 33;
+            let m = *data
+                .get(sigs_at)
+                .ok_or_else(|| "not enough bytes for multisig signatures".to_string())?
+                as usize;
+            sigs_at + 1 + m * (1 + 65)
+        }
+        // vesting_transfer: clock (u8) | start (u64) | cliff (u64) | duration (u64)
+        7 => base + 1 + 8 * 3,
+        _ => base,
+    };
+    // pipe_v2: memo_len (u8) | memo после хвоста типа
+    if &data[base - 7..base] == PIPE_V2.as_bytes() {
+        let memo_len = *data
+            .get(len)
+            .ok_or_else(|| "not enough bytes for memo".to_string())?
+            as usize;
+        return Ok(len + 1 + memo_len);
+    }
+    Ok(len)
+}
 
-    if raw.len() != 269 {
-        return Err(format!("raw_tx must be 269 bytes, got {}", raw.len()));
+// -------------------------------------------------------
+// PARSE RAW TX (269 bytes + хвост по типу)
+// -------------------------------------------------------
+pub fn parse_raw_tx(raw: &[u8]) -> Result<Tx, String> {
+    let expected = raw_tx_len(raw)?;
+    if raw.len() != expected {
+        return Err(format!("raw_tx must be {} bytes, got {}", expected, raw.len()));
     }
 
+    // каждое поле читаем с проверкой длины: кривой raw tx — Err, а не паника
     let mut cur = raw;
 
     // domain_tag (9)
-    let (domain_tag, rest) = parse_fixed_utf8(cur, 9, "domain_tag")?;
-    cur = rest;
+    let domain_tag = take_utf8(&mut cur, 9, "domain_tag")?;
 
     // chain_id (9)
-    let (chain_id, rest) = parse_fixed_utf8(cur, 9, "chain_id")?;
-    cur = rest;
+    let chain_id = take_utf8(&mut cur, 9, "chain_id")?;
 
     // tx_type (1)
-    let tx_type = tx_type_from_byte(cur[0]);
-    cur = &cur[1..];
+    let tx_type = tx_type_from_byte(take_u8(&mut cur, "tx_type")?);
 
     // from (56)
-    let (from, rest) = parse_fixed_utf8(cur, 56, "from")?;
-    cur = rest;
+    let from = take_utf8(&mut cur, 56, "from")?;
 
     // to (56)
-    let (to, rest) = parse_fixed_utf8(cur, 56, "to")?;
-    cur = rest;
-
-    // token (1)
-    let token = token_from_byte(cur[0]);
-    cur = &cur[1..];
+    let to = take_utf8(&mut cur, 56, "to")?;
 
-    // amount (8)
-    let amount = u64::from_be_bytes(cur[0..8].try_into().unwrap());
-    cur = &cur[8..];
+    // token (1) — код; символ подставит нода по реестру
+    let token_id = take_u8(&mut cur, "token")?;
 
-    // fee (8)
-    let fee = u64::from_be_bytes(cur[0..8].try_into().unwrap());
-    cur = &cur[8..];
-
-    // nonce (8)
-    let nonce = u64::from_be_bytes(cur[0..8].try_into().unwrap());
-    cur = &cur[8..];
-
-    // timestamp (8)
-    let timestamp = u64::from_be_bytes(cur[0..8].try_into().unwrap());
-    cur = &cur[8..];
+    // amount, fee, nonce, timestamp (8 каждое)
+    let amount = take_u64(&mut cur, "amount")?;
+    let fee = take_u64(&mut cur, "fee")?;
+    let nonce = take_u64(&mut cur, "nonce")?;
+    let timestamp = take_u64(&mut cur, "timestamp")?;
 
     // pubkey (33 → hex)
-    let pubkey = bytes_to_hex(&cur[0..33]);
-    cur = &cur[33..];
+    let pubkey = bytes_to_hex(take(&mut cur, 33, "pubkey")?);
 
     // signature (65 → hex)
-    let signature = bytes_to_hex(&cur[0..65]);
-    cur = &cur[65..];
+    let signature = bytes_to_hex(take(&mut cur, 65, "signature")?);
 
     // encoding (7)
-    let (encoding, rest) = parse_fixed_utf8(cur, 7, "encoding")?;
-    cur = rest;
+    let encoding = take_utf8(&mut cur, 7, "encoding")?;
+
+    // хвост по типу
+    let mut token = String::new();
+    let mut extra = TxExtra::None;
+    if tx_type == "create_token" {
+        let symbol_len = take_u8(&mut cur, "token symbol length")? as usize;
+        token = take_utf8(&mut cur, symbol_len, "token symbol")?;
+        extra = TxExtra::CreateToken {
+            decimals: take_u8(&mut cur, "decimals")?,
+            initial_supply: take_u64(&mut cur, "initial_supply")?,
+        };
+    } else if tx_type == "multi_send" {
+        let count = take(&mut cur, 2, "multi_send count")?;
+        let count = u16::from_be_bytes([count[0], count[1]]);
+        let mut payouts = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let to = take_utf8(&mut cur, 56, "multi_send to")?;
+            payouts.push((to, take_u64(&mut cur, "multi_send amount")?));
+        }
+        extra = TxExtra::MultiSend(payouts);
+    } else if tx_type == "multisig_transfer" {
+        let threshold = take_u8(&mut cur, "multisig threshold")?;
+        let n = take_u8(&mut cur, "multisig pubkeys")?;
+        let pubkeys = (0..n)
+            .map(|_| take(&mut cur, 33, "multisig pubkey").map(bytes_to_hex))
+            .collect::<Result<Vec<_>, _>>()?;
+        let m = take_u8(&mut cur, "multisig signatures")?;
+        let signatures = (0..m)
+            .map(|_| {
+                let index = take_u8(&mut cur, "multisig signature index")?;
+                Ok((index, bytes_to_hex(take(&mut cur, 65, "multisig signature")?)))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        extra = TxExtra::Multisig { threshold, pubkeys, signatures };
+    } else if tx_type == "vesting_transfer" {
+        let clock = VestingClock::from_byte(take_u8(&mut cur, "vesting clock")?)
+            .ok_or_else(|| "invalid vesting clock".to_string())?;
+        extra = TxExtra::Vesting(VestingSchedule {
+            clock,
+            start: take_u64(&mut cur, "vesting start")?,
+            cliff: take_u64(&mut cur, "vesting cliff")?,
+            duration: take_u64(&mut cur, "vesting duration")?,
+        });
+    }
+
+    // memo (pipe_v2)
+    let mut memo = String::new();
+    if encoding == PIPE_V2 {
+        let memo_len = take_u8(&mut cur, "memo length")? as usize;
+        memo = take_utf8(&mut cur, memo_len, "memo")?;
+    }
 
     if !cur.is_empty() {
         return Err("extra bytes at end of tx".into());
@@ -152,6 +309,7 @@ pub fn parse_raw_tx(raw: &[u8]) -> Result<Tx, String> {
         from,
         to,
         token,
+        token_id,
         amount,
         fee,
         nonce,
@@ -159,9 +317,30 @@ pub fn parse_raw_tx(raw: &[u8]) -> Result<Tx, String> {
         pubkey,
         signature,
         encoding,
+        extra,
+        memo,
     })
 }
 
+/// Компактный raw tx: тот же raw tx без pubkey. Pubkey остаётся пустым —
+/// его восстановит из подписи validate_tx.
+pub fn parse_compact_raw_tx(raw: &[u8]) -> Result<Tx, String> {
+    let expected = compact_raw_tx_len(raw)?;
+    if raw.len() != expected {
+        return Err(format!("compact raw_tx must be {} bytes, got {}", expected, raw.len()));
+    }
+
+    // вставляем нулевой pubkey и разбираем как обычный raw tx
+    let mut full = Vec::with_capacity(raw.len() + 33);
+    full.extend_from_slice(&raw[..RAW_TX_PUBKEY_AT]);
+    full.extend_from_slice(&[0u8; 33]);
+    full.extend_from_slice(&raw[RAW_TX_PUBKEY_AT..]);
+
+    let mut tx = parse_raw_tx(&full)?;
+    tx.pubkey = String::new();
+    Ok(tx)
+}
+
 // -------------------------------------------------------
 // MAIN DECODER FOR FGX1 PACKETS
 // -------------------------------------------------------
@@ -185,10 +364,10 @@ pub fn decode_message(msg: &[u8]) -> Result<Decoded, String> {
 
     match msg_type {
         // -------------------------------------
-        // TYPE 1 — SEND_TX (tx_hash + raw_tx)
+        // TYPE 1 — SEND_TX (tx_hash + raw_tx, в конверте или без)
         // -------------------------------------
         1 => {
-            if payload.len() != 301 {
+            if payload.len() < 32 + COMPACT_RAW_TX_BASE_LEN {
                 return Err("invalid tx payload".into());
             }
 
@@ -197,22 +376,22 @@ pub fn decode_message(msg: &[u8]) -> Result<Decoded, String> {
 
             let raw_tx = &payload[32..];
 
-            let mut tx = parse_raw_tx(raw_tx)?;
+            let mut tx = decode_tx(raw_tx)?;
             tx.tx_hash = tx_hash;
 
-            Ok(Decoded::Tx(tx))
+            Ok(Decoded::Tx(Box::new(tx)))
         }
 
         // -------------------------------------
-        // TYPE 3 — ASK_BALANCE (56 bytes addr + 3 bytes token)
+        // TYPE 3 — ASK_BALANCE (56 bytes addr + token symbol)
         // -------------------------------------
         3 => {
-            if payload.len() < 56 + 3 {
+            if payload.len() < 56 + 1 {
                 return Err("AskBalance payload too short".into());
             }
 
             let addr_bytes = &payload[..56];
-            let token_bytes = &payload[56..59];
+            let token_bytes = &payload[56..];
 
             let addr = String::from_utf8(addr_bytes.to_vec())
                 .map_err(|_| "invalid utf-8 in address")?;
@@ -235,6 +414,131 @@ pub fn decode_message(msg: &[u8]) -> Result<Decoded, String> {
             Ok(Decoded::AskNonce(address))
         }
 
+        // -------------------------------------
+        // TYPE 16 — ASK_TOKEN (token symbol)
+        // -------------------------------------
+        MSG_TYPE_ASK_TOKEN => {
+            if payload.is_empty() {
+                return Err("invalid ask_token payload".into());
+            }
+            let symbol = String::from_utf8(payload.to_vec())
+                .map_err(|_| "invalid utf8 token".to_string())?;
+
+            Ok(Decoded::AskToken(symbol))
+        }
+
+        // -------------------------------------
+        // TYPE 18 — ASK_VALIDATORS (пустой payload)
+        // -------------------------------------
+        MSG_TYPE_ASK_VALIDATORS => {
+            if !payload.is_empty() {
+                return Err("invalid ask_validators payload".into());
+            }
+            Ok(Decoded::AskValidators)
+        }
+
+        // -------------------------------------
+        // TYPE 20 — ASK_FEES (пустой payload)
+        // -------------------------------------
+        MSG_TYPE_ASK_FEES => {
+            if !payload.is_empty() {
+                return Err("invalid ask_fees payload".into());
+            }
+            Ok(Decoded::AskFees)
+        }
+
+        // -------------------------------------
+        // TYPE 22 — ASK_FEE_ESTIMATE (u16 target_blocks)
+        // -------------------------------------
+        MSG_TYPE_ASK_FEE_ESTIMATE => {
+            if payload.len() != 2 {
+                return Err("invalid ask_fee_estimate payload".into());
+            }
+            Ok(Decoded::AskFeeEstimate(u16::from_be_bytes([payload[0], payload[1]])))
+        }
+
+        // -------------------------------------
+        // TYPE 8 — SUBSCRIBE (u64 from_height + u8 flags)
+        // -------------------------------------
+        MSG_TYPE_SUBSCRIBE => {
+            if payload.len() != 9 {
+                return Err("invalid subscribe payload".into());
+            }
+            let from_height = u64::from_be_bytes(payload[0..8].try_into().unwrap());
+            let flags = payload[8];
+
+            Ok(Decoded::Subscribe {
+                from_height,
+                with_receipts: flags & SUBSCRIBE_FLAG_RECEIPTS != 0,
+            })
+        }
+
+        // -------------------------------------
+        // TYPE 10 — ASK_HEIGHT (пустой payload)
+        // -------------------------------------
+        MSG_TYPE_ASK_HEIGHT => {
+            if !payload.is_empty() {
+                return Err("invalid ask_height payload".into());
+            }
+            Ok(Decoded::AskHeight)
+        }
+
+        // -------------------------------------
+        // TYPE 12 — ADMIN (u8 token_len + token + u8 cmd + args)
+        // -------------------------------------
+        MSG_TYPE_ADMIN => {
+            let Some((&token_len, rest)) = payload.split_first() else {
+                return Err("invalid admin payload".into());
+            };
+            let token_len = token_len as usize;
+            if rest.len() < token_len + 1 {
+                return Err("invalid admin payload".into());
+            }
+
+            let token = rest[..token_len].to_vec();
+            let cmd = rest[token_len];
+            let args = rest[token_len + 1..].to_vec();
+
+            Ok(Decoded::Admin { token, cmd, args })
+        }
+
+        // -------------------------------------
+        // TYPE 14 — SEND_TX_BATCH (u16 count + count * (tx_hash + raw_tx))
+        // -------------------------------------
+        MSG_TYPE_SEND_TX_BATCH => {
+            if payload.len() < 2 {
+                return Err("invalid tx batch payload".into());
+            }
+            let count = u16::from_be_bytes([payload[0], payload[1]]) as usize;
+            let body = &payload[2..];
+
+            if count == 0 || count > MAX_TX_BATCH {
+                return Err(format!("tx batch must hold 1..={} txs", MAX_TX_BATCH));
+            }
+
+            // транзы идут подряд, длину каждой знает enveloped_tx_len
+            let mut txs = Vec::with_capacity(count);
+            let mut cur = body;
+            for _ in 0..count {
+                if cur.len() < 32 {
+                    return Err("invalid tx batch payload".into());
+                }
+                let len = enveloped_tx_len(&cur[32..])?;
+                if cur.len() < 32 + len {
+                    return Err("invalid tx batch payload".into());
+                }
+                let mut tx = decode_tx(&cur[32..32 + len])?;
+                tx.tx_hash = bytes_to_hex(&cur[..32]);
+                txs.push(tx);
+                cur = &cur[32 + len..];
+            }
+            if !cur.is_empty() {
+                return Err("invalid tx batch payload".into());
+            }
+
+            Ok(Decoded::TxBatch(txs))
+        }
+
         _ => Err(format!("unsupported msg type {}", msg_type)),
     }
 }
@@ -269,15 +573,16 @@ pub fn make_tx_reject_response(reason: &str) -> Vec<u8> {
     buf
 }
 
-pub fn make_balance_response(balance: u64, address: &str) -> Vec<u8> {
+/// BALANCE: balance (u64, свободный) | address (56) | locked (u64, под вестингом)
+pub fn make_balance_response(balance: u64, address: &str, locked: u64) -> Vec<u8> {
     let msg_type: u8 = 4;
 
     let addr_bytes = address.as_bytes();
     assert!(addr_bytes.len() == 56);
 
-    let payload_len: u16 = (8 + 56) as u16;
+    let payload_len: u16 = (8 + 56 + 8) as u16;
 
-    let mut buf = Vec::with_capacity(4 + 1 + 2 + 64);
+    let mut buf = Vec::with_capacity(4 + 1 + 2 + 72);
 
     buf.extend_from_slice(b"FGX1");
     buf.push(msg_type);
@@ -289,6 +594,9 @@ pub fn make_balance_response(balance: u64, address: &str) -> Vec<u8> {
     // address
     buf.extend_from_slice(addr_bytes);
 
+    // locked
+    buf.extend_from_slice(&locked.to_be_bytes());
+
     buf
 }
 
@@ -312,6 +620,467 @@ pub fn make_nonce_response(nonce: u64, address: &str) -> Vec<u8> {
     buf
 }
 
+pub fn make_height_response(height: u64) -> Vec<u8> {
+    let payload_len: u16 = 8;
+
+    let mut buf = Vec::with_capacity(4 + 1 + 2 + 8);
+
+    buf.extend_from_slice(b"FGX1");
+    buf.push(MSG_TYPE_HEIGHT);
+    buf.extend_from_slice(&payload_len.to_be_bytes());
+    buf.extend_from_slice(&height.to_be_bytes());
+
+    buf
+}
+
+/// TOKEN: FGX1 | 17 | len | found (u8) | code (u8) | decimals (u8) | max_supply (u64) | supply (u64) | issuer (56) | symbol
+/// Токена нет — только found = 0.
+pub fn make_token_response(token: Option<(TokenId, &TokenInfo, u64)>, network: &NetworkProfile) -> Vec<u8> {
+    let mut payload = Vec::new();
+    match token {
+        None => payload.push(0),
+        Some((id, t, supply)) => {
+            payload.push(1);
+            payload.push(id);
+            payload.push(t.decimals);
+            payload.extend_from_slice(&t.max_supply.to_be_bytes());
+            payload.extend_from_slice(&supply.to_be_bytes());
+            payload.extend_from_slice(encode_address(&t.issuer, network).as_bytes());
+            payload.extend_from_slice(t.symbol.as_bytes());
+        }
+    }
+
+    let mut buf = Vec::with_capacity(7 + payload.len());
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_TOKEN);
+    buf.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&payload);
+    buf
+}
+
+/// VALIDATORS: FGX1 | 19 | len | epoch (u64) | count (u16) | count * (address (56) | stake (u64))
+pub fn make_validators_response(set: &ValidatorSet, network: &NetworkProfile) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(8 + 2 + set.validators.len() * 64);
+    payload.extend_from_slice(&set.epoch.to_be_bytes());
+    payload.extend_from_slice(&(set.validators.len() as u16).to_be_bytes());
+    for (addr, stake) in &set.validators {
+        payload.extend_from_slice(encode_address(addr, network).as_bytes());
+        payload.extend_from_slice(&stake.to_be_bytes());
+    }
+
+    let mut buf = Vec::with_capacity(7 + payload.len());
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_VALIDATORS);
+    buf.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&payload);
+    buf
+}
+
+/// ASK_VALIDATORS: FGX1 | 18 | 0
+pub fn make_ask_validators_message() -> Vec<u8> {
+    let mut buf = Vec::with_capacity(7);
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_ASK_VALIDATORS);
+    buf.extend_from_slice(&0u16.to_be_bytes());
+    buf
+}
+
+/// VALIDATORS -> (эпоха, [(адрес, стейк)])
+pub fn decode_validators_response(msg: &[u8]) -> Result<(u64, Vec<(String, u64)>), String> {
+    if msg.len() < 7 + 10 || &msg[0..4] != MAGIC || msg[4] != MSG_TYPE_VALIDATORS {
+        return Err("invalid validators response".into());
+    }
+    let payload = &msg[7..];
+    let epoch = u64::from_be_bytes(payload[0..8].try_into().unwrap());
+    let count = u16::from_be_bytes([payload[8], payload[9]]) as usize;
+    let entries = &payload[10..];
+    if entries.len() != count * 64 {
+        return Err("invalid validators response length".into());
+    }
+
+    let mut validators = Vec::with_capacity(count);
+    for entry in entries.chunks_exact(64) {
+        let address = String::from_utf8(entry[..56].to_vec()).map_err(|_| "invalid utf8 in address".to_string())?;
+        validators.push((address, u64::from_be_bytes(entry[56..].try_into().unwrap())));
+    }
+    Ok((epoch, validators))
+}
+
+/// FEES: FGX1 | 21 | len | height (u64) | base_fee (u64) | min_base_fee (u64)
+///       | target_block_txs (u32) | burn_percent (u8) | proposer_percent (u8)
+/// height — следующего блока, base_fee — его
+pub fn make_fees_response(height: u64, base_fee: u64, fees: &FeeConfig) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(30);
+    payload.extend_from_slice(&height.to_be_bytes());
+    payload.extend_from_slice(&base_fee.to_be_bytes());
+    payload.extend_from_slice(&fees.min_base_fee.to_be_bytes());
+    payload.extend_from_slice(&fees.target_block_txs.to_be_bytes());
+    payload.push(fees.burn_percent);
+    payload.push(fees.proposer_percent);
+
+    let mut buf = Vec::with_capacity(7 + payload.len());
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_FEES);
+    buf.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&payload);
+    buf
+}
+
+/// ASK_FEES: FGX1 | 20 | 0
+pub fn make_ask_fees_message() -> Vec<u8> {
+    let mut buf = Vec::with_capacity(7);
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_ASK_FEES);
+    buf.extend_from_slice(&0u16.to_be_bytes());
+    buf
+}
+
+/// FEE_ESTIMATE: FGX1 | 23 | len | ok (u8) | ...
+///   ok = 1: target_blocks (u16) | base_fee (u64) | low (u64) | medium (u64) | high (u64)
+///   ok = 0: текст ошибки
+pub fn make_fee_estimate_response(estimate: &Result<FeeEstimate, String>) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(35);
+    match estimate {
+        Ok(e) => {
+            payload.push(1);
+            payload.extend_from_slice(&e.target_blocks.to_be_bytes());
+            for v in [e.base_fee, e.low, e.medium, e.high] {
+                payload.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Err(reason) => {
+            payload.push(0);
+            payload.extend_from_slice(reason.as_bytes());
+        }
+    }
+
+    let mut buf = Vec::with_capacity(7 + payload.len());
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_FEE_ESTIMATE);
+    buf.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&payload);
+    buf
+}
+
+/// ASK_FEE_ESTIMATE: FGX1 | 22 | 2 | target_blocks (u16)
+pub fn make_ask_fee_estimate_message(target_blocks: u16) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(9);
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_ASK_FEE_ESTIMATE);
+    buf.extend_from_slice(&2u16.to_be_bytes());
+    buf.extend_from_slice(&target_blocks.to_be_bytes());
+    buf
+}
+
+/// FEE_ESTIMATE -> оценка или причина отказа
+pub fn decode_fee_estimate_response(msg: &[u8]) -> Result<FeeEstimate, String> {
+    if msg.len() < 8 || &msg[0..4] != MAGIC || msg[4] != MSG_TYPE_FEE_ESTIMATE {
+        return Err("invalid fee estimate response".into());
+    }
+    let p = &msg[7..];
+    if p[0] == 0 {
+        return Err(String::from_utf8_lossy(&p[1..]).into_owned());
+    }
+    if p.len() != 35 {
+        return Err("invalid fee estimate response length".into());
+    }
+    let at = |i: usize| u64::from_be_bytes(p[i..i + 8].try_into().unwrap());
+    Ok(FeeEstimate {
+        target_blocks: u16::from_be_bytes([p[1], p[2]]),
+        base_fee: at(3),
+        low: at(11),
+        medium: at(19),
+        high: at(27),
+    })
+}
+
+/// FEES -> (высота следующего блока, его base fee, параметры комиссий)
+pub fn decode_fees_response(msg: &[u8]) -> Result<(u64, u64, FeeConfig), String> {
+    if msg.len() != 7 + 30 || &msg[0..4] != MAGIC || msg[4] != MSG_TYPE_FEES {
+        return Err("invalid fees response".into());
+    }
+    let p = &msg[7..];
+    let height = u64::from_be_bytes(p[0..8].try_into().unwrap());
+    let base_fee = u64::from_be_bytes(p[8..16].try_into().unwrap());
+    let fees = FeeConfig {
+        min_base_fee: u64::from_be_bytes(p[16..24].try_into().unwrap()),
+        target_block_txs: u32::from_be_bytes(p[24..28].try_into().unwrap()),
+        burn_percent: p[28],
+        proposer_percent: p[29],
+    };
+    Ok((height, base_fee, fees))
+}
+
+/// ASK_TOKEN: FGX1 | 16 | len | symbol
+pub fn make_ask_token_message(symbol: &str) -> Result<Vec<u8>, String> {
+    if symbol.is_empty() || symbol.len() > u8::MAX as usize {
+        return Err("invalid token symbol".into());
+    }
+
+    let mut buf = Vec::with_capacity(7 + symbol.len());
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_ASK_TOKEN);
+    buf.extend_from_slice(&(symbol.len() as u16).to_be_bytes());
+    buf.extend_from_slice(symbol.as_bytes());
+    Ok(buf)
+}
+
+/// TOKEN -> (код, запись реестра, общий выпуск); None — токена нет.
+/// issuer разбирается как адрес сети network.
+pub fn decode_token_response(msg: &[u8], network: &NetworkProfile) -> Result<Option<(TokenId, TokenInfo, u64)>, String> {
+    if msg.len() < 8 || &msg[0..4] != MAGIC || msg[4] != MSG_TYPE_TOKEN {
+        return Err("invalid token response".into());
+    }
+    let payload = &msg[7..];
+    if payload[0] == 0 {
+        return Ok(None);
+    }
+    if payload.len() < 1 + 1 + 1 + 8 + 8 + 56 {
+        return Err("truncated token response".into());
+    }
+
+    let issuer = String::from_utf8_lossy(&payload[19..75]);
+    let info = TokenInfo {
+        symbol: String::from_utf8_lossy(&payload[75..]).into_owned(),
+        decimals: payload[2],
+        max_supply: u64::from_be_bytes(payload[3..11].try_into().unwrap()),
+        issuer: decode_address(&issuer, network)?,
+    };
+    let supply = u64::from_be_bytes(payload[11..19].try_into().unwrap());
+    Ok(Some((payload[1], info, supply)))
+}
+
+/// SEND_TX: FGX1 | 1 | len | tx_hash (32) | marker | version | raw_tx (269 + хвост по типу)
+pub fn make_send_tx_message(tx: &Tx) -> Result<Vec<u8>, String> {
+    let tx_hash = hex_to_bytes(&tx.tx_hash)?;
+    if tx_hash.len() != 32 {
+        return Err("tx_hash must be 32 bytes".into());
+    }
+    let raw = encode_tx(tx)?;
+
+    let payload_len = (tx_hash.len() + raw.len()) as u16;
+    let mut buf = Vec::with_capacity(4 + 1 + 2 + payload_len as usize);
+
+    buf.extend_from_slice(b"FGX1");
+    buf.push(1);
+    buf.extend_from_slice(&payload_len.to_be_bytes());
+    buf.extend_from_slice(&tx_hash);
+    buf.extend_from_slice(&raw);
+
+    Ok(buf)
+}
+
+/// SEND_TX_BATCH: FGX1 | 14 | len | count (u16) | count * (tx_hash (32) | конверт с raw_tx)
+pub fn make_send_tx_batch_message(txs: &[Tx]) -> Result<Vec<u8>, String> {
+    if txs.is_empty() || txs.len() > MAX_TX_BATCH {
+        return Err(format!("tx batch must hold 1..={} txs", MAX_TX_BATCH));
+    }
+
+    let mut payload = Vec::with_capacity(2 + txs.len() * 301);
+    payload.extend_from_slice(&(txs.len() as u16).to_be_bytes());
+    for tx in txs {
+        // тот же кусок, что и в SEND_TX, без заголовка
+        payload.extend_from_slice(&make_send_tx_message(tx)?[7..]);
+    }
+    if payload.len() > u16::MAX as usize {
+        return Err("tx batch too large".into());
+    }
+
+    let mut buf = Vec::with_capacity(7 + payload.len());
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_SEND_TX_BATCH);
+    buf.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&payload);
+    Ok(buf)
+}
+
+/// TX_BATCH_RESPONSE: FGX1 | 15 | len | count (u16) | count * (ok (u8) | reason_len (u16) | reason)
+/// None — транза принята, Some — причина отказа
+pub fn make_tx_batch_response(results: &[Option<String>]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&(results.len() as u16).to_be_bytes());
+    for r in results {
+        let reason = r.as_deref().unwrap_or("").as_bytes();
+        // причина длиннее 255 байт ничего не добавляет
+        let reason = &reason[..reason.len().min(255)];
+        payload.push(r.is_none() as u8);
+        payload.extend_from_slice(&(reason.len() as u16).to_be_bytes());
+        payload.extend_from_slice(reason);
+    }
+
+    let mut buf = Vec::with_capacity(7 + payload.len());
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_TX_BATCH_RESPONSE);
+    buf.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&payload);
+    buf
+}
+
+/// TX_BATCH_RESPONSE -> по транзе: None — принята, Some — причина отказа
+pub fn decode_tx_batch_response(msg: &[u8]) -> Result<Vec<Option<String>>, String> {
+    if msg.len() < 9 || &msg[0..4] != MAGIC || msg[4] != MSG_TYPE_TX_BATCH_RESPONSE {
+        return Err("invalid tx batch response".into());
+    }
+    let count = u16::from_be_bytes([msg[7], msg[8]]) as usize;
+
+    let mut cur = &msg[9..];
+    let mut out = Vec::with_capacity(count);
+    for _ in 0..count {
+        if cur.len() < 3 {
+            return Err("truncated tx batch response".into());
+        }
+        let ok = cur[0] == 1;
+        let len = u16::from_be_bytes([cur[1], cur[2]]) as usize;
+        if cur.len() < 3 + len {
+            return Err("truncated tx batch response".into());
+        }
+        let reason = String::from_utf8_lossy(&cur[3..3 + len]).into_owned();
+        out.push(if ok { None } else { Some(reason) });
+        cur = &cur[3 + len..];
+    }
+    Ok(out)
+}
+
+/// TX_RESPONSE -> "ACCEPTED" или причина отказа
+pub fn decode_tx_response(msg: &[u8]) -> Result<String, String> {
+    if msg.len() < 7 || &msg[0..4] != MAGIC || msg[4] != 2 {
+        return Err("invalid tx response".into());
+    }
+    Ok(String::from_utf8_lossy(&msg[7..]).into_owned())
+}
+
+/// ASK_NONCE: FGX1 | 5 | len | address (56)
+pub fn make_ask_nonce_message(address: &str) -> Result<Vec<u8>, String> {
+    if address.len() != 56 {
+        return Err(format!("address must be 56 bytes, got {}", address.len()));
+    }
+
+    let mut buf = Vec::with_capacity(4 + 1 + 2 + 56);
+    buf.extend_from_slice(MAGIC);
+    buf.push(5);
+    buf.extend_from_slice(&56u16.to_be_bytes());
+    buf.extend_from_slice(address.as_bytes());
+    Ok(buf)
+}
+
+/// NONCE -> nonce
+pub fn decode_nonce_response(msg: &[u8]) -> Result<u64, String> {
+    if msg.len() != 7 + 8 + 56 || &msg[0..4] != MAGIC || msg[4] != 6 {
+        return Err("invalid nonce response".into());
+    }
+    Ok(u64::from_be_bytes(msg[7..15].try_into().unwrap()))
+}
+
+/// ASK_HEIGHT: FGX1 | 10 | 0
+pub fn make_ask_height_message() -> Vec<u8> {
+    let mut buf = Vec::with_capacity(7);
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_ASK_HEIGHT);
+    buf.extend_from_slice(&0u16.to_be_bytes());
+    buf
+}
+
+/// HEIGHT -> высота последнего блока
+pub fn decode_height_response(msg: &[u8]) -> Result<u64, String> {
+    if msg.len() != 7 + 8 || &msg[0..4] != MAGIC || msg[4] != MSG_TYPE_HEIGHT {
+        return Err("invalid height response".into());
+    }
+    Ok(u64::from_be_bytes(msg[7..15].try_into().unwrap()))
+}
+
+/// SUBSCRIBE: FGX1 | 8 | len | from_height (u64) | flags (u8)
+pub fn make_subscribe_message(from_height: u64, with_receipts: bool) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(4 + 1 + 2 + 9);
+    buf.extend_from_slice(MAGIC);
+    buf.push(MSG_TYPE_SUBSCRIBE);
+    buf.extend_from_slice(&9u16.to_be_bytes());
+    buf.extend_from_slice(&from_height.to_be_bytes());
+    buf.push(if with_receipts { SUBSCRIBE_FLAG_RECEIPTS } else { 0 });
+    buf
+}
+
+/// Payload RECEIPTS -> (height, [(tx_hash, status)])
+pub fn decode_receipts_payload(payload: &[u8]) -> Result<(u64, Vec<(String, u8)>), String> {
+    if payload.len() < 12 {
+        return Err("receipts payload too short".into());
+    }
+    let height = u64::from_be_bytes(payload[0..8].try_into().unwrap());
+    let count = u32::from_be_bytes(payload[8..12].try_into().unwrap()) as usize;
+
+    let body = &payload[12..];
+    if body.len() != count * 41 {
+        return Err(format!("receipts payload size mismatch: {} entries, {} bytes", count, body.len()));
+    }
+
+    let entries = body
+        .chunks(41)
+        .map(|r| (bytes_to_hex(&r[..32]), r[32]))
+        .collect();
+
+    Ok((height, entries))
+}
+
+/// Запрос к админке: FGX1 | 12 | len | token_len | token | cmd | args
+pub fn encode_admin_request(token: &str, cmd: u8, args: &[u8]) -> Result<Vec<u8>, String> {
+    let token = token.as_bytes();
+    if token.len() > u8::MAX as usize {
+        return Err("admin token too long".into());
+    }
+
+    let payload_len = 1 + token.len() + 1 + args.len();
+    if payload_len > u16::MAX as usize {
+        return Err("admin request too large".into());
+    }
+
+    let mut buf = Vec::with_capacity(4 + 1 + 2 + payload_len);
+
+    buf.extend_from_slice(b"FGX1");
+    buf.push(MSG_TYPE_ADMIN);
+    buf.extend_from_slice(&(payload_len as u16).to_be_bytes());
+    buf.push(token.len() as u8);
+    buf.extend_from_slice(token);
+    buf.push(cmd);
+    buf.extend_from_slice(args);
+
+    Ok(buf)
+}
+
+/// Ответ админки: FGX1 | 13 | len | ok(u8) | текст
+pub fn make_admin_response(ok: bool, text: &str) -> Vec<u8> {
+    // текст обрезаем, чтобы влез в u16
+    let mut end = text.len().min(u16::MAX as usize - 1);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let text = &text.as_bytes()[..end];
+    let payload_len = (1 + text.len()) as u16;
+
+    let mut buf = Vec::with_capacity(4 + 1 + 2 + 1 + text.len());
+
+    buf.extend_from_slice(b"FGX1");
+    buf.push(MSG_TYPE_ADMIN_RESPONSE);
+    buf.extend_from_slice(&payload_len.to_be_bytes());
+    buf.push(ok as u8);
+    buf.extend_from_slice(text);
+
+    buf
+}
+
+/// Разобрать ответ админки -> (ok, текст)
+pub fn decode_admin_response(msg: &[u8]) -> Result<(bool, String), String> {
+    if msg.len() < 8 || &msg[0..4] != MAGIC || msg[4] != MSG_TYPE_ADMIN_RESPONSE {
+        return Err("invalid admin response".into());
+    }
+
+    let payload_len = u16::from_be_bytes([msg[5], msg[6]]) as usize;
+    if msg.len() != 7 + payload_len {
+        return Err("wrong length".into());
+    }
+
+    let text = String::from_utf8_lossy(&msg[8..]).into_owned();
+    Ok((msg[7] == 1, text))
+}
+
 /// Главная функция: Block -> raw_block (FGX1|type|len|payload)
 pub fn encode_block_raw(block: &Block) -> Result<Vec<u8>, String> {
     // ---------- Сначала собираем payload ----------
@@ -363,6 +1132,17 @@ pub fn encode_block_raw(block: &Block) -> Result<Vec<u8>, String> {
         payload.extend_from_slice(&tx_bytes);
     }
 
+    // ---------- Награды: count (u16) | count * [to (str_u16) | kind (u8) | amount (u64)] ----------
+    if block.body.rewards.len() > u16::MAX as usize {
+        return Err("too many rewards in block".into());
+    }
+    payload.extend_from_slice(&(block.body.rewards.len() as u16).to_be_bytes());
+    for r in &block.body.rewards {
+        write_str_u16(&mut payload, &r.to)?;
+        payload.push(r.kind);
+        payload.extend_from_slice(&r.amount.to_be_bytes());
+    }
+
     // ---------- Теперь оборачиваем в FGX1|msg_type|len|payload ----------
     let payload_len = payload.len();
     if payload_len > u32::MAX as usize {
@@ -386,6 +1166,37 @@ pub fn encode_block_raw(block: &Block) -> Result<Vec<u8>, String> {
     Ok(out)
 }
 
+/// Квитанции блока -> raw (FGX1|9|u32 len|payload)
+///
+/// payload: height (u64) | count (u32) | count * [tx_hash(32) | status(1) | fee(u64)]
+pub fn encode_receipts_raw(height: u64, receipts: &[Receipt]) -> Result<Vec<u8>, String> {
+    let mut payload = Vec::with_capacity(12 + receipts.len() * 41);
+
+    payload.extend_from_slice(&height.to_be_bytes());
+    payload.extend_from_slice(&(receipts.len() as u32).to_be_bytes());
+
+    for r in receipts {
+        let tx_hash_bytes = hex_to_bytes(&r.tx_hash)?;
+        if tx_hash_bytes.len() != 32 {
+            return Err(format!(
+                "tx_hash must be 32 bytes, got {}",
+                tx_hash_bytes.len()
+            ));
+        }
+        payload.extend_from_slice(&tx_hash_bytes);
+        payload.push(r.status);
+        payload.extend_from_slice(&r.fee.to_be_bytes());
+    }
+
+    let mut out = Vec::with_capacity(4 + 1 + 4 + payload.len());
+    out.extend_from_slice(MAGIC);
+    out.push(MSG_TYPE_RECEIPTS);
+    out.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    out.extend_from_slice(&payload);
+
+    Ok(out)
+}
+
 //
 // ===== ХЕЛПЕРЫ =====
 //
@@ -410,7 +1221,7 @@ fn write_str_u16(buf: &mut Vec<u8>, s: &str) -> Result<(), String> {
 /// Преобразование hex-строки в байты
 fn hex_to_bytes(s: &str) -> Result<Vec<u8>, String> {
     let s = s.trim();
-    if s.len() % 2 != 0 {
+    if !s.len().is_multiple_of(2) {
         return Err(format!("hex string has odd length: {}", s.len()));
     }
 
@@ -439,7 +1250,16 @@ fn hex_val(c: char) -> Result<u8, String> {
 fn encode_tx_type(s: &str) -> Result<u8, String> {
     match s {
         "transfer" => Ok(1),
-        // сюда потом добавишь другие типы ("mint" => 2, "stake" => 3, и т.д.)
+        "create_token" => Ok(2),
+        "mint" => Ok(3),
+        "burn" => Ok(4),
+        "multi_send" => Ok(5),
+        "multisig_transfer" => Ok(6),
+        "vesting_transfer" => Ok(7),
+        "stake" => Ok(8),
+        "unstake" => Ok(9),
+        "delegate" => Ok(10),
+        // сюда потом добавишь другие типы ("mint", "stake" и т.д.)
         other => Err(format!("unknown tx_type: {}", other)),
     }
 }
@@ -493,5 +1313,48 @@ fn encode_tx_for_block(tx: &ValidTxCore) -> Result<Vec<u8>, String> {
     }
     buf.extend_from_slice(&sig_bytes);
 
+    // 7) поля по типу: create_token — decimals (u8) | initial_supply (u64);
+    //    multi_send — count (u16) | count * (to (str u16) | amount (u64));
+    //    multisig_transfer — threshold (u8) | n (u8) | n * pubkey (33) | m (u8) | m * (index (u8) | sig (65));
+    //    vesting_transfer — clock (u8) | start (u64) | cliff (u64) | duration (u64)
+    match &tx.extra {
+        TxExtra::None => {}
+        TxExtra::CreateToken { decimals, initial_supply } => {
+            buf.push(*decimals);
+            buf.extend_from_slice(&initial_supply.to_be_bytes());
+        }
+        TxExtra::MultiSend(payouts) => {
+            if payouts.len() > u16::MAX as usize {
+                return Err("too many multi_send recipients".into());
+            }
+            buf.extend_from_slice(&(payouts.len() as u16).to_be_bytes());
+            for (to, amount) in payouts {
+                write_str_u16(&mut buf, to)?;
+                buf.extend_from_slice(&amount.to_be_bytes());
+            }
+        }
+        TxExtra::Multisig { threshold, pubkeys, signatures } => {
+            buf.push(*threshold);
+            buf.push(pubkeys.len() as u8);
+            for pk in pubkeys {
+                buf.extend_from_slice(&hex_to_bytes(pk)?);
+            }
+            buf.push(signatures.len() as u8);
+            for (index, sig) in signatures {
+                buf.push(*index);
+                buf.extend_from_slice(&hex_to_bytes(sig)?);
+            }
+        }
+        TxExtra::Vesting(v) => {
+            buf.push(v.clock.to_byte());
+            buf.extend_from_slice(&v.start.to_be_bytes());
+            buf.extend_from_slice(&v.cliff.to_be_bytes());
+            buf.extend_from_slice(&v.duration.to_be_bytes());
+        }
+    }
+
+    // 8) memo (строка с префиксом длины u16, пустая — без memo)
+    write_str_u16(&mut buf, &tx.memo)?;
+
     Ok(buf)
 }
@@ -0,0 +1,260 @@
+//! Мультисиг-аккаунты M-of-N.
+//!
+//! Адрес — тот же формат, что у ключа, но со своим hrp (`multisig_hrp` профиля,
+//! в devnet — gms2), а ядро не из pubkey, а
+//! sha256("forgex-multisig" | threshold | n | pubkeys по возрастанию)[..20].
+//! Тратит с него транза multisig_transfer: в хвосте — threshold, все N pubkey
+//! и подписи (индекс ключа + r|s|v). Базовые pubkey/signature у неё нулевые.
+//!
+//! Подписи собираются вне сети: raw tx в hex ходит от подписанта к подписанту,
+//! каждый добавляет свою (`forgex_node multisig sign`), последний отправляет ноде.
+
+use crate::address::{AddrKey, encode_address};
+use crate::clock::{Clock, SystemClock};
+use crate::config::NodeConfig;
+use crate::model::{
+    Tx, decode_token_response, decode_tx_response, make_ask_token_message, make_send_tx_message, parse_raw_tx,
+};
+use crate::network::NetworkProfile;
+use crate::p2p::p2p_request;
+use crate::state::TokenId;
+use crate::tx::{TxExtra, build_raw_tx_from_struct, bytes_to_hex, hex_to_bytes, sha256};
+use crate::wallet::Wallet;
+
+/// Сколько ключей максимум в мультисиге
+pub const MAX_MULTISIG_KEYS: usize = 16;
+
+/// pubkey и signature в базе raw tx у multisig_transfer — нули
+pub const ZERO_PUBKEY_HEX: &str = "000000000000000000000000000000000000000000000000000000000000000000";
+pub const ZERO_SIGNATURE_HEX: &str = "0000000000000000000000000000000000000000000000000000000000000000\
+                                      0000000000000000000000000000000000000000000000000000000000000000\
+                                      00";
+
+/// threshold в 1..=n, n в 1..=MAX_MULTISIG_KEYS, ключи по 33 байта строго по возрастанию
+pub fn check_policy(threshold: u8, pubkeys: &[Vec<u8>]) -> Result<(), String> {
+    if pubkeys.is_empty() || pubkeys.len() > MAX_MULTISIG_KEYS {
+        return Err(format!("multisig must have 1..={} pubkeys", MAX_MULTISIG_KEYS));
+    }
+    if threshold == 0 || threshold as usize > pubkeys.len() {
+        return Err(format!("multisig threshold must be 1..={}", pubkeys.len()));
+    }
+    if pubkeys.iter().any(|pk| pk.len() != 33) {
+        return Err("multisig pubkey must be 33 bytes".into());
+    }
+    if pubkeys.windows(2).any(|w| w[0] >= w[1]) {
+        return Err("multisig pubkeys must be sorted and unique".into());
+    }
+    Ok(())
+}
+
+/// Адрес мультисига в сети; порядок ключей на входе не важен
+pub fn multisig_address(threshold: u8, pubkeys: &[Vec<u8>], network: &NetworkProfile) -> Result<String, String> {
+    let mut sorted = pubkeys.to_vec();
+    sorted.sort();
+    check_policy(threshold, &sorted)?;
+
+    let mut material = Vec::with_capacity(15 + 2 + 33 * sorted.len());
+    material.extend_from_slice(b"forgex-multisig");
+    material.push(threshold);
+    material.push(sorted.len() as u8);
+    for pk in &sorted {
+        material.extend_from_slice(pk);
+    }
+
+    let mut core = [0u8; 20];
+    core.copy_from_slice(&sha256(&material)[..20]);
+    Ok(encode_address(&AddrKey::multisig(core), network))
+}
+
+/// Неподписанный multisig_transfer с адреса (threshold, pubkeys).
+/// Подписи добавляет add_signature, tx_hash пересчитывается каждый раз.
+#[allow(clippy::too_many_arguments)]
+pub fn new_multisig_transfer(
+    network: &NetworkProfile,
+    threshold: u8,
+    pubkeys: &[Vec<u8>],
+    token: &str,
+    token_id: TokenId,
+    to: &str,
+    amount: u64,
+    fee: u64,
+    nonce: u64,
+    timestamp: u64,
+) -> Result<Tx, String> {
+    let from = multisig_address(threshold, pubkeys, network)?;
+    let mut sorted = pubkeys.to_vec();
+    sorted.sort();
+
+    let mut tx = Tx {
+        tx_hash: String::new(),
+        domain_tag: "GLD_TX_v1".into(),
+        chain_id: network.chain_id.into(),
+        tx_type: "multisig_transfer".into(),
+        from,
+        to: to.to_string(),
+        token: token.to_string(),
+        token_id,
+        amount,
+        fee,
+        nonce,
+        timestamp,
+        pubkey: ZERO_PUBKEY_HEX.into(),
+        signature: ZERO_SIGNATURE_HEX.into(),
+        encoding: "pipe_v1".into(),
+        extra: TxExtra::Multisig {
+            threshold,
+            pubkeys: sorted.iter().map(|pk| bytes_to_hex(pk)).collect(),
+            signatures: Vec::new(),
+        },
+        memo: String::new(),
+    };
+    tx.tx_hash = bytes_to_hex(&sha256(&build_raw_tx_from_struct(&tx)?));
+    Ok(tx)
+}
+
+/// Добавить подпись кошелька в multisig_transfer (на место его ключа)
+pub fn add_signature(tx: &mut Tx, wallet: &Wallet) -> Result<(), String> {
+    let signature = wallet.sign_pipe(tx)?;
+    let my_pubkey = bytes_to_hex(&wallet.pubkey);
+
+    let TxExtra::Multisig { pubkeys, signatures, .. } = &mut tx.extra else {
+        return Err("not a multisig_transfer".into());
+    };
+    let index = pubkeys
+        .iter()
+        .position(|pk| *pk == my_pubkey)
+        .ok_or_else(|| "wallet is not a signer of this multisig".to_string())? as u8;
+
+    match signatures.binary_search_by_key(&index, |(i, _)| *i) {
+        Ok(_) => return Err("wallet already signed this tx".into()),
+        Err(pos) => signatures.insert(pos, (index, signature)),
+    }
+
+    tx.tx_hash = bytes_to_hex(&sha256(&build_raw_tx_from_struct(tx)?));
+    Ok(())
+}
+
+/// Сколько подписей собрано и сколько нужно
+pub fn signature_progress(tx: &Tx) -> Option<(usize, u8)> {
+    match &tx.extra {
+        TxExtra::Multisig { threshold, signatures, .. } => Some((signatures.len(), *threshold)),
+        _ => None,
+    }
+}
+
+// ─────────────────────── CLI ───────────────────────
+
+const MULTISIG_USAGE: &str = "usage: forgex_node multisig \
+    <address <threshold> <pubkey>...\
+    |new <threshold> <pubkey,pubkey,...> <to> <token> <amount> <fee> <nonce>\
+    |sign <token> <raw_hex>\
+    |submit <raw_hex>>\n\
+    secret for sign: FORGEX_MULTISIG_SECRET, FORGEX_MULTISIG_KEY_FILE or stdin";
+
+/// `forgex_node multisig <команда> ...` — сбор подписей вне сети.
+/// Сеть (адреса, chain_id) — из конфига ноды; new и submit ходят к ноде
+/// по его p2p_addr, address и sign работают офлайн.
+pub async fn run_multisig_cli(args: &[String], cfg: &NodeConfig) -> Result<(), String> {
+    let network = cfg.profile();
+    let name = args.first().ok_or_else(|| MULTISIG_USAGE.to_string())?;
+    let args: Vec<&str> = args[1..].iter().map(String::as_str).collect();
+
+    match (name.as_str(), args.as_slice()) {
+        ("address", [threshold, pubkeys @ ..]) => {
+            let pubkeys = pubkeys.iter().map(|pk| hex_to_bytes(pk)).collect::<Result<Vec<_>, _>>()?;
+            println!("{}", multisig_address(parse_num(threshold)?, &pubkeys, network)?);
+            Ok(())
+        }
+
+        ("new", [threshold, pubkeys, to, token, amount, fee, nonce]) => {
+            let pubkeys = pubkeys.split(',').map(hex_to_bytes).collect::<Result<Vec<_>, _>>()?;
+            let token_id = ask_token_code(cfg, token).await?;
+            let tx = new_multisig_transfer(
+                network,
+                parse_num(threshold)?,
+                &pubkeys,
+                token,
+                token_id,
+                to,
+                parse_num(amount)?,
+                parse_num(fee)?,
+                parse_num(nonce)?,
+                SystemClock.now_ms(),
+            )?;
+            eprintln!("multisig_transfer from {}, 0/{} signatures", tx.from, threshold);
+            println!("{}", bytes_to_hex(&build_raw_tx_from_struct(&tx)?));
+            Ok(())
+        }
+
+        // символ токена подписант указывает сам: в raw tx только код,
+        // а подписывается символ — так он видит, что именно одобряет
+        ("sign", [token, raw]) => {
+            let wallet = Wallet::from_secret(&hex_to_bytes(&read_signer_secret()?)?, network)?;
+            let mut tx = parse_raw_tx(&hex_to_bytes(raw)?)?;
+            tx.token = token.to_string();
+            add_signature(&mut tx, &wallet)?;
+
+            let (have, need) = signature_progress(&tx).unwrap_or_default();
+            eprintln!(
+                "{} {} {} -> {}, {}/{} signatures",
+                tx.tx_type, tx.amount, tx.token, tx.to, have, need
+            );
+            println!("{}", bytes_to_hex(&build_raw_tx_from_struct(&tx)?));
+            Ok(())
+        }
+
+        ("submit", [raw]) => {
+            let raw = hex_to_bytes(raw)?;
+            let mut tx = parse_raw_tx(&raw)?;
+            tx.tx_hash = bytes_to_hex(&sha256(&raw));
+
+            let response = p2p_request(&cfg.p2p_addr, &make_send_tx_message(&tx)?)
+                .await
+                .map_err(|e| format!("failed to reach node at {}: {}", cfg.p2p_addr, e))?;
+            let status = decode_tx_response(&response)?;
+            if status == "ACCEPTED" {
+                println!("{} {}", status, tx.tx_hash);
+                Ok(())
+            } else {
+                Err(status)
+            }
+        }
+
+        _ => Err(MULTISIG_USAGE.into()),
+    }
+}
+
+/// Код токена из реестра ноды
+async fn ask_token_code(cfg: &NodeConfig, symbol: &str) -> Result<TokenId, String> {
+    let response = p2p_request(&cfg.p2p_addr, &make_ask_token_message(symbol)?)
+        .await
+        .map_err(|e| format!("failed to reach node at {}: {}", cfg.p2p_addr, e))?;
+    decode_token_response(&response, cfg.profile())?
+        .map(|(code, _, _)| code)
+        .ok_or_else(|| format!("unknown token {}", symbol))
+}
+
+/// Секрет подписанта — не из argv (его видно в ps и истории shell):
+/// FORGEX_MULTISIG_SECRET, файл из FORGEX_MULTISIG_KEY_FILE или первая строка stdin
+fn read_signer_secret() -> Result<String, String> {
+    if let Ok(secret) = std::env::var("FORGEX_MULTISIG_SECRET") {
+        return Ok(secret.trim().to_string());
+    }
+    if let Ok(path) = std::env::var("FORGEX_MULTISIG_KEY_FILE") {
+        return std::fs::read_to_string(&path)
+            .map(|s| s.trim().to_string())
+            .map_err(|e| format!("failed to read key file {}: {}", path, e));
+    }
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .map_err(|e| format!("failed to read secret from stdin: {}", e))?;
+    match line.trim() {
+        "" => Err("no signer secret: set FORGEX_MULTISIG_SECRET or FORGEX_MULTISIG_KEY_FILE, or pipe it to stdin".into()),
+        secret => Ok(secret.to_string()),
+    }
+}
+
+fn parse_num<T: std::str::FromStr>(s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("invalid number: {}", s))
+}
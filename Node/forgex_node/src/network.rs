@@ -0,0 +1,99 @@
+//! Профили сетей: devnet, testnet, mainnet.
+//!
+//! Профиль — всё, чем сети отличаются друг от друга: chain_id (входит в подпись,
+//! так что транза одной сети в другой не пройдёт), HRP адресов ключей и мультисигов, генезис казны,
+//! порты по умолчанию и параметры протокола. Нода выбирает профиль по
+//! `network` в конфиге, loadgen и devnet — флагом --network.
+//!
+//! Глобального профиля нет: нода берёт свой из конфига и держит в Node и
+//! ChainState, кошелёк — в Wallet. Так в одном процессе уживаются ноды разных сетей.
+
+use crate::config::{FeeConfig, RewardConfig};
+use crate::state::TREASURY_INITIAL_GLD;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NetworkProfile {
+    pub name: &'static str,
+    /// Ровно 9 байт — столько под chain_id в raw tx
+    pub chain_id: &'static str,
+    /// Ровно 4 символа: bech32 hrp и первые 4 байта payload адреса
+    pub hrp: &'static str,
+    /// hrp мультисиг-аккаунтов, тоже 4 символа: по нему адрес мультисига
+    /// не спутать с адресом ключа
+    pub multisig_hrp: &'static str,
+    /// Генезис: сколько GLD у казны, если в конфиге нет [[genesis]]
+    pub treasury_gld: u64,
+    /// Порты по умолчанию (на 127.0.0.1)
+    pub p2p_port: u16,
+    /// Админка: отдельный слушатель, только на localhost
+    pub admin_port: u16,
+    pub metrics_port: u16,
+    pub indexer_port: u16,
+    pub block_interval_ms: u64,
+    pub max_block_txs: usize,
+    pub fees: FeeConfig,
+    pub rewards: RewardConfig,
+}
+
+/// Локальная сеть для разработки (её же по умолчанию берёт нода без конфига)
+pub const DEVNET: NetworkProfile = NetworkProfile {
+    name: "devnet",
+    chain_id: "gld-dev-1",
+    hrp: "gld2",
+    multisig_hrp: "gms2",
+    treasury_gld: TREASURY_INITIAL_GLD,
+    p2p_port: 5050,
+    admin_port: 5051,
+    metrics_port: 9100,
+    indexer_port: 9000,
+    block_interval_ms: 2000,
+    max_block_txs: 25,
+    // те же значения, что FeeConfig/RewardConfig по умолчанию до профилей;
+    // min_base_fee продублирован в Wallet/main.js (NETWORKS)
+    fees: FeeConfig { min_base_fee: 10, target_block_txs: 12, burn_percent: 50, proposer_percent: 25 },
+    rewards: RewardConfig { block_reward: 1_000_000, halving_interval: 1_000_000 },
+};
+
+/// Публичная тестовая сеть: параметры как у mainnet, порты свои
+pub const TESTNET: NetworkProfile = NetworkProfile {
+    name: "testnet",
+    chain_id: "gld-test1",
+    hrp: "gldt",
+    multisig_hrp: "gmst",
+    treasury_gld: TREASURY_INITIAL_GLD,
+    p2p_port: 6050,
+    admin_port: 6051,
+    metrics_port: 6100,
+    indexer_port: 6000,
+    block_interval_ms: 5000,
+    max_block_txs: 100,
+    fees: FeeConfig { min_base_fee: 100, target_block_txs: 50, burn_percent: 50, proposer_percent: 25 },
+    rewards: RewardConfig { block_reward: 1_000_000, halving_interval: 4_000_000 },
+};
+
+pub const MAINNET: NetworkProfile = NetworkProfile {
+    name: "mainnet",
+    chain_id: "gld-main1",
+    hrp: "gldm",
+    multisig_hrp: "gmsm",
+    treasury_gld: TREASURY_INITIAL_GLD,
+    p2p_port: 7050,
+    admin_port: 7051,
+    metrics_port: 7100,
+    indexer_port: 7000,
+    block_interval_ms: 5000,
+    max_block_txs: 100,
+    fees: FeeConfig { min_base_fee: 100, target_block_txs: 50, burn_percent: 50, proposer_percent: 25 },
+    rewards: RewardConfig { block_reward: 1_000_000, halving_interval: 4_000_000 },
+};
+
+pub const NETWORKS: &[&NetworkProfile] = &[&DEVNET, &TESTNET, &MAINNET];
+
+pub fn network_by_name(name: &str) -> Option<&'static NetworkProfile> {
+    NETWORKS.iter().copied().find(|n| n.name == name)
+}
+
+/// Имена сетей через запятую (для сообщений об ошибках)
+pub fn network_names() -> String {
+    NETWORKS.iter().map(|n| n.name).collect::<Vec<_>>().join(", ")
+}
@@ -1,104 +1,224 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex, RwLock};
 use tokio::time::{sleep, Duration};
+use tracing::{error, info_span};
+
+use crate::block::{Block, BlockHeader, BlockBody, BlockStore};
+use crate::clock::{Clock, SystemClock};
+use crate::config::NodeConfig;
+use crate::control::NodeControl;
+use crate::events::{NodeEvent, NodeHook, emit};
+use crate::mempool::{Mempool, mempool_get_payable, mempool_remove_by_hash};
+use crate::metrics::NodeMetrics;
+use crate::shutdown::Shutdown;
+use crate::network::NetworkProfile;
+use crate::state::{ChainState, StateDiff, apply_block, block_info, block_rewards, select_applicable};
+use crate::subscribe::{Feed, new_feed};
+use crate::verify::VerifyPool;
+
+// ─────────────────────── НОДА ───────────────────────
+
+/// Одна нода со всем своим состоянием.
+/// В одном процессе можно держать сколько угодно нод — они ничего не делят.
+pub struct Node {
+    /// Действующий конфиг. Меняется при reload из админки.
+    pub config: RwLock<NodeConfig>,
+    /// Сеть ноды (из конфига при старте; reload её не меняет)
+    pub network: &'static NetworkProfile,
+    pub mempool: Mutex<Mempool>,
+    pub state: Mutex<ChainState>,
+    pub blocks: Mutex<BlockStore>,
+    /// Хуки в порядке регистрации
+    pub hooks: RwLock<Vec<Arc<dyn NodeHook>>>,
+    /// Лента блоков для подписчиков
+    pub feed: Feed,
+    pub control: NodeControl,
+    pub shutdown: Shutdown,
+    pub metrics: NodeMetrics,
+    /// Проверка входящих транз вне воркеров Tokio
+    pub verifier: VerifyPool,
+    /// Откуда нода берёт "сейчас" (timestamp блоков, проверка транз)
+    pub clock: Arc<dyn Clock>,
+}
+
+impl Node {
+    /// Нода на генезисе: пустой мемпул, пустое хранилище, без хуков
+    pub fn new(cfg: NodeConfig) -> Self {
+        Self::with_clock(cfg, Arc::new(SystemClock))
+    }
+
+    /// То же, но со своими часами (симулятор)
+    pub fn with_clock(cfg: NodeConfig, clock: Arc<dyn Clock>) -> Self {
+        let network = cfg.profile();
+        Node {
+            network,
+            mempool: Mutex::new(Mempool::new()),
+            state: Mutex::new(ChainState::genesis(network, &cfg.genesis, &cfg.fees, &cfg.rewards)),
+            blocks: Mutex::new(BlockStore::new(&cfg.blocks_log_path)),
+            hooks: RwLock::new(Vec::new()),
+            feed: new_feed(),
+            control: NodeControl::default(),
+            shutdown: Shutdown::default(),
+            metrics: NodeMetrics::default(),
+            verifier: VerifyPool::new(cfg.verify_threads, cfg.verify_queue),
+            clock,
+            config: RwLock::new(cfg),
+        }
+    }
+
+    /// Снимок текущего конфига
+    pub fn config(&self) -> NodeConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    pub fn set_config(&self, cfg: NodeConfig) {
+        *self.config.write().unwrap() = cfg;
+    }
+}
 
-use crate::mempool::{MEMPOOL, mempool_get_top, mempool_remove_by_hash};
-use crate::state::{apply_block, block_info};
-use crate::block::{Block, BlockHeader, BlockBody, save_block};
-use crate::p2p::{p2p_send};
-use crate::model::{encode_block_raw};
+// ─────────────────────── ЦИКЛ НОДЫ ───────────────────────
 
 /// Запускает цикл ноды:
-/// каждые 2 секунды берёт до 25 транзакций из мемпула,
-/// делает блок, применяет его к state и сохраняет в txt.
-pub async fn run_node_loop() {
+/// раз в block_interval_ms берёт до max_block_txs транзакций из мемпула,
+/// делает блок и применяет его к state.
+/// Всё остальное (хранилище, индексер, подписчики) — через хуки.
+/// Админка может поставить цикл на паузу или попросить блок вне очереди.
+/// При остановке ноды выходит между блоками: блок собирается и применяется
+/// без await, так что он либо сделан целиком, либо не начат.
+pub async fn run_node_loop(node: Arc<Node>) {
     loop {
-        // ждём 2 секунды между попытками
-        sleep(Duration::from_secs(2)).await;
+        let interval = node.config().block_interval_ms;
 
-        // ---- Берём транзы из мемпула (до 25) ----
-        let txs = {
-            let mp = MEMPOOL.lock().unwrap();
-            mempool_get_top(&mp, 25)
+        // ждём интервал между попытками или принудительный блок
+        let forced = tokio::select! {
+            _ = sleep(Duration::from_millis(interval)) => false,
+            _ = node.control.force_block_requested() => true,
+            _ = node.shutdown.requested() => return,
         };
 
-        if txs.is_empty() {
-            // ничего нет — просто пропускаем
-            // println!("[NODE] mempool empty, skip");
+        if node.control.is_paused() && !forced {
             continue;
         }
 
-        // ---- Определяем height и prev_hash ----
-        let (prev_height, prev_hash) = block_info().unwrap_or((0, "0".repeat(64)));
-        let height = prev_height + 1;
-
-        // ---- Делаем tx_root и block_id (пока простые строки) ----
-        let tx_root = make_tx_root_string(&txs);
-        let timestamp_ms = current_timestamp_ms();
-        let block_id = make_block_id_string(height, &tx_root, timestamp_ms);
-
-        // ---- Собираем блок ----
-        let header = BlockHeader {
-            version: "0.1".to_string(),
-            chain_id: "gld-dev-1".to_string(),
-            height,
-            prev_hash,
-            timestamp_ms,
-            tx_count: txs.len() as u32,
-            tx_root: tx_root.clone(),
-        };
-
-        let body = BlockBody { txs: txs.clone() };
+        produce_block(&node, forced);
+    }
+}
 
-        let block = Block {
-            block_id: block_id.clone(),
-            header,
-            body,
-        };
+/// Один шаг цикла: собрать блок из мемпула и применить его.
+/// Без транз блок делается только принудительно (forced).
+/// Возвращает применённый блок.
+pub fn produce_block(node: &Node, forced: bool) -> Option<Block> {
+    // ---- Берём транзы из мемпула: только те, что покрывают base fee ----
+    let base_fee = node.state.lock().unwrap().base_fee;
+    let txs = {
+        let mp = node.mempool.lock().unwrap();
+        mempool_get_payable(&mp, node.config().max_block_txs, base_fee)
+    };
 
-        // ---- Применяем к state ----
-        match apply_block(&block) {
-            Ok(()) => {
-                println!("[NODE] applied block height={}, hash={}", height, block_id);
-            }
-            Err(e) => {
-                println!("[NODE] FAILED apply_block on height {}: {}", height, e);
-                // если стейт не применился, блок дальше не сохраняем и транзы не удаляем
-                continue;
-            }
+    // ---- Определяем height, prev_hash и время: по ним ещё и открывается вестинг ----
+    let (prev_height, prev_hash) =
+        block_info(&node.state.lock().unwrap()).unwrap_or((0, "0".repeat(64)));
+    let height = prev_height + 1;
+    let timestamp_ms = node.clock.now_ms() as i64;
+
+    // ---- Отсеиваем то, что не применится: иначе блок не соберётся никогда ----
+    let (txs, failed, fees) = select_applicable(&mut node.state.lock().unwrap(), txs, height, timestamp_ms as u64);
+    if !failed.is_empty() {
+        let mut mp = node.mempool.lock().unwrap();
+        for (tx, _) in &failed {
+            mempool_remove_by_hash(&mut mp, &tx.tx_hash);
         }
+    }
+    for (tx, reason) in failed {
+        emit(node, NodeEvent::TxEvicted { tx_hash: tx.tx_hash, reason });
+    }
 
-        let raw_block = encode_block_raw(&block);
-        match raw_block {
-            Ok(ref bytes) => {
-                println!("[NODE] encoded block to {} bytes", bytes.len());
-
-                if let Err(e) = p2p_send("127.0.0.1:9000", bytes).await {
-                    eprintln!("[NODE] FAILED to send block to indexer: {}", e);
-                }
-            }
-            Err(ref e) => {
-                println!("[NODE] FAILED encode_block_raw: {}", e);
-                continue;
-            }
+    if txs.is_empty() && !forced {
+        // ничего нет — просто пропускаем (принудительный блок может быть пустым)
+        return None;
+    }
+
+    // всё, что ниже, логируется внутри span с высотой блока
+    let _span = info_span!("block", height).entered();
+
+    // ---- Делаем tx_root и block_id (пока простые строки) ----
+    let tx_root = make_tx_root_string(&txs);
+    let block_id = make_block_id_string(height, &tx_root, timestamp_ms);
+
+    // ---- Собираем блок ----
+    let header = BlockHeader {
+        version: "0.1".to_string(),
+        chain_id: node.network.chain_id.to_string(),
+        height,
+        prev_hash,
+        timestamp_ms,
+        tx_count: txs.len() as u32,
+        tx_root: tx_root.clone(),
+    };
+
+    // ---- Награды: выпуск за блок и доля комиссий на наш адрес ----
+    let rewards = block_rewards(&node.state.lock().unwrap(), height, &node.config().proposer(), fees);
+
+    let body = BlockBody { txs, rewards };
+
+    let block = Block {
+        block_id: block_id.clone(),
+        header,
+        body,
+    };
+
+    // ---- Применяем к state ----
+    let applied = apply_block(&mut node.state.lock().unwrap(), &block);
+    let diff = match applied {
+        Ok(diff) => diff,
+        Err(e) => {
+            error!(error = %e, "failed to apply block");
+            // если стейт не применился, блок дальше не сохраняем и транзы не удаляем
+            return None;
         }
+    };
 
+    // блок, который не применился, никому не показываем
+    emit(node, NodeEvent::BlockProduced { block: block.clone() });
+    finish_block(node, &block, diff);
+
+    Some(block)
+}
 
-        // ---- Удаляем использованные транзы из мемпула ----
-        {
-            let mut mp = MEMPOOL.lock().unwrap();
-            for tx in txs {
-                mempool_remove_by_hash(&mut mp, &tx.tx_hash);
-            }
+/// Принять чужой блок (от производителя) и применить его.
+/// Блок должен идти ровно следующим за нашим последним.
+/// Транзы внутри не перепроверяются: производитель один и ему доверяем.
+pub fn import_block(node: &Node, block: &Block) -> Result<(), String> {
+    let height = block.header.height;
+    let _span = info_span!("block", height).entered();
+
+    let diff = {
+        let mut state = node.state.lock().unwrap();
+
+        let (prev_height, prev_hash) = block_info(&state).unwrap_or((0, "0".repeat(64)));
+        if height != prev_height + 1 {
+            return Err(format!("expected block {}, got {}", prev_height + 1, height));
         }
-    }
+        if block.header.prev_hash != prev_hash {
+            return Err(format!("prev_hash mismatch at height {}", height));
+        }
+
+        apply_block(&mut state, block)?
+    };
+
+    finish_block(node, block, diff);
+    Ok(())
 }
 
-/// Текущий timestamp в миллисекундах Unix.
-fn current_timestamp_ms() -> i64 {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("time went backwards");
-    now.as_millis() as i64
+/// Общий хвост для своих и чужих блоков: события + чистка мемпула
+fn finish_block(node: &Node, block: &Block, diff: StateDiff) {
+    emit(node, NodeEvent::StateChanged { height: block.header.height, diff });
+    emit(node, NodeEvent::BlockApplied { block: block.clone() });
+
+    // ---- Удаляем использованные транзы из мемпула ----
+    let mut mp = node.mempool.lock().unwrap();
+    for tx in &block.body.txs {
+        mempool_remove_by_hash(&mut mp, &tx.tx_hash);
+    }
 }
 
 /// Простейший "tx_root": конкатенируем все tx_hash в одну строку.
@@ -1,60 +1,248 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use anyhow::Result;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::timeout;
+use tracing::{Instrument, debug, error, info, info_span, warn};
+
+use crate::events::{NodeEvent, NodeHook};
+use crate::model::{Decoded, MSG_TYPE_SUBSCRIBE, decode_message, encode_block_raw};
+use crate::node::Node;
+use crate::subscribe::serve_subscriber;
+
+/// Сколько ждём хвост кадра, если он пришёл не целиком
+const FRAME_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Ответ обработчика (обработка может ждать, например, пул верификации)
+pub type HandlerFuture<'a> = Pin<Box<dyn Future<Output = Vec<u8>> + Send + 'a>>;
+
+/// Тип обработчика: нода + сырые байты сообщения -> ответ в байтах
+pub type Handler = for<'a> fn(&'a Node, Vec<u8>) -> HandlerFuture<'a>;
 
 /// handler: функция, которая принимает сырые байты сообщения и возвращает ответ в байтах
+/// При остановке ноды перестаёт принимать соединения,
+/// а после close_peers() ждёт, пока все открытые закроются.
 pub async fn run_p2p_server(
+    node: Arc<Node>,
     addr: &str,
-    handler: fn(Vec<u8>) -> Vec<u8>,
+    handler: Handler,
 ) -> Result<()> {
+    serve(node, addr, "P2P", handler, true).await
+}
+
+/// Админка: свой слушатель на loopback, без подписок на блоки.
+/// Что пускать дальше, решает handler (см. admin::admin_handler).
+pub async fn run_admin_server(node: Arc<Node>, addr: &str, handler: Handler) -> Result<()> {
+    serve(node, addr, "admin", handler, false).await
+}
+
+/// subscribe: можно ли превратить соединение в поток блоков
+async fn serve(node: Arc<Node>, addr: &str, name: &'static str, handler: Handler, subscribe: bool) -> Result<()> {
     let listener = TcpListener::bind(addr).await?;
-    println!("P2P node listening on {}", addr);
+    info!(addr = %addr, "{} listening", name);
+
+    let mut connections = JoinSet::new();
 
     loop {
-        let (socket, peer_addr) = listener.accept().await?;
-        println!("Incoming connection from {}", peer_addr);
+        let (socket, peer_addr) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = node.shutdown.requested() => break,
+        };
+
+        // заодно чистим завершившиеся таски
+        while connections.try_join_next().is_some() {}
 
-        // на каждое соединение — отдельная таска
-        tokio::spawn(handle_connection(socket, handler));
+        // на каждое соединение — отдельная таска со своим span
+        let span = info_span!("peer", peer = %peer_addr);
+        let node = node.clone();
+        connections.spawn(
+            async move {
+                debug!("incoming connection");
+                node.metrics.p2p_connection_opened();
+                handle_connection(&node, socket, handler, subscribe).await;
+                node.metrics.p2p_connection_closed();
+            }
+            .instrument(span),
+        );
     }
+
+    drop(listener);
+    info!(open = connections.len(), "{} listener closed, waiting for peers", name);
+
+    while connections.join_next().await.is_some() {}
+    info!("all {} connections closed", name);
+
+    Ok(())
 }
 
-async fn handle_connection(mut stream: TcpStream, handler: fn(Vec<u8>) -> Vec<u8>) {
+async fn handle_connection(node: &Node, mut stream: TcpStream, handler: Handler, subscribe: bool) {
     let mut buf = vec![0u8; 4096];
 
     loop {
-        match stream.read(&mut buf).await {
+        let read = tokio::select! {
+            read = stream.read(&mut buf) => read,
+            _ = node.shutdown.peers_closing() => {
+                debug!("closing connection on shutdown");
+                let _ = stream.shutdown().await;
+                return;
+            }
+        };
+
+        match read {
             Ok(0) => {
-                println!("Peer disconnected");
+                debug!("peer disconnected");
                 return; // выходим из задачи
             }
             Ok(n) => {
                 let mut msg = buf[..n].to_vec();
-                println!("Received {} bytes", n);
+                match timeout(FRAME_READ_TIMEOUT, read_rest_of_frame(&mut stream, &mut msg)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        warn!(error = %e, "socket read error");
+                        return;
+                    }
+                    Err(_) => {
+                        warn!("timed out waiting for the rest of the frame");
+                        return;
+                    }
+                }
+                debug!(bytes = msg.len(), "received message");
+
+                // SUBSCRIBE забирает соединение целиком под поток блоков
+                if subscribe && msg.len() > 4 && msg[4] == MSG_TYPE_SUBSCRIBE {
+                    match decode_message(&msg) {
+                        Ok(Decoded::Subscribe { from_height, with_receipts }) => {
+                            serve_subscriber(node, stream, from_height, with_receipts).await;
+                            return;
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            warn!(error = %e, "failed to decode message");
+                            let _ = stream.write_all(b"ERR").await;
+                            continue;
+                        }
+                    }
+                }
 
-                let response = handler(msg);
+                let response = handler(node, msg).await;
 
                 if let Err(e) = stream.write_all(&response).await {
-                    eprintln!("Failed to send response: {}", e);
+                    warn!(error = %e, "failed to send response");
                     return;
                 }
             }
             Err(e) => {
-                eprintln!("Socket read error: {}", e);
+                warn!(error = %e, "socket read error");
                 return;
             }
         }
     }
 }
 
+/// Кадр FGX1 (u16 длина) может прийти не одним read: добираем до конца.
+/// Не-FGX1 байты оставляем как есть — декодер ответит ошибкой.
+async fn read_rest_of_frame(stream: &mut TcpStream, msg: &mut Vec<u8>) -> std::io::Result<()> {
+    if msg.len() < 7 || &msg[0..4] != b"FGX1" {
+        return Ok(());
+    }
+    let total = 7 + u16::from_be_bytes([msg[5], msg[6]]) as usize;
+    if msg.len() < total {
+        let have = msg.len();
+        msg.resize(total, 0);
+        stream.read_exact(&mut msg[have..]).await?;
+    }
+    Ok(())
+}
+
 pub async fn p2p_send(addr: &str, data: &[u8]) -> Result<()> {
     let mut stream = TcpStream::connect(addr).await?;
 
     // отправляем весь пакет
     stream.write_all(data).await?;
 
-    println!("Sent {} bytes to {}", data.len(), addr);
+    debug!(bytes = data.len(), addr = %addr, "sent message");
     stream.shutdown().await?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Отправить запрос и дождаться одного ответа FGX1 (u16 длина)
+pub async fn p2p_request(addr: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(addr).await?;
+    stream.write_all(data).await?;
+
+    let mut header = [0u8; 7];
+    stream.read_exact(&mut header).await?;
+    if &header[0..4] != b"FGX1" {
+        anyhow::bail!("invalid response magic");
+    }
+
+    let payload_len = u16::from_be_bytes([header[5], header[6]]) as usize;
+    let mut msg = header.to_vec();
+    msg.resize(7 + payload_len, 0);
+    stream.read_exact(&mut msg[7..]).await?;
+
+    Ok(msg)
+}
+
+/// Хук: каждый применённый блок пушим индексеру.
+/// Адрес берём из текущего конфига (меняется через reload).
+/// Отправка идёт в отдельной таске, цикл ноды не ждёт сеть.
+#[derive(Default)]
+pub struct IndexerPushHook {
+    /// Незавершённые отправки (ждём их при остановке)
+    pushes: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl IndexerPushHook {
+    /// Дождаться, пока уйдут все блоки индексеру, но не дольше wait
+    pub async fn wait_pending(&self, wait: Duration) {
+        let pushes: Vec<_> = self.pushes.lock().unwrap().drain(..).collect();
+        if pushes.is_empty() {
+            return;
+        }
+
+        let n = pushes.len();
+        let all = async {
+            for push in pushes {
+                let _ = push.await;
+            }
+        };
+
+        if timeout(wait, all).await.is_err() {
+            warn!(pending = n, "gave up waiting for indexer pushes");
+        }
+    }
+}
+
+impl NodeHook for IndexerPushHook {
+    fn on_event(&self, node: &Node, event: &NodeEvent) {
+        let NodeEvent::BlockApplied { block } = event else {
+            return;
+        };
+
+        let bytes = match encode_block_raw(block) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!(height = block.header.height, error = %e, "failed to encode block");
+                return;
+            }
+        };
+
+        let addr = node.config().indexer_addr;
+        let push = tokio::spawn(async move {
+            if let Err(e) = p2p_send(&addr, &bytes).await {
+                warn!(addr = %addr, error = %e, "failed to send block to indexer");
+            }
+        });
+
+        let mut pushes = self.pushes.lock().unwrap();
+        pushes.retain(|p| !p.is_finished());
+        pushes.push(push);
+    }
+}
@@ -0,0 +1,128 @@
+use tokio::sync::watch;
+use tracing::{error, info};
+
+use crate::block::flush_block_store;
+use crate::mempool::dump_mempool;
+use crate::node::Node;
+use crate::state::dump_state;
+
+/// Стадии остановки ноды, идут строго по порядку
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Running,
+    /// Новые транзы и соединения не принимаем, цикл ноды доделывает блок и выходит
+    Draining,
+    /// Блоков больше не будет — закрываем соединения с пирами
+    Closing,
+}
+
+/// Остановка одной ноды: все её таски смотрят на общую стадию
+pub struct Shutdown {
+    phase: watch::Sender<Phase>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown { phase: watch::Sender::new(Phase::Running) }
+    }
+}
+
+impl Shutdown {
+    fn advance(&self, phase: Phase) {
+        self.phase.send_if_modified(|current| {
+            if *current < phase {
+                *current = phase;
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    async fn reached(&self, phase: Phase) {
+        let mut rx = self.phase.subscribe();
+        // Sender живёт, пока жива нода, так что ошибки тут не бывает
+        let _ = rx.wait_for(|current| *current >= phase).await;
+    }
+
+    /// Начать остановку (сигнал, падение P2P и т.п.)
+    pub fn request(&self) {
+        self.advance(Phase::Draining);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        *self.phase.borrow() >= Phase::Draining
+    }
+
+    /// Дождаться начала остановки
+    pub async fn requested(&self) {
+        self.reached(Phase::Draining).await;
+    }
+
+    /// Сказать соединениям закрываться (после остановки цикла ноды)
+    pub fn close_peers(&self) {
+        self.advance(Phase::Closing);
+    }
+
+    /// Дождаться команды закрыть соединения
+    pub async fn peers_closing(&self) {
+        self.reached(Phase::Closing).await;
+    }
+}
+
+/// Ждём SIGINT (Ctrl-C) или SIGTERM и запускаем остановку
+pub async fn wait_for_signal(node: &Node) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        let mut term = match signal(SignalKind::terminate()) {
+            Ok(s) => s,
+            Err(e) => {
+                error!(error = %e, "failed to install SIGTERM handler");
+                let _ = tokio::signal::ctrl_c().await;
+                info!("SIGINT received, shutting down");
+                node.shutdown.request();
+                return;
+            }
+        };
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("SIGINT received, shutting down"),
+            _ = term.recv() => info!("SIGTERM received, shutting down"),
+            _ = node.shutdown.requested() => return,
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("Ctrl-C received, shutting down"),
+            _ = node.shutdown.requested() => return,
+        }
+    }
+
+    node.shutdown.request();
+}
+
+/// Сбросить на диск всё, что живёт в памяти:
+/// лог блоков (fsync), снимок state и мемпула.
+/// Ошибки только логируем — остановку всё равно доводим до конца.
+pub fn flush_to_disk(node: &Node) {
+    let cfg = node.config();
+
+    match flush_block_store(&node.blocks.lock().unwrap()) {
+        Ok(()) => info!("block store flushed"),
+        Err(e) => error!(error = %e, "failed to flush block store"),
+    }
+
+    match dump_state(&node.state.lock().unwrap(), &cfg.state_snapshot_path) {
+        Ok(n) => info!(path = %cfg.state_snapshot_path, entries = n, "state snapshot written"),
+        Err(e) => error!(error = %e, "failed to write state snapshot"),
+    }
+
+    match dump_mempool(&node.mempool.lock().unwrap(), node.network, &cfg.mempool_snapshot_path) {
+        Ok(n) => info!(path = %cfg.mempool_snapshot_path, txs = n, "mempool snapshot written"),
+        Err(e) => error!(error = %e, "failed to write mempool snapshot"),
+    }
+}
@@ -0,0 +1,480 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::block::{Block, BlockStoreHook, blocks_from};
+use crate::clock::{Clock, VirtualClock};
+use crate::config::{GenesisBalance, NodeConfig};
+use crate::events::register_hook;
+use crate::handler::handle_message;
+use crate::model::make_send_tx_message;
+use crate::network::DEVNET;
+use crate::node::{Node, import_block, produce_block};
+use crate::state::{block_info, state_lines};
+use crate::tx::bytes_to_hex;
+use crate::wallet::Wallet;
+
+// ─────────────────────── НАСТРОЙКИ ───────────────────────
+
+/// Шаг виртуального времени
+const TICK_MS: u64 = 10;
+
+/// Старт виртуальных часов (чтобы timestamp транз выглядели живыми)
+const START_MS: u64 = 1_700_000_000_000;
+
+/// Сколько блоков максимум отдаём за один SyncResponse
+const SYNC_BATCH: usize = 100;
+
+/// Стартовый баланс каждого тестового аккаунта
+const ACCOUNT_BALANCE: u64 = 1_000_000_000_000;
+
+/// Сценарий симуляции. Один и тот же seed — тот же прогон до байта.
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub seed: u64,
+    /// Нода 0 — производитель блоков, остальные принимают блоки от неё
+    pub nodes: usize,
+    pub accounts: usize,
+    /// Время под нагрузкой
+    pub duration_ms: u64,
+    /// Время после нагрузки: сеть без потерь и разделений, все догоняют
+    pub settle_ms: u64,
+    pub block_interval_ms: u64,
+    pub txs_per_sec: u64,
+    /// Задержка доставки, равномерно в [min, max]
+    pub latency_ms: (u64, u64),
+    /// Доля потерянных сообщений между нодами (0.0..1.0)
+    pub drop_rate: f64,
+    pub partitions: Vec<Partition>,
+}
+
+/// На отрезке [start_ms, end_ms) ноды из разных групп не слышат друг друга.
+/// Нода, которой нет ни в одной группе, отрезана от всех.
+#[derive(Debug, Clone)]
+pub struct Partition {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub groups: Vec<Vec<usize>>,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        SimConfig {
+            seed: 1,
+            nodes: 4,
+            accounts: 16,
+            duration_ms: 10_000,
+            settle_ms: 5_000,
+            block_interval_ms: 500,
+            txs_per_sec: 40,
+            latency_ms: (5, 50),
+            drop_rate: 0.0,
+            partitions: Vec::new(),
+        }
+    }
+}
+
+/// Готовые сценарии (forgex_sim и тесты гоняют одни и те же)
+pub const SCENARIOS: &[&str] = &["calm", "lossy", "partition"];
+
+/// Сценарий name поверх base: сеть, потери, разделения. None — нет такого.
+pub fn scenario(name: &str, base: &SimConfig) -> Option<SimConfig> {
+    let mut cfg = base.clone();
+    match name {
+        "calm" => {}
+        "lossy" => {
+            cfg.drop_rate = cfg.drop_rate.max(0.2);
+            cfg.latency_ms = (cfg.latency_ms.0, cfg.latency_ms.1.max(300));
+        }
+        "partition" => {
+            // половина нод без производителя на трети времени теряет связь
+            let half = cfg.nodes / 2;
+            cfg.partitions.push(Partition {
+                start_ms: cfg.duration_ms / 3,
+                end_ms: cfg.duration_ms * 2 / 3,
+                groups: vec![(0..half.max(1)).collect(), (half.max(1)..cfg.nodes).collect()],
+            });
+        }
+        _ => return None,
+    }
+    Some(cfg)
+}
+
+/// Итог прогона
+#[derive(Debug, Clone)]
+pub struct SimReport {
+    /// (height, digest state) по каждой ноде
+    pub nodes: Vec<(u64, String)>,
+    pub txs_submitted: u64,
+    pub txs_accepted: u64,
+    pub messages_sent: u64,
+    pub messages_dropped: u64,
+    /// Хеш всего, что происходило в сети, — для проверки воспроизводимости
+    pub trace_digest: String,
+}
+
+impl SimReport {
+    /// Все ноды пришли к одной высоте и одному state
+    pub fn agreed(&self) -> bool {
+        self.nodes.windows(2).all(|w| w[0] == w[1])
+    }
+}
+
+// ─────────────────────── ГСЧ ───────────────────────
+
+/// SplitMix64: маленький, быстрый и одинаковый на всех платформах
+struct SimRng(u64);
+
+impl SimRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Случайное число в [lo, hi]
+    fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next_u64() % (hi - lo + 1)
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+}
+
+// ─────────────────────── СЕТЬ ───────────────────────
+
+enum SimMessage {
+    /// FGX1 SEND_TX, как его шлёт кошелёк
+    Tx(Vec<u8>),
+    Block(Block),
+    /// Производитель сообщает свою высоту (чтобы отставшие узнали о дырке)
+    Status { height: u64 },
+    SyncRequest { from_height: u64 },
+    SyncResponse(Vec<Block>),
+}
+
+impl SimMessage {
+    /// Короткая запись для trace
+    fn trace(&self) -> String {
+        match self {
+            SimMessage::Tx(msg) => format!("tx:{}", bytes_to_hex(&msg[7..39])),
+            SimMessage::Block(b) => format!("block:{}", b.header.height),
+            SimMessage::Status { height } => format!("status:{}", height),
+            SimMessage::SyncRequest { from_height } => format!("sync_req:{}", from_height),
+            SimMessage::SyncResponse(blocks) => format!("sync_resp:{}", blocks.len()),
+        }
+    }
+}
+
+struct Envelope {
+    from: usize,
+    to: usize,
+    msg: SimMessage,
+}
+
+struct SimNetwork {
+    /// (время доставки, порядковый номер) -> сообщение
+    queue: BTreeMap<(u64, u64), Envelope>,
+    seq: u64,
+    sent: u64,
+    dropped: u64,
+}
+
+// ─────────────────────── СИМУЛЯТОР ───────────────────────
+
+struct Sim {
+    cfg: SimConfig,
+    rng: SimRng,
+    clock: Arc<VirtualClock>,
+    nodes: Vec<Arc<Node>>,
+    wallets: Vec<Wallet>,
+    next_nonce: Vec<u64>,
+    net: SimNetwork,
+    trace: Sha256,
+    txs_submitted: u64,
+    txs_accepted: u64,
+}
+
+/// Прогнать сценарий от начала до конца
+pub fn run_simulation(cfg: &SimConfig) -> SimReport {
+    let mut sim = Sim::new(cfg.clone());
+    sim.run();
+    sim.report()
+}
+
+impl Sim {
+    fn new(cfg: SimConfig) -> Self {
+        let clock = Arc::new(VirtualClock::new(START_MS));
+
+        let wallets: Vec<Wallet> = (0..cfg.accounts as u64)
+            .map(|i| Wallet::from_seed(cfg.seed, i, &DEVNET))
+            .collect();
+
+        let node_cfg = NodeConfig {
+            block_interval_ms: cfg.block_interval_ms,
+            // блоки только в памяти — каждая нода сама по себе
+            blocks_log_path: String::new(),
+            genesis: wallets
+                .iter()
+                .map(|w| GenesisBalance {
+                    address: w.address.clone(),
+                    token: "GLD".into(),
+                    amount: ACCOUNT_BALANCE,
+                })
+                .collect(),
+            ..NodeConfig::for_network(&DEVNET)
+        };
+
+        let nodes = (0..cfg.nodes)
+            .map(|_| {
+                let node = Arc::new(Node::with_clock(node_cfg.clone(), clock.clone()));
+                register_hook(&node, Arc::new(BlockStoreHook));
+                node
+            })
+            .collect();
+
+        Sim {
+            rng: SimRng(cfg.seed),
+            next_nonce: vec![1; wallets.len()],
+            clock,
+            nodes,
+            wallets,
+            net: SimNetwork {
+                queue: BTreeMap::new(),
+                seq: 0,
+                sent: 0,
+                dropped: 0,
+            },
+            trace: Sha256::new(),
+            txs_submitted: 0,
+            txs_accepted: 0,
+            cfg,
+        }
+    }
+
+    /// Виртуальное время от старта симуляции
+    fn elapsed(&self) -> u64 {
+        self.clock.now_ms() - START_MS
+    }
+
+    fn run(&mut self) {
+        let total = self.cfg.duration_ms + self.cfg.settle_ms;
+
+        while self.elapsed() < total {
+            self.clock.advance(TICK_MS);
+            let now = self.elapsed();
+
+            self.deliver_due(now);
+
+            if now <= self.cfg.duration_ms {
+                self.submit_txs();
+            }
+
+            if now.is_multiple_of(self.cfg.block_interval_ms) {
+                self.producer_tick();
+            }
+        }
+
+        // добиваем то, что ещё в полёте
+        while let Some((&(at, _), _)) = self.net.queue.first_key_value() {
+            self.deliver_due(at);
+        }
+    }
+
+    // ---- нагрузка ----
+
+    fn submit_txs(&mut self) {
+        let per_tick = self.cfg.txs_per_sec * TICK_MS;
+        let mut count = per_tick / 1000;
+        if self.rng.chance((per_tick % 1000) as f64 / 1000.0) {
+            count += 1;
+        }
+
+        for _ in 0..count {
+            if self.wallets.len() < 2 {
+                return;
+            }
+
+            let from = self.rng.range(0, self.wallets.len() as u64 - 1) as usize;
+            let mut to = self.rng.range(0, self.wallets.len() as u64 - 2) as usize;
+            if to >= from {
+                to += 1;
+            }
+
+            let amount = self.rng.range(10, 1000);
+            let fee = self.rng.range(10, 100);
+            let nonce = self.next_nonce[from];
+            self.next_nonce[from] += 1;
+
+            let to_addr = self.wallets[to].address.clone();
+            let msg = match self.wallets[from]
+                .transfer(&to_addr, amount, fee, nonce, self.clock.now_ms())
+                .and_then(|tx| make_send_tx_message(&tx))
+            {
+                Ok(msg) => msg,
+                Err(e) => {
+                    warn!(error = %e, "failed to build sim tx");
+                    continue;
+                }
+            };
+
+            // клиент стучится в случайную ноду, та раздаёт транзу остальным
+            let entry = self.rng.range(0, self.nodes.len() as u64 - 1) as usize;
+            self.txs_submitted += 1;
+            self.record(entry, entry, &SimMessage::Tx(msg.clone()).trace());
+
+            let response = handle_message(&self.nodes[entry], msg.clone());
+            if response.ends_with(b"ACCEPTED") {
+                self.txs_accepted += 1;
+                self.broadcast(entry, || SimMessage::Tx(msg.clone()));
+            } else {
+                debug!(node = entry, response = %String::from_utf8_lossy(&response), "sim tx rejected");
+            }
+        }
+    }
+
+    fn producer_tick(&mut self) {
+        let producer = self.nodes[0].clone();
+
+        if let Some(block) = produce_block(&producer, false) {
+            self.broadcast(0, || SimMessage::Block(block.clone()));
+        }
+
+        let height = local_height(&producer);
+        self.broadcast(0, || SimMessage::Status { height });
+    }
+
+    // ---- сеть ----
+
+    fn broadcast(&mut self, from: usize, make: impl Fn() -> SimMessage) {
+        for to in 0..self.nodes.len() {
+            if to != from {
+                self.send(from, to, make());
+            }
+        }
+    }
+
+    fn send(&mut self, from: usize, to: usize, msg: SimMessage) {
+        let now = self.elapsed();
+        self.net.sent += 1;
+
+        // после нагрузки сеть честная — иначе догнать нельзя
+        let lossy = now <= self.cfg.duration_ms;
+        if lossy && (self.partitioned(now, from, to) || self.rng.chance(self.cfg.drop_rate)) {
+            self.net.dropped += 1;
+            return;
+        }
+
+        let (lo, hi) = self.cfg.latency_ms;
+        let deliver_at = now + self.rng.range(lo, hi.max(lo));
+
+        self.net.seq += 1;
+        self.net.queue.insert((deliver_at, self.net.seq), Envelope { from, to, msg });
+    }
+
+    fn partitioned(&self, now: u64, a: usize, b: usize) -> bool {
+        self.cfg
+            .partitions
+            .iter()
+            .filter(|p| p.start_ms <= now && now < p.end_ms)
+            .any(|p| {
+                let group_of = |n: usize| p.groups.iter().position(|g| g.contains(&n));
+                match (group_of(a), group_of(b)) {
+                    (Some(ga), Some(gb)) => ga != gb,
+                    _ => true,
+                }
+            })
+    }
+
+    fn deliver_due(&mut self, now: u64) {
+        while let Some(entry) = self.net.queue.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            let (at, _) = *entry.key();
+            let env = entry.remove();
+            self.record(env.from, env.to, &format!("{}@{}", env.msg.trace(), at));
+            self.handle(env);
+        }
+    }
+
+    /// Что делает нода, получив сообщение
+    fn handle(&mut self, env: Envelope) {
+        let node = self.nodes[env.to].clone();
+        let local = local_height(&node);
+
+        match env.msg {
+            SimMessage::Tx(msg) => {
+                handle_message(&node, msg);
+            }
+            SimMessage::Block(block) => {
+                let height = block.header.height;
+                if height == local + 1 {
+                    if let Err(e) = import_block(&node, &block) {
+                        warn!(node = env.to, error = %e, "sim block import failed");
+                    }
+                } else if height > local + 1 {
+                    self.send(env.to, env.from, SimMessage::SyncRequest { from_height: local + 1 });
+                }
+            }
+            SimMessage::Status { height } => {
+                if height > local {
+                    self.send(env.to, env.from, SimMessage::SyncRequest { from_height: local + 1 });
+                }
+            }
+            SimMessage::SyncRequest { from_height } => {
+                let mut blocks = blocks_from(&node.blocks.lock().unwrap(), from_height);
+                blocks.truncate(SYNC_BATCH);
+                if !blocks.is_empty() {
+                    self.send(env.to, env.from, SimMessage::SyncResponse(blocks));
+                }
+            }
+            SimMessage::SyncResponse(blocks) => {
+                for block in blocks {
+                    if block.header.height != local_height(&node) + 1 {
+                        continue;
+                    }
+                    if let Err(e) = import_block(&node, &block) {
+                        warn!(node = env.to, error = %e, "sim block import failed");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, from: usize, to: usize, what: &str) {
+        self.trace.update(format!("{} {}->{} {}\n", self.elapsed(), from, to, what).as_bytes());
+    }
+
+    fn report(self) -> SimReport {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let state = node.state.lock().unwrap();
+                let height = block_info(&state).map(|(h, _)| h).unwrap_or(0);
+                let digest = bytes_to_hex(&Sha256::digest(state_lines(&state).join("\n").as_bytes()));
+                (height, digest)
+            })
+            .collect();
+
+        SimReport {
+            nodes,
+            txs_submitted: self.txs_submitted,
+            txs_accepted: self.txs_accepted,
+            messages_sent: self.net.sent,
+            messages_dropped: self.net.dropped,
+            trace_digest: bytes_to_hex(&self.trace.finalize()),
+        }
+    }
+}
+
+fn local_height(node: &Node) -> u64 {
+    block_info(&node.state.lock().unwrap()).map(|(h, _)| h).unwrap_or(0)
+}
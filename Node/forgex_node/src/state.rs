@@ -1,17 +1,297 @@
 use std::collections::HashMap;
-use std::sync::Mutex;
-use crate::block::Block;
-use once_cell::sync::Lazy;
+use crate::address::{AddrKey, decode_address, encode_address};
+use crate::block::{Block, REWARD_KIND_FEES, REWARD_KIND_ISSUANCE, Reward};
+use crate::config::{FeeConfig, GenesisBalance, RewardConfig};
+use crate::network::NetworkProfile;
+use crate::tx::{TxExtra, ValidTxCore};
+use tracing::{debug, error, warn};
 
-use crate::tx::ValidTxCore;
+/// Казна в виде ключа state (строка адреса зависит от сети)
+pub const TREASURY: AddrKey = AddrKey::key([
+    0x9e, 0x6d, 0x21, 0x3f, 0xda, 0x2b, 0x7e, 0xb3, 0xbb, 0x63,
+    0x9e, 0xe1, 0x2d, 0x48, 0x4f, 0x7c, 0xa1, 0x97, 0x00, 0xfa,
+]);
 
-/// Казна
-pub const TREASURY_ADDR: &str =
-    "gld21vakxgv57d5snlk3t06emkcu7uyk5snmu5xtsp7sjtasx69tqawy";
+/// Адрес казны в сети (в devnet — gld21vakxgv57d5snlk3t06emkcu7uyk5snmu5xtsp7sjtasx69tqawy)
+pub fn treasury_addr(network: &NetworkProfile) -> String {
+    encode_address(&TREASURY, network)
+}
 
 /// 1_000_000_000 GLD в минимальных юнитах (если у тебя 6 знаков после запятой)
 pub const TREASURY_INITIAL_GLD: u64 = 1_000_000_000_000_000; // 1e9 * 1e6
 
+// ─────────────────────── ТОКЕНЫ ───────────────────────
+
+/// Код токена в state (тот же байт, что в raw tx)
+pub type TokenId = u8;
+
+/// 0 в raw tx — токена ещё нет (create_token)
+pub const NO_TOKEN: TokenId = 0;
+
+pub const GLD: TokenId = 1;
+
+/// Символ GLD; комиссии всегда платятся в нём
+pub const GLD_SYMBOL: &str = "GLD";
+
+/// Максимум знаков после запятой у токена
+pub const MAX_TOKEN_DECIMALS: u8 = 18;
+
+/// Запись реестра токенов
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+    pub max_supply: u64,
+    pub issuer: AddrKey,
+}
+
+/// Реестр токенов. Код = позиция + 1, коды раздаются по порядку
+/// и не переиспользуются; GLD заведён с генезиса под кодом 1.
+#[derive(Debug, Clone)]
+pub struct TokenRegistry {
+    tokens: Vec<TokenInfo>,
+}
+
+impl Default for TokenRegistry {
+    fn default() -> Self {
+        TokenRegistry {
+            tokens: vec![TokenInfo {
+                symbol: GLD_SYMBOL.into(),
+                decimals: 6,
+                // у GLD потолка нет, всё выпущено в генезисе
+                max_supply: u64::MAX,
+                issuer: TREASURY,
+            }],
+        }
+    }
+}
+
+impl TokenRegistry {
+    pub fn get(&self, id: TokenId) -> Option<&TokenInfo> {
+        (id as usize).checked_sub(1).and_then(|i| self.tokens.get(i))
+    }
+
+    /// Символ -> код; None — такого токена нет
+    pub fn id_of(&self, symbol: &str) -> Option<TokenId> {
+        self.tokens
+            .iter()
+            .position(|t| t.symbol == symbol)
+            .map(|i| (i + 1) as TokenId)
+    }
+
+    /// Код -> символ (для дампов и ответов)
+    pub fn symbol(&self, id: TokenId) -> Option<&str> {
+        self.get(id).map(|t| t.symbol.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Все токены по возрастанию кода
+    pub fn iter(&self) -> impl Iterator<Item = (TokenId, &TokenInfo)> {
+        self.tokens.iter().enumerate().map(|(i, t)| ((i + 1) as TokenId, t))
+    }
+
+    /// Завести токен, вернуть его код
+    fn register(&mut self, info: TokenInfo) -> Result<TokenId, String> {
+        if self.id_of(&info.symbol).is_some() {
+            return Err(format!("token {} already exists", info.symbol));
+        }
+        if self.tokens.len() >= TokenId::MAX as usize {
+            return Err("token registry is full".into());
+        }
+        self.tokens.push(info);
+        Ok(self.tokens.len() as TokenId)
+    }
+
+    /// Откатить реестр до len записей
+    fn truncate(&mut self, len: usize) {
+        self.tokens.truncate(len);
+    }
+}
+
+/// Символ токена: 2..=8 знаков A-Z0-9, первый — буква
+pub fn check_token_symbol(symbol: &str) -> Result<(), String> {
+    if !(2..=8).contains(&symbol.len()) {
+        return Err("token symbol must be 2..=8 chars".into());
+    }
+    if !symbol.starts_with(|c: char| c.is_ascii_uppercase())
+        || !symbol.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        return Err("token symbol must be A-Z0-9 and start with a letter".into());
+    }
+    Ok(())
+}
+
+// ─────────────────────── ВЕСТИНГ ───────────────────────
+
+/// Сколько залоков максимум на один (address, token)
+pub const MAX_VESTING_LOCKS: usize = 64;
+
+/// По чему считается разлок: высота блока или его timestamp (мс)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VestingClock {
+    Height,
+    Time,
+}
+
+impl VestingClock {
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0 => Some(VestingClock::Height),
+            1 => Some(VestingClock::Time),
+            _ => None,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        match self {
+            VestingClock::Height => 0,
+            VestingClock::Time => 1,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VestingClock::Height => "height",
+            VestingClock::Time => "time",
+        }
+    }
+}
+
+/// До start + cliff не открыто ничего, потом линейно от start до start + duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub clock: VestingClock,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+impl VestingSchedule {
+    pub fn check(&self) -> Result<(), String> {
+        if self.duration == 0 {
+            return Err("vesting duration must be > 0".into());
+        }
+        if self.cliff > self.duration {
+            return Err("vesting cliff cannot be longer than duration".into());
+        }
+        if self.start.checked_add(self.duration).is_none() {
+            return Err("vesting end overflows".into());
+        }
+        Ok(())
+    }
+
+    /// Сколько из total открыто к блоку (height, timestamp_ms)
+    pub fn unlocked(&self, total: u64, height: u64, timestamp_ms: u64) -> u64 {
+        let now = match self.clock {
+            VestingClock::Height => height,
+            VestingClock::Time => timestamp_ms,
+        };
+        if now < self.start.saturating_add(self.cliff) {
+            return 0;
+        }
+        let passed = now - self.start;
+        if passed >= self.duration {
+            return total;
+        }
+        (total as u128 * passed as u128 / self.duration as u128) as u64
+    }
+}
+
+/// Залоченная часть баланса от одного vesting_transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VestingLock {
+    pub schedule: VestingSchedule,
+    pub total: u64,
+    /// Сколько уже переведено на обычный баланс
+    pub released: u64,
+}
+
+// ─────────────────────── СТЕЙКИНГ ───────────────────────
+
+/// Длина эпохи в блоках: набор валидаторов пересчитывается в конце блока,
+/// высота которого кратна EPOCH_LENGTH
+pub const EPOCH_LENGTH: u64 = 100;
+
+/// Через сколько блоков после unstake GLD вернётся на свободный баланс
+pub const UNBONDING_BLOCKS: u64 = 1000;
+
+/// Сколько валидаторов максимум в активном наборе
+pub const MAX_VALIDATORS: usize = 21;
+
+/// Сколько незакрытых unstake максимум на адрес
+pub const MAX_UNBONDING_ENTRIES: usize = 32;
+
+/// Активный набор валидаторов на эпоху
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidatorSet {
+    /// Номер эпохи (height / EPOCH_LENGTH блока, где набор посчитан)
+    pub epoch: u64,
+    /// (валидатор, весь стейк на нём) по убыванию стейка
+    pub validators: Vec<(AddrKey, u64)>,
+}
+
+// ─────────────────────── КОМИССИИ ───────────────────────
+
+/// За блок base fee меняется не больше чем на 1/BASE_FEE_CHANGE_DENOMINATOR
+pub const BASE_FEE_CHANGE_DENOMINATOR: u64 = 8;
+
+/// base fee следующего блока по base fee текущего и числу транз в нём:
+/// блок полнее target_block_txs — растёт, пустее — падает, но не ниже min_base_fee
+pub fn next_base_fee(base_fee: u64, tx_count: usize, fees: &FeeConfig) -> u64 {
+    let base = base_fee as u128;
+    let used = tx_count as u128;
+    let target = fees.target_block_txs.max(1) as u128;
+    let denom = BASE_FEE_CHANGE_DENOMINATOR as u128;
+
+    let next = if used > target {
+        // хотя бы +1, иначе маленький base fee не сдвинется с места
+        base + (base * (used - target) / target / denom).max(1)
+    } else {
+        base - base * (target - used) / target / denom
+    };
+    next.clamp(fees.min_base_fee as u128, u64::MAX as u128) as u64
+}
+
+/// Как делится fee транзы: (сгорает, производителю, в казну).
+/// Чаевые сверх base fee целиком идут производителю.
+pub fn split_fee(fee: u64, base_fee: u64, fees: &FeeConfig) -> (u64, u64, u64) {
+    let base = base_fee.min(fee);
+    let burned = (base as u128 * fees.burn_percent as u128 / 100) as u64;
+    let proposer = (base as u128 * fees.proposer_percent as u128 / 100) as u64 + (fee - base);
+    (burned, proposer, fee - burned - proposer)
+}
+
+/// Выпуск за блок height: block_reward, поделённый пополам за каждый
+/// прошедший halving_interval
+pub fn issuance_at(rewards: &RewardConfig, height: u64) -> u64 {
+    if rewards.halving_interval == 0 {
+        return rewards.block_reward;
+    }
+    let halvings = height.saturating_sub(1) / rewards.halving_interval;
+    if halvings >= 64 {
+        0
+    } else {
+        rewards.block_reward >> halvings
+    }
+}
+
+/// Награды производителю proposer за блок height: выпуск и fees — его доля
+/// комиссий применённых транз (её возвращают select_applicable и apply_txs).
+/// Нулевые не пишем.
+pub fn block_rewards(state: &ChainState, height: u64, proposer: &str, fees: u64) -> Vec<Reward> {
+    [(REWARD_KIND_ISSUANCE, issuance_at(&state.rewards, height)), (REWARD_KIND_FEES, fees)]
+        .into_iter()
+        .filter(|(_, amount)| *amount > 0)
+        .map(|(kind, amount)| Reward { to: proposer.to_string(), kind, amount })
+        .collect()
+}
 
 // ─────────────────────── ВНУТРЕННЕЕ СОСТОЯНИЕ ───────────────────────
 
@@ -21,122 +301,826 @@ pub struct BlockMeta {
     pub hash: String,
 }
 
+/// Итоговые значения всего, что тронул блок
+#[derive(Debug, Clone, Default)]
+pub struct StateDiff {
+    /// (address, token, новый баланс)
+    pub balances: Vec<(AddrKey, TokenId, u64)>,
+    /// (address, новый nonce)
+    pub nonces: Vec<(AddrKey, u64)>,
+    /// Коды токенов, заведённых блоком
+    pub tokens: Vec<TokenId>,
+    /// (token, новый общий выпуск)
+    pub supply: Vec<(TokenId, u64)>,
+    /// (address, token, сколько осталось залочено)
+    pub locked: Vec<(AddrKey, TokenId, u64)>,
+    /// (делегатор, валидатор, новый стейк)
+    pub bonds: Vec<(AddrKey, AddrKey, u64)>,
+    /// Новый набор валидаторов, если блок закрыл эпоху
+    pub validator_set: Option<ValidatorSet>,
+    /// base fee следующего блока
+    pub base_fee: u64,
+}
+
 #[derive(Debug)]
 pub struct ChainState {
-    /// Балансы по (address, token)
-    pub balances: HashMap<(String, String), u64>,
+    /// Сеть: по ней адреса разбираются и собираются в строки
+    pub network: &'static NetworkProfile,
+    /// Свободные балансы по (address, token)
+    pub balances: HashMap<(AddrKey, TokenId), u64>,
+    /// Залоченное вестингом по (address, token); открытое переезжает в balances
+    pub vesting: HashMap<(AddrKey, TokenId), Vec<VestingLock>>,
     /// Nonce по адресу (один nonce на адрес)
-    pub nonces: HashMap<String, u64>,
+    pub nonces: HashMap<AddrKey, u64>,
+    /// Стейк GLD по (делегатор, валидатор); у самого валидатора делегатор = он сам
+    pub bonds: HashMap<(AddrKey, AddrKey), u64>,
+    /// Выводимое из стейка: (высота, с которой GLD свободен, сумма)
+    pub unbonding: HashMap<AddrKey, Vec<(u64, u64)>>,
+    /// Активный набор валидаторов текущей эпохи
+    pub validator_set: ValidatorSet,
+    /// Реестр токенов
+    pub tokens: TokenRegistry,
+    /// Общий выпуск по токену (всё на балансах, в залоках и в стейке)
+    pub supply: HashMap<TokenId, u64>,
+    /// Параметры комиссий из генезиса
+    pub fees: FeeConfig,
+    /// График выпуска из генезиса
+    pub rewards: RewardConfig,
+    /// base fee для следующего блока: транзы с fee ниже в блок не идут
+    pub base_fee: u64,
     /// Последний применённый блок
     pub latest_block: Option<BlockMeta>,
 }
 
-/// Глобальное состояние ноды (in-memory)
-pub static STATE: Lazy<Mutex<ChainState>> = Lazy::new(|| {
-    let mut balances = HashMap::new();
+impl ChainState {
+    /// Состояние ноды (in-memory) на генезисе.
+    /// Без явных балансов всё получает казна. base fee стартует с минимума.
+    pub fn genesis(
+        network: &'static NetworkProfile,
+        allocations: &[GenesisBalance],
+        fees: &FeeConfig,
+        rewards: &RewardConfig,
+    ) -> Self {
+        let mut balances = HashMap::new();
+        let tokens = TokenRegistry::default();
 
-    // Казне сразу даём 1e9 GLD
-    balances.insert(
-        (TREASURY_ADDR.to_string(), "GLD".to_string()),
-        TREASURY_INITIAL_GLD,
-    );
+        if allocations.is_empty() {
+            // Казне сразу даём генезис сети (1e9 GLD)
+            balances.insert((TREASURY, GLD), network.treasury_gld);
+        }
+
+        for g in allocations {
+            // load_config уже проверил генезис, сюда плохие строки не доходят
+            let (Ok(addr), Some(token)) = (decode_address(&g.address, network), tokens.id_of(&g.token)) else {
+                error!(address = %g.address, token = %g.token, "skipping invalid genesis entry");
+                continue;
+            };
+            *balances.entry((addr, token)).or_insert(0) += g.amount;
+        }
+
+        // выпуск на генезисе — всё, что раздали
+        let mut supply = HashMap::new();
+        for ((_, token), amount) in &balances {
+            *supply.entry(*token).or_insert(0) += *amount;
+        }
 
-    Mutex::new(ChainState {
-        balances,
-        nonces: HashMap::new(),
-        latest_block: None,
-    })
-});
+        ChainState {
+            network,
+            balances,
+            vesting: HashMap::new(),
+            nonces: HashMap::new(),
+            bonds: HashMap::new(),
+            unbonding: HashMap::new(),
+            validator_set: ValidatorSet::default(),
+            tokens,
+            supply,
+            fees: fees.clone(),
+            rewards: rewards.clone(),
+            base_fee: fees.min_base_fee,
+            latest_block: None,
+        }
+    }
+}
 
 // ─────────────────────── 1) apply_block ───────────────────────
 
 /// Применить блок к состоянию.
-/// Списывает amount+fee с from, зачисляет amount на to,
-/// fee отправляет в казну, обновляет nonce и latest_block.
-pub fn apply_block(block: &Block) -> Result<(), String> {
-    let mut state = STATE.lock().unwrap();
-
+/// transfer: списывает amount с from, зачисляет на to;
+/// create_token: заводит токен в реестре и выдаёт issuer начальный выпуск;
+/// mint: issuer выпускает amount на to (не выше max_supply);
+/// burn: from сжигает amount своего токена;
+/// vesting_transfer: amount уходит в залок на to;
+/// stake / delegate: amount GLD в стейк на валидатора to;
+/// unstake: amount из стейка на to, вернётся через UNBONDING_BLOCKS.
+/// До транз открывшееся по залокам и unstake переезжает на свободный баланс,
+/// после — на границе эпохи пересчитывается набор валидаторов.
+/// fee (в GLD) должна быть не ниже base fee: burn_percent от base fee сгорает,
+/// доля казны уходит в казну, доля производителя и чаевые — в награду блока.
+/// Награды (выпуск и комиссии) должны совпасть с block_rewards.
+/// Обновляет nonce, base fee следующего блока и latest_block.
+/// Возвращает итоговые значения затронутых балансов и nonce.
+/// Блок применяется целиком или никак: при ошибке state откатывается.
+pub fn apply_block(state: &mut ChainState, block: &Block) -> Result<StateDiff, String> {
     // Можно на всякий случай проверить tx_count
     if block.header.tx_count as usize != block.body.txs.len() {
-        println!(
-            "[STATE] WARN: header.tx_count={} but body.txs.len()={}",
-            block.header.tx_count,
-            block.body.txs.len()
+        warn!(
+            header_tx_count = block.header.tx_count,
+            body_tx_count = block.body.txs.len(),
+            "header tx_count does not match body"
         );
     }
 
-    for tx in &block.body.txs {
-        // Пока обрабатываем только transfer
-        if tx.tx_type != "transfer" {
-            println!("[STATE] skip tx_type {} for now", tx.tx_type);
+    let mut undo = Undo::new(state);
+
+    let height = block.header.height;
+    begin_block(state, height, block.header.timestamp_ms as u64, &mut undo);
+    let applied = apply_txs(state, &block.body.txs, height, &mut undo)
+        .and_then(|fees| apply_rewards(state, block, fees, &mut undo));
+    if let Err(e) = applied {
+        undo.rollback(state);
+        return Err(e);
+    }
+    let validator_set = end_block(state, height, block.body.txs.len(), &mut undo);
+
+    // Сохраняем информацию о последнем блоке
+    state.latest_block = Some(BlockMeta {
+        height: block.header.height,
+        hash: block.block_id.clone(),
+    });
+
+    // в undo ровно затронутые ключи
+    let diff = StateDiff {
+        balances: touched_keys(undo.balances)
+            .into_iter()
+            .map(|key| (key.0, key.1, *state.balances.get(&key).unwrap_or(&0)))
+            .collect(),
+        nonces: touched_keys(undo.nonces)
+            .into_iter()
+            .map(|addr| (addr, *state.nonces.get(&addr).unwrap_or(&0)))
+            .collect(),
+        tokens: (undo.tokens + 1..=state.tokens.len()).map(|i| i as TokenId).collect(),
+        supply: touched_keys(undo.supply)
+            .into_iter()
+            .map(|token| (token, supply_of(state, token)))
+            .collect(),
+        locked: touched_keys(undo.vesting)
+            .into_iter()
+            .map(|key| (key.0, key.1, locked_of(state, &key.0, key.1)))
+            .collect(),
+        bonds: touched_keys(undo.bonds)
+            .into_iter()
+            .map(|key| (key.0, key.1, *state.bonds.get(&key).unwrap_or(&0)))
+            .collect(),
+        validator_set,
+        base_fee: state.base_fee,
+    };
+
+    Ok(diff)
+}
+
+/// Отобрать из кандидатов в блок транзы, которые применятся по порядку.
+/// Остальные возвращаются с причиной (не хватает баланса, mint сверх потолка
+/// из-за соседней транзы и т.п.). State после вызова тот же, что был.
+/// height и timestamp_ms — будущего блока: по ним открывается вестинг.
+/// Транзы с fee ниже текущего base fee тоже отсеиваются.
+/// Третье в ответе — доля производителя в комиссиях отобранных (для block_rewards).
+/// Транзы одного отправителя идут по nonce; транзы с nonce из будущего
+/// (перед ними дыра) не попадают ни в отобранные, ни в отказы — ждут в мемпуле.
+#[allow(clippy::type_complexity)]
+pub fn select_applicable(
+    state: &mut ChainState,
+    txs: Vec<ValidTxCore>,
+    height: u64,
+    timestamp_ms: u64,
+) -> (Vec<ValidTxCore>, Vec<(ValidTxCore, String)>, u64) {
+    let mut all = Undo::new(state);
+    begin_block(state, height, timestamp_ms, &mut all);
+    let mut ok = Vec::with_capacity(txs.len());
+    let mut failed = Vec::new();
+    let mut fees: u64 = 0;
+
+    for tx in by_sender_nonce(txs) {
+        if tx.nonce > nonce_of(state, &tx.from_key) + 1 {
             continue;
         }
+        let mut one = Undo::new(state);
+        match apply_txs(state, std::slice::from_ref(&tx), height, &mut one) {
+            Ok(tx_fees) => {
+                all.absorb(one);
+                ok.push(tx);
+                fees = fees.saturating_add(tx_fees);
+            }
+            Err(e) => {
+                one.rollback(state);
+                failed.push((tx, e));
+            }
+        }
+    }
+
+    all.rollback(state);
+    (ok, failed, fees)
+}
+
+/// Транзы каждого отправителя по возрастанию nonce на тех же местах в
+/// списке, что они занимали (общий порядок по комиссии сохраняется)
+fn by_sender_nonce(txs: Vec<ValidTxCore>) -> Vec<ValidTxCore> {
+    let mut slots: HashMap<AddrKey, Vec<usize>> = HashMap::new();
+    for (i, tx) in txs.iter().enumerate() {
+        slots.entry(tx.from_key).or_default().push(i);
+    }
+
+    let mut txs: Vec<Option<ValidTxCore>> = txs.into_iter().map(Some).collect();
+    let mut ordered: Vec<Option<ValidTxCore>> = vec![None; txs.len()];
+    for positions in slots.into_values() {
+        let mut sender: Vec<ValidTxCore> = positions.iter().filter_map(|&i| txs[i].take()).collect();
+        sender.sort_by_key(|tx| tx.nonce);
+        for (pos, tx) in positions.into_iter().zip(sender) {
+            ordered[pos] = Some(tx);
+        }
+    }
+    ordered.into_iter().flatten().collect()
+}
+
+/// Старые значения по ключу (None — ключа не было)
+type Touched<K, V> = HashMap<K, Option<V>>;
+
+/// Старые значения всего, что тронул блок. По ключу хранится значение до
+/// первого касания: повторные касания и поздние undo его не перетирают.
+struct Undo {
+    balances: Touched<(AddrKey, TokenId), u64>,
+    nonces: Touched<AddrKey, u64>,
+    supply: Touched<TokenId, u64>,
+    vesting: Touched<(AddrKey, TokenId), Vec<VestingLock>>,
+    bonds: Touched<(AddrKey, AddrKey), u64>,
+    unbonding: Touched<AddrKey, Vec<(u64, u64)>>,
+    /// Набор валидаторов до блока, если блок его сменил
+    validator_set: Option<ValidatorSet>,
+    /// Размер реестра токенов до блока (реестр только растёт)
+    tokens: usize,
+}
+
+impl Undo {
+    fn new(state: &ChainState) -> Self {
+        Undo {
+            balances: HashMap::new(),
+            nonces: HashMap::new(),
+            supply: HashMap::new(),
+            vesting: HashMap::new(),
+            bonds: HashMap::new(),
+            unbonding: HashMap::new(),
+            validator_set: None,
+            tokens: state.tokens.len(),
+        }
+    }
+
+    fn remember_balance(&mut self, state: &ChainState, key: (AddrKey, TokenId)) {
+        self.balances.entry(key).or_insert_with(|| state.balances.get(&key).copied());
+    }
+
+    fn remember_nonce(&mut self, state: &ChainState, addr: AddrKey) {
+        self.nonces.entry(addr).or_insert_with(|| state.nonces.get(&addr).copied());
+    }
+
+    fn remember_supply(&mut self, state: &ChainState, token: TokenId) {
+        self.supply.entry(token).or_insert_with(|| state.supply.get(&token).copied());
+    }
 
-        let from = tx.from.clone();
-        let to = tx.to.clone();
-        let token = tx.token.clone();
+    fn remember_vesting(&mut self, state: &ChainState, key: (AddrKey, TokenId)) {
+        self.vesting.entry(key).or_insert_with(|| state.vesting.get(&key).cloned());
+    }
+
+    fn remember_bond(&mut self, state: &ChainState, key: (AddrKey, AddrKey)) {
+        self.bonds.entry(key).or_insert_with(|| state.bonds.get(&key).copied());
+    }
+
+    fn remember_unbonding(&mut self, state: &ChainState, addr: AddrKey) {
+        self.unbonding.entry(addr).or_insert_with(|| state.unbonding.get(&addr).cloned());
+    }
 
+    /// Добавить значения из undo более поздней транзы (наши старше — они остаются)
+    fn absorb(&mut self, later: Undo) {
+        keep_first(&mut self.balances, later.balances);
+        keep_first(&mut self.nonces, later.nonces);
+        keep_first(&mut self.supply, later.supply);
+        keep_first(&mut self.vesting, later.vesting);
+        keep_first(&mut self.bonds, later.bonds);
+        keep_first(&mut self.unbonding, later.unbonding);
+        if self.validator_set.is_none() {
+            self.validator_set = later.validator_set;
+        }
+    }
+
+    fn rollback(self, state: &mut ChainState) {
+        restore(&mut state.balances, self.balances);
+        restore(&mut state.nonces, self.nonces);
+        restore(&mut state.supply, self.supply);
+        restore(&mut state.vesting, self.vesting);
+        restore(&mut state.bonds, self.bonds);
+        restore(&mut state.unbonding, self.unbonding);
+        if let Some(set) = self.validator_set {
+            state.validator_set = set;
+        }
+        state.tokens.truncate(self.tokens);
+    }
+}
+
+fn keep_first<K: std::hash::Hash + Eq, V>(ours: &mut Touched<K, V>, later: Touched<K, V>) {
+    for (key, old) in later {
+        ours.entry(key).or_insert(old);
+    }
+}
+
+fn restore<K: std::hash::Hash + Eq, V>(map: &mut HashMap<K, V>, undo: Touched<K, V>) {
+    for (key, old) in undo {
+        match old {
+            Some(v) => map.insert(key, v),
+            None => map.remove(&key),
+        };
+    }
+}
+
+/// Затронутые ключи по возрастанию: diff не зависит от порядка HashMap
+fn touched_keys<K: Ord, V>(undo: Touched<K, V>) -> Vec<K> {
+    let mut keys: Vec<K> = undo.into_keys().collect();
+    keys.sort_unstable();
+    keys
+}
+
+/// height — блока, в который идут транзы (от него считается unbonding).
+/// Возвращает долю производителя в комиссиях с применённых транз.
+/// Транза неизвестного типа — ошибка: в блок такие не попадают.
+fn apply_txs(state: &mut ChainState, txs: &[ValidTxCore], height: u64, undo: &mut Undo) -> Result<u64, String> {
+    let mut fees: u64 = 0;
+    for tx in txs {
+        if tx.fee < state.base_fee {
+            return Err(format!("fee {} is below base fee {}", tx.fee, state.base_fee));
+        }
+        // строго следующий nonce: одна и та же платёжка (в другой кодировке,
+        // с другим набором подписей мультисига) второй раз не пройдёт
+        let expected = nonce_of(state, &tx.from_key) + 1;
+        if tx.nonce != expected {
+            return Err(format!("nonce {} is not the next nonce {}", tx.nonce, expected));
+        }
+
+        match tx.tx_type.as_str() {
+            // multisig_transfer списывает так же, подписи уже проверены
+            "transfer" | "multisig_transfer" => apply_transfer(state, tx, undo)?,
+            "create_token" => apply_create_token(state, tx, undo)?,
+            "mint" => apply_mint(state, tx, undo)?,
+            "burn" => apply_burn(state, tx, undo)?,
+            "multi_send" => apply_multi_send(state, tx, undo)?,
+            "vesting_transfer" => apply_vesting_transfer(state, tx, undo)?,
+            "stake" | "delegate" => apply_bond(state, tx, undo)?,
+            "unstake" => apply_unstake(state, tx, height, undo)?,
+            other => return Err(format!("unsupported tx_type {}", other)),
+        }
+
+        fees = fees.saturating_add(split_fee(tx.fee, state.base_fee, &state.fees).1);
+
+        // ── Обновляем nonce отправителя ──
+        undo.remember_nonce(state, tx.from_key);
+        state.nonces.insert(tx.from_key, tx.nonce);
+    }
+
+    Ok(fees)
+}
+
+fn apply_transfer(state: &mut ChainState, tx: &ValidTxCore, undo: &mut Undo) -> Result<(), String> {
+    if state.tokens.get(tx.token_id).is_none() {
+        return Err(format!("unknown token code {}", tx.token_id));
+    }
+
+    // ── Списываем с отправителя: amount в токене перевода, fee в GLD ──
+    if tx.token_id == GLD {
         let total_debit = tx.amount
             .checked_add(tx.fee)
             .ok_or_else(|| "overflow on amount+fee".to_string())?;
+        debit(state, undo, (tx.from_key, GLD), total_debit)?;
+    } else {
+        debit(state, undo, (tx.from_key, tx.token_id), tx.amount)?;
+        debit(state, undo, (tx.from_key, GLD), tx.fee)?;
+    }
 
-        // ── Списываем с отправителя ──
-        let from_key = (from.clone(), token.clone());
-        let from_balance = state.balances.entry(from_key.clone()).or_insert(0);
+    // ── Зачисляем получателю amount ──
+    credit(state, undo, (tx.to_key, tx.token_id), tx.amount)
+        .map_err(|_| "overflow on receiver balance".to_string())?;
 
-        if *from_balance < total_debit {
-            return Err(format!(
-                "insufficient balance for {}, need {}, have {}",
-                from, total_debit, *from_balance
-            ));
-        }
+    // ── Комиссию отправляем в казну ──
+    collect_fee(state, undo, tx.fee)
+}
+
+/// multi_send: amount — сумма всех выплат, списывается разом.
+/// Если хоть одна выплата не прошла, undo откатит всю транзу.
+fn apply_multi_send(state: &mut ChainState, tx: &ValidTxCore, undo: &mut Undo) -> Result<(), String> {
+    if state.tokens.get(tx.token_id).is_none() {
+        return Err(format!("unknown token code {}", tx.token_id));
+    }
+    let total = tx.payouts.iter().try_fold(0u64, |acc, (_, amount)| acc.checked_add(*amount));
+    if total != Some(tx.amount) {
+        return Err(format!("multi_send {} payouts do not sum to amount", tx.tx_hash));
+    }
 
-        *from_balance -= total_debit;
+    if tx.token_id == GLD {
+        let total_debit = tx.amount
+            .checked_add(tx.fee)
+            .ok_or_else(|| "overflow on amount+fee".to_string())?;
+        debit(state, undo, (tx.from_key, GLD), total_debit)?;
+    } else {
+        debit(state, undo, (tx.from_key, tx.token_id), tx.amount)?;
+        debit(state, undo, (tx.from_key, GLD), tx.fee)?;
+    }
 
-        // ── Зачисляем получателю amount ──
-        let to_key = (to.clone(), token.clone());
-        let to_balance = state.balances.entry(to_key).or_insert(0);
-        *to_balance = to_balance
-            .checked_add(tx.amount)
-            .ok_or_else(|| "overflow on receiver balance".to_string())?;
+    for (to, amount) in &tx.payouts {
+        credit(state, undo, (*to, tx.token_id), *amount)
+            .map_err(|_| "overflow on receiver balance".to_string())?;
+    }
 
-        // ── Комиссию отправляем в казну ──
-        let treasury_key = (TREASURY_ADDR.to_string(), token.clone());
-        let treasury_balance = state.balances.entry(treasury_key).or_insert(0);
-        *treasury_balance = treasury_balance
+    collect_fee(state, undo, tx.fee)
+}
+
+/// vesting_transfer: amount с from уходит в залок на to, открывается по расписанию
+fn apply_vesting_transfer(state: &mut ChainState, tx: &ValidTxCore, undo: &mut Undo) -> Result<(), String> {
+    let TxExtra::Vesting(schedule) = tx.extra else {
+        return Err(format!("vesting_transfer {} without schedule", tx.tx_hash));
+    };
+    if state.tokens.get(tx.token_id).is_none() {
+        return Err(format!("unknown token code {}", tx.token_id));
+    }
+
+    let key = (tx.to_key, tx.token_id);
+    if state.vesting.get(&key).map_or(0, Vec::len) >= MAX_VESTING_LOCKS {
+        return Err(format!("too many vesting locks for {}", encode_address(&tx.to_key, state.network)));
+    }
+
+    if tx.token_id == GLD {
+        let total_debit = tx.amount
             .checked_add(tx.fee)
-            .ok_or_else(|| "overflow on treasury balance".to_string())?;
+            .ok_or_else(|| "overflow on amount+fee".to_string())?;
+        debit(state, undo, (tx.from_key, GLD), total_debit)?;
+    } else {
+        debit(state, undo, (tx.from_key, tx.token_id), tx.amount)?;
+        debit(state, undo, (tx.from_key, GLD), tx.fee)?;
+    }
 
-        // ── Обновляем nonce отправителя ──
-        let nonce_entry = state.nonces.entry(from.clone()).or_insert(0);
-        if tx.nonce > *nonce_entry {
-            *nonce_entry = tx.nonce;
+    undo.remember_vesting(state, key);
+    state.vesting.entry(key).or_default().push(VestingLock {
+        schedule,
+        total: tx.amount,
+        released: 0,
+    });
+
+    collect_fee(state, undo, tx.fee)
+}
+
+/// stake (to = from) и delegate: amount GLD в стейк на валидатора to.
+/// Делегировать можно только тому, у кого есть свой стейк.
+fn apply_bond(state: &mut ChainState, tx: &ValidTxCore, undo: &mut Undo) -> Result<(), String> {
+    if tx.tx_type == "delegate" && !state.bonds.contains_key(&(tx.to_key, tx.to_key)) {
+        return Err(format!("{} is not a validator", encode_address(&tx.to_key, state.network)));
+    }
+
+    let total_debit = tx.amount
+        .checked_add(tx.fee)
+        .ok_or_else(|| "overflow on amount+fee".to_string())?;
+    debit(state, undo, (tx.from_key, GLD), total_debit)?;
+
+    let key = (tx.from_key, tx.to_key);
+    undo.remember_bond(state, key);
+    let bond = state.bonds.entry(key).or_insert(0);
+    *bond = bond
+        .checked_add(tx.amount)
+        .ok_or_else(|| "overflow on bond".to_string())?;
+
+    collect_fee(state, undo, tx.fee)
+}
+
+/// unstake: amount из стейка from на валидаторе to уходит в unbonding
+/// и станет свободным с высоты height + UNBONDING_BLOCKS
+fn apply_unstake(state: &mut ChainState, tx: &ValidTxCore, height: u64, undo: &mut Undo) -> Result<(), String> {
+    let key = (tx.from_key, tx.to_key);
+    let bonded = *state.bonds.get(&key).unwrap_or(&0);
+    if bonded < tx.amount {
+        return Err(format!(
+            "insufficient stake of {} on {}, need {}, have {}",
+            encode_address(&tx.from_key, state.network),
+            encode_address(&tx.to_key, state.network),
+            tx.amount,
+            bonded
+        ));
+    }
+    if state.unbonding.get(&tx.from_key).map_or(0, Vec::len) >= MAX_UNBONDING_ENTRIES {
+        return Err(format!("too many unbonding entries for {}", encode_address(&tx.from_key, state.network)));
+    }
+
+    debit(state, undo, (tx.from_key, GLD), tx.fee)?;
+
+    undo.remember_bond(state, key);
+    if bonded == tx.amount {
+        state.bonds.remove(&key);
+    } else {
+        state.bonds.insert(key, bonded - tx.amount);
+    }
+
+    undo.remember_unbonding(state, tx.from_key);
+    state
+        .unbonding
+        .entry(tx.from_key)
+        .or_default()
+        .push((height + UNBONDING_BLOCKS, tx.amount));
+
+    collect_fee(state, undo, tx.fee)
+}
+
+/// Начало блока: всё, что открылось к (height, timestamp_ms), — на свободные балансы
+fn begin_block(state: &mut ChainState, height: u64, timestamp_ms: u64, undo: &mut Undo) {
+    release_vested(state, height, timestamp_ms, undo);
+    release_unbonded(state, height, undo);
+}
+
+/// Конец блока: пересчитать base fee по числу транз в блоке,
+/// на границе эпохи — ещё и набор валидаторов.
+/// Возвращает новый набор, если он посчитан.
+fn end_block(state: &mut ChainState, height: u64, tx_count: usize, undo: &mut Undo) -> Option<ValidatorSet> {
+    // после end_block блок уже не откатывается, старый base fee в undo не нужен
+    state.base_fee = next_base_fee(state.base_fee, tx_count, &state.fees);
+
+    if height == 0 || !height.is_multiple_of(EPOCH_LENGTH) {
+        return None;
+    }
+
+    // весь стейк на валидаторе; в набор идут только те, у кого есть свой
+    let mut stake: HashMap<AddrKey, u64> = HashMap::new();
+    for ((_, validator), amount) in &state.bonds {
+        *stake.entry(*validator).or_insert(0) += *amount;
+    }
+    let mut validators: Vec<(AddrKey, u64)> = stake
+        .into_iter()
+        .filter(|(v, _)| state.bonds.contains_key(&(*v, *v)))
+        .collect();
+    validators.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    validators.truncate(MAX_VALIDATORS);
+
+    let set = ValidatorSet { epoch: height / EPOCH_LENGTH, validators };
+    let old = std::mem::replace(&mut state.validator_set, set.clone());
+    if undo.validator_set.is_none() {
+        undo.validator_set = Some(old);
+    }
+    Some(set)
+}
+
+/// Вернуть на свободный баланс unstake, срок которых подошёл к height
+fn release_unbonded(state: &mut ChainState, height: u64, undo: &mut Undo) {
+    let mut addrs: Vec<_> = state
+        .unbonding
+        .iter()
+        .filter(|(_, entries)| entries.iter().any(|(at, _)| *at <= height))
+        .map(|(addr, _)| *addr)
+        .collect();
+    addrs.sort();
+
+    for addr in addrs {
+        undo.remember_unbonding(state, addr);
+        let entries = state.unbonding.get_mut(&addr).unwrap();
+        let due: u64 = entries.iter().filter(|(at, _)| *at <= height).map(|(_, amount)| *amount).sum();
+        entries.retain(|(at, _)| *at > height);
+        if entries.is_empty() {
+            state.unbonding.remove(&addr);
         }
+
+        // стейк уже был в выпуске, переполниться не может
+        undo.remember_balance(state, (addr, GLD));
+        *state.balances.entry((addr, GLD)).or_insert(0) += due;
     }
+}
 
-    // Сохраняем информацию о последнем блоке
-    state.latest_block = Some(BlockMeta {
-        height: block.header.height,
-        hash: block.block_id.clone(),
-    });
+/// Перевести открывшееся к блоку (height, timestamp_ms) на свободные балансы.
+/// Полностью открытые залоки удаляются.
+fn release_vested(state: &mut ChainState, height: u64, timestamp_ms: u64, undo: &mut Undo) {
+    // по порядку ключей, чтобы diff не зависел от HashMap
+    let mut keys: Vec<_> = state.vesting.keys().copied().collect();
+    keys.sort();
+
+    for key in keys {
+        let due: u64 = state.vesting[&key]
+            .iter()
+            .map(|l| l.schedule.unlocked(l.total, height, timestamp_ms).saturating_sub(l.released))
+            .sum();
+        if due == 0 {
+            continue;
+        }
+
+        undo.remember_vesting(state, key);
+        let locks = state.vesting.get_mut(&key).unwrap();
+        for l in locks.iter_mut() {
+            // timestamp блоков может чуть пятиться — открытое назад не закрываем
+            l.released = l.released.max(l.schedule.unlocked(l.total, height, timestamp_ms));
+        }
+        locks.retain(|l| l.released < l.total);
+        if locks.is_empty() {
+            state.vesting.remove(&key);
+        }
+
+        // сумма залоков уже была в выпуске, переполниться не может
+        undo.remember_balance(state, key);
+        *state.balances.entry(key).or_insert(0) += due;
+    }
+}
+
+/// create_token: token — символ, amount — max_supply, to — issuer
+fn apply_create_token(state: &mut ChainState, tx: &ValidTxCore, undo: &mut Undo) -> Result<(), String> {
+    let TxExtra::CreateToken { decimals, initial_supply } = tx.extra else {
+        return Err(format!("create_token {} without token params", tx.tx_hash));
+    };
 
+    debit(state, undo, (tx.from_key, GLD), tx.fee)?;
+    collect_fee(state, undo, tx.fee)?;
+
+    let id = state.tokens.register(TokenInfo {
+        symbol: tx.token.clone(),
+        decimals,
+        max_supply: tx.amount,
+        issuer: tx.to_key,
+    })?;
+
+    // начальный выпуск — сразу issuer
+    if initial_supply > 0 {
+        credit(state, undo, (tx.to_key, id), initial_supply)?;
+    }
+    undo.remember_supply(state, id);
+    state.supply.insert(id, initial_supply);
+
+    debug!(token = %tx.token, id, "token created");
+    Ok(())
+}
+
+/// mint: from — issuer токена, amount уходит на to
+fn apply_mint(state: &mut ChainState, tx: &ValidTxCore, undo: &mut Undo) -> Result<(), String> {
+    let token = state
+        .tokens
+        .get(tx.token_id)
+        .ok_or_else(|| format!("unknown token code {}", tx.token_id))?;
+    if token.issuer != tx.from_key {
+        return Err(format!("only issuer can mint {}", token.symbol));
+    }
+
+    let supply = supply_of(state, tx.token_id)
+        .checked_add(tx.amount)
+        .filter(|s| *s <= token.max_supply)
+        .ok_or_else(|| format!("mint exceeds max supply of {}", token.symbol))?;
+
+    debit(state, undo, (tx.from_key, GLD), tx.fee)?;
+    collect_fee(state, undo, tx.fee)?;
+
+    credit(state, undo, (tx.to_key, tx.token_id), tx.amount)
+        .map_err(|_| "overflow on receiver balance".to_string())?;
+    undo.remember_supply(state, tx.token_id);
+    state.supply.insert(tx.token_id, supply);
+    Ok(())
+}
+
+/// burn: from сжигает amount, выпуск уменьшается
+fn apply_burn(state: &mut ChainState, tx: &ValidTxCore, undo: &mut Undo) -> Result<(), String> {
+    if state.tokens.get(tx.token_id).is_none() {
+        return Err(format!("unknown token code {}", tx.token_id));
+    }
+
+    debit(state, undo, (tx.from_key, tx.token_id), tx.amount)?;
+    debit(state, undo, (tx.from_key, GLD), tx.fee)?;
+    collect_fee(state, undo, tx.fee)?;
+
+    // выпуск не меньше любой суммы балансов, так что вычитание не уходит в минус
+    let supply = supply_of(state, tx.token_id).saturating_sub(tx.amount);
+    undo.remember_supply(state, tx.token_id);
+    state.supply.insert(tx.token_id, supply);
+    Ok(())
+}
+
+/// Разнести уже списанную с отправителя fee: сгоревшее уменьшает выпуск GLD,
+/// доля казны — в казну. Долю производителя начислит награда блока.
+fn collect_fee(state: &mut ChainState, undo: &mut Undo, fee: u64) -> Result<(), String> {
+    let (burned, _, treasury) = split_fee(fee, state.base_fee, &state.fees);
+    if burned > 0 {
+        undo.remember_supply(state, GLD);
+        let supply = supply_of(state, GLD).saturating_sub(burned);
+        state.supply.insert(GLD, supply);
+    }
+
+    credit(state, undo, (TREASURY, GLD), treasury)
+        .map_err(|_| "overflow on treasury balance".to_string())
+}
+
+/// Сверить награды блока с положенными и начислить их.
+/// fees — доля производителя в комиссиях, которую вернул apply_txs.
+/// Производитель — адрес из первой награды (без наград сверять не с чем,
+/// тогда положенное должно быть пустым).
+fn apply_rewards(state: &mut ChainState, block: &Block, fees: u64, undo: &mut Undo) -> Result<(), String> {
+    let treasury = treasury_addr(state.network);
+    let proposer = block.body.rewards.first().map_or(treasury.as_str(), |r| r.to.as_str());
+    let expected = block_rewards(state, block.header.height, proposer, fees);
+    if block.body.rewards != expected {
+        return Err(format!("block {} rewards do not match", block.header.height));
+    }
+
+    for r in &block.body.rewards {
+        let to = decode_address(&r.to, state.network).map_err(|e| format!("reward address {}: {}", r.to, e))?;
+        credit(state, undo, (to, GLD), r.amount).map_err(|_| "overflow on proposer balance".to_string())?;
+        // комиссии уже были в выпуске, выпуск — новые GLD
+        if r.kind == REWARD_KIND_ISSUANCE {
+            undo.remember_supply(state, GLD);
+            let supply = supply_of(state, GLD).saturating_add(r.amount);
+            state.supply.insert(GLD, supply);
+        }
+    }
+    Ok(())
+}
+
+/// Списать amount с key; не хватает — ошибка (state не тронут)
+fn debit(state: &mut ChainState, undo: &mut Undo, key: (AddrKey, TokenId), amount: u64) -> Result<(), String> {
+    // запоминаем старое значение до любых изменений
+    undo.remember_balance(state, key);
+
+    let balance = state.balances.entry(key).or_insert(0);
+    if *balance < amount {
+        return Err(format!(
+            "insufficient {} balance for {}, need {}, have {}",
+            state.tokens.symbol(key.1).unwrap_or("?"),
+            encode_address(&key.0, state.network),
+            amount,
+            *balance
+        ));
+    }
+    *balance -= amount;
+    Ok(())
+}
+
+/// Зачислить amount на key
+fn credit(state: &mut ChainState, undo: &mut Undo, key: (AddrKey, TokenId), amount: u64) -> Result<(), String> {
+    undo.remember_balance(state, key);
+
+    let balance = state.balances.entry(key).or_insert(0);
+    *balance = balance
+        .checked_add(amount)
+        .ok_or_else(|| "overflow on balance".to_string())?;
     Ok(())
 }
 
 // ─────────────────────── 2) balance(addr, token) ───────────────────────
 
-pub fn balance(addr: &str, token: &str) -> u64 {
-    let state = STATE.lock().unwrap();
-    *state
-        .balances
-        .get(&(addr.to_string(), token.to_string()))
-        .unwrap_or(&0)
+/// Баланс по bech32-адресу и символу токена (для запросов снаружи).
+/// Неразборчивый адрес или неизвестный токен — ноль.
+pub fn balance(state: &ChainState, addr: &str, token: &str) -> u64 {
+    match (decode_address(addr, state.network), state.tokens.id_of(token)) {
+        (Ok(addr), Some(token)) => balance_of(state, &addr, token),
+        _ => 0,
+    }
+}
+
+pub fn balance_of(state: &ChainState, addr: &AddrKey, token: TokenId) -> u64 {
+    *state.balances.get(&(*addr, token)).unwrap_or(&0)
+}
+
+/// Ещё залоченное вестингом (в balance_of не входит)
+pub fn locked_of(state: &ChainState, addr: &AddrKey, token: TokenId) -> u64 {
+    state
+        .vesting
+        .get(&(*addr, token))
+        .map_or(0, |locks| locks.iter().map(|l| l.total - l.released).sum())
+}
+
+/// (свободно, залочено) к блоку (height, timestamp_ms): открывшееся к нему
+/// считается свободным, хотя на баланс переедет только с самим блоком
+pub fn balance_at(state: &ChainState, addr: &str, token: &str, height: u64, timestamp_ms: u64) -> (u64, u64) {
+    let (Ok(addr), Some(token)) = (decode_address(addr, state.network), state.tokens.id_of(token)) else {
+        return (0, 0);
+    };
+    let due: u64 = state.vesting.get(&(addr, token)).map_or(0, |locks| {
+        locks
+            .iter()
+            .map(|l| l.schedule.unlocked(l.total, height, timestamp_ms).saturating_sub(l.released))
+            .sum()
+    });
+    // unstake, срок которых подошёл, к этому блоку тоже свободны
+    let unbonded: u64 = match token {
+        GLD => state.unbonding.get(&addr).map_or(0, |entries| {
+            entries.iter().filter(|(at, _)| *at <= height).map(|(_, amount)| *amount).sum()
+        }),
+        _ => 0,
+    };
+    (balance_of(state, &addr, token) + due + unbonded, locked_of(state, &addr, token) - due)
+}
+
+/// Общий выпуск токена (0 — токена нет)
+pub fn supply_of(state: &ChainState, token: TokenId) -> u64 {
+    *state.supply.get(&token).unwrap_or(&0)
 }
 
 // ─────────────────────── 3) nonce(addr) ───────────────────────
 
-pub fn nonce(addr: &str) -> u64 {
-    let state = STATE.lock().unwrap();
+pub fn nonce(state: &ChainState, addr: &str) -> u64 {
+    decode_address(addr, state.network).map(|a| nonce_of(state, &a)).unwrap_or(0)
+}
+
+pub fn nonce_of(state: &ChainState, addr: &AddrKey) -> u64 {
     *state.nonces.get(addr).unwrap_or(&0)
 }
 
@@ -144,10 +1128,128 @@ pub fn nonce(addr: &str) -> u64 {
 
 /// Вернуть (height, hash) последнего блока.
 /// Если блоков ещё нет — None.
-pub fn block_info() -> Option<(u64, String)> {
-    let state = STATE.lock().unwrap();
+pub fn block_info(state: &ChainState) -> Option<(u64, String)> {
     state
         .latest_block
         .as_ref()
         .map(|b| (b.height, b.hash.clone()))
 }
+
+// ─────────────────────── 5) dump_state(state, path) ───────────────────────
+
+/// Сбросить состояние в текстовый файл, по строке на запись:
+///   token <code> <symbol> <decimals> <max_supply> <issuer> <supply>
+///   balance <address> <token> <amount>
+///   vesting <address> <token> <height|time> <start> <cliff> <duration> <total> <released>
+///   bond <delegator> <validator> <amount>
+///   unbonding <address> <release_height> <amount>
+///   validator <epoch> <address> <stake>
+///   nonce <address> <nonce>
+///   base_fee <base fee следующего блока>
+///   latest <height> <hash>
+/// Пишем во временный файл и переименовываем, чтобы не оставить огрызок.
+/// Возвращает число записанных строк.
+pub fn dump_state(state: &ChainState, path: &str) -> Result<usize, String> {
+    let lines = state_lines(state);
+    write_lines_atomic(path, &lines)?;
+    Ok(lines.len())
+}
+
+/// Состояние построчно в каноническом порядке (формат как в dump_state)
+pub fn state_lines(state: &ChainState) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    // реестр и так упорядочен по коду
+    for (id, t) in state.tokens.iter() {
+        lines.push(format!(
+            "token {} {} {} {} {} {}",
+            id,
+            t.symbol,
+            t.decimals,
+            t.max_supply,
+            encode_address(&t.issuer, state.network),
+            supply_of(state, id)
+        ));
+    }
+
+    // сортируем уже строки, чтобы порядок не зависел от внутренних ключей
+    let mut balances: Vec<_> = state
+        .balances
+        .iter()
+        .map(|((addr, token), amount)| (encode_address(addr, state.network), state.tokens.symbol(*token).unwrap_or("UNKNOWN"), *amount))
+        .collect();
+    balances.sort();
+    for (addr, token, amount) in balances {
+        lines.push(format!("balance {} {} {}", addr, token, amount));
+    }
+
+    let mut locks: Vec<_> = state
+        .vesting
+        .iter()
+        .flat_map(|((addr, token), locks)| {
+            let addr = encode_address(addr, state.network);
+            let token = state.tokens.symbol(*token).unwrap_or("UNKNOWN");
+            locks.iter().map(move |l| (addr.clone(), token, l))
+        })
+        .map(|(addr, token, l)| {
+            let s = &l.schedule;
+            format!(
+                "vesting {} {} {} {} {} {} {} {}",
+                addr, token, s.clock.name(), s.start, s.cliff, s.duration, l.total, l.released
+            )
+        })
+        .collect();
+    locks.sort();
+    lines.extend(locks);
+
+    let mut bonds: Vec<_> = state
+        .bonds
+        .iter()
+        .map(|((d, v), amount)| format!("bond {} {} {}", encode_address(d, state.network), encode_address(v, state.network), amount))
+        .collect();
+    bonds.sort();
+    lines.extend(bonds);
+
+    let mut unbonding: Vec<_> = state
+        .unbonding
+        .iter()
+        .flat_map(|(addr, entries)| {
+            let addr = encode_address(addr, state.network);
+            entries.iter().map(move |(at, amount)| format!("unbonding {} {} {}", addr, at, amount))
+        })
+        .collect();
+    unbonding.sort();
+    lines.extend(unbonding);
+
+    // набор уже в каноническом порядке (по стейку)
+    for (addr, stake) in &state.validator_set.validators {
+        lines.push(format!("validator {} {} {}", state.validator_set.epoch, encode_address(addr, state.network), stake));
+    }
+
+    let mut nonces: Vec<_> = state.nonces.iter().map(|(addr, n)| (encode_address(addr, state.network), *n)).collect();
+    nonces.sort();
+    for (addr, n) in nonces {
+        lines.push(format!("nonce {} {}", addr, n));
+    }
+
+    lines.push(format!("base_fee {}", state.base_fee));
+
+    if let Some(b) = &state.latest_block {
+        lines.push(format!("latest {} {}", b.height, b.hash));
+    }
+
+    lines
+}
+
+/// Записать строки в path через path.tmp + rename
+pub fn write_lines_atomic(path: &str, lines: &[String]) -> Result<(), String> {
+    let tmp = format!("{}.tmp", path);
+
+    let mut text = lines.join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+
+    std::fs::write(&tmp, text).map_err(|e| format!("failed to write {}: {}", tmp, e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("failed to rename {}: {}", tmp, e))
+}
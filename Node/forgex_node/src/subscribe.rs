@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::{self, error::{RecvError, TryRecvError}};
+use tokio::time::{timeout, Duration};
+use tracing::{error, info, warn};
+
+use crate::block::{Block, blocks_from, make_receipts};
+use crate::events::{NodeEvent, NodeHook};
+use crate::model::{encode_block_raw, encode_receipts_raw};
+use crate::node::Node;
+
+/// Сколько блоков держим в очереди на подписчика.
+/// Кто отстал сильнее — догоняет из хранилища блоков.
+const FEED_CAPACITY: usize = 64;
+
+/// Если подписчик не вычитал сообщение за это время — отключаем его.
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Уже закодированный блок (и квитанции), готовый к отправке подписчикам
+pub struct FeedItem {
+    pub height: u64,
+    pub block_raw: Vec<u8>,
+    pub receipts_raw: Vec<u8>,
+}
+
+impl FeedItem {
+    pub fn from_block(block: &Block) -> Result<Self, String> {
+        Ok(FeedItem {
+            height: block.header.height,
+            block_raw: encode_block_raw(block)?,
+            receipts_raw: encode_receipts_raw(block.header.height, &make_receipts(block))?,
+        })
+    }
+}
+
+/// Лента блоков ноды для подписчиков
+pub type Feed = broadcast::Sender<Arc<FeedItem>>;
+
+pub fn new_feed() -> Feed {
+    let (tx, _rx) = broadcast::channel(FEED_CAPACITY);
+    tx
+}
+
+/// Разослать новый блок всем подписчикам ноды.
+/// Блок должен быть уже сохранён через save_block, иначе отставшие его не догонят.
+pub fn publish_block(node: &Node, block: &Block) {
+    match FeedItem::from_block(block) {
+        Ok(item) => {
+            // Err = подписчиков нет, это нормально
+            let _ = node.feed.send(Arc::new(item));
+        }
+        Err(e) => {
+            error!(height = block.header.height, error = %e, "failed to encode block for subscribers");
+        }
+    }
+}
+
+/// Хук: применённые блоки уходят в ленту подписчиков
+pub struct SubscriptionHook;
+
+impl NodeHook for SubscriptionHook {
+    fn on_event(&self, node: &Node, event: &NodeEvent) {
+        if let NodeEvent::BlockApplied { block } = event {
+            publish_block(node, block);
+        }
+    }
+}
+
+/// Обслуживание долгоживущего соединения после SUBSCRIBE(from_height).
+///
+/// Сначала отдаём историю из хранилища, потом всё новое по мере производства.
+/// Медленный подписчик не тормозит ноду: при переполнении очереди он
+/// догоняет из хранилища, а если не читает совсем — отключается по таймауту.
+pub async fn serve_subscriber(node: &Node, mut stream: TcpStream, from_height: u64, with_receipts: bool) {
+    // подписываемся ДО чтения истории, чтобы не потерять блоки между ними
+    let mut rx = node.feed.subscribe();
+    let mut next_height = from_height.max(1);
+
+    info!(from_height = next_height, with_receipts, "subscriber attached");
+
+    if let Err(e) = replay_from_store(node, &mut stream, &mut next_height, with_receipts).await {
+        warn!(error = %e, "subscriber dropped during replay");
+        return;
+    }
+
+    loop {
+        let received = tokio::select! {
+            received = rx.recv() => received,
+            _ = node.shutdown.peers_closing() => {
+                // блоков больше не будет: отдаём то, что уже в очереди, и закрываем
+                drain_on_shutdown(node, &mut stream, &mut rx, next_height, with_receipts).await;
+                return;
+            }
+        };
+
+        let item = match received {
+            Ok(item) => item,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(skipped, "subscriber lagged, catching up from store");
+                if let Err(e) = replay_from_store(node, &mut stream, &mut next_height, with_receipts).await {
+                    warn!(error = %e, "subscriber dropped during catch-up");
+                    return;
+                }
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        if item.height < next_height {
+            // уже отдали из истории
+            continue;
+        }
+
+        if item.height > next_height {
+            // дырка в последовательности — добираем из хранилища
+            if let Err(e) = replay_from_store(node, &mut stream, &mut next_height, with_receipts).await {
+                warn!(error = %e, "subscriber dropped during catch-up");
+                return;
+            }
+            if item.height < next_height {
+                continue;
+            }
+        }
+
+        if let Err(e) = send_item(&mut stream, &item, with_receipts).await {
+            info!(error = %e, "subscriber dropped");
+            return;
+        }
+        next_height = item.height + 1;
+    }
+}
+
+async fn drain_on_shutdown(
+    node: &Node,
+    stream: &mut TcpStream,
+    rx: &mut broadcast::Receiver<Arc<FeedItem>>,
+    mut next_height: u64,
+    with_receipts: bool,
+) {
+    // при переполнении очереди всё нужное есть в хранилище
+    let mut result = replay_from_store(node, stream, &mut next_height, with_receipts).await;
+
+    while result.is_ok() {
+        match rx.try_recv() {
+            Ok(item) if item.height >= next_height => {
+                result = send_item(stream, &item, with_receipts).await;
+                next_height = item.height + 1;
+            }
+            Ok(_) | Err(TryRecvError::Lagged(_)) => {}
+            Err(_) => break,
+        }
+    }
+
+    if let Err(e) = result {
+        warn!(error = %e, "subscriber dropped during shutdown");
+    }
+    let _ = stream.shutdown().await;
+    info!("subscriber closed on shutdown");
+}
+
+async fn replay_from_store(
+    node: &Node,
+    stream: &mut TcpStream,
+    next_height: &mut u64,
+    with_receipts: bool,
+) -> Result<(), String> {
+    // копию берём сразу, чтобы не держать лок хранилища на время отправки
+    let blocks = blocks_from(&node.blocks.lock().unwrap(), *next_height);
+    for block in blocks {
+        let item = FeedItem::from_block(&block)?;
+        send_item(stream, &item, with_receipts).await?;
+        *next_height = item.height + 1;
+    }
+    Ok(())
+}
+
+async fn send_item(stream: &mut TcpStream, item: &FeedItem, with_receipts: bool) -> Result<(), String> {
+    write_with_timeout(stream, &item.block_raw).await?;
+    if with_receipts {
+        write_with_timeout(stream, &item.receipts_raw).await?;
+    }
+    Ok(())
+}
+
+async fn write_with_timeout(stream: &mut TcpStream, data: &[u8]) -> Result<(), String> {
+    match timeout(SEND_TIMEOUT, stream.write_all(data)).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(format!("write failed: {}", e)),
+        Err(_) => Err("send timeout (subscriber not reading)".into()),
+    }
+}
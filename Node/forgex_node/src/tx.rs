@@ -1,12 +1,42 @@
-use crate::model::Tx;
-use std::time::{SystemTime, UNIX_EPOCH};
+use crate::address::{AddrKey, decode_address, encode_address};
+use crate::envelope::validate_tx_version;
+use crate::network::NetworkProfile;
+use crate::model::{RAW_TX_PUBKEY_AT, Tx};
+use crate::multisig::{MAX_MULTISIG_KEYS, ZERO_PUBKEY_HEX, ZERO_SIGNATURE_HEX, check_policy, multisig_address};
+use crate::state::{GLD, GLD_SYMBOL, MAX_TOKEN_DECIMALS, NO_TOKEN, TokenId, VestingSchedule, check_token_symbol};
 use sha2::{Digest, Sha256};
-use k256::ecdsa::{Signature, VerifyingKey};
-use k256::elliptic_curve::sec1::ToEncodedPoint;
-use bech32::{self, FromBase32, ToBase32};
-use k256::ecdsa::signature::Verifier;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
 use k256::ecdsa::signature::hazmat::PrehashVerifier;
 
+/// Сколько получателей максимум в одном multi_send
+pub const MAX_MULTI_SEND: usize = 100;
+
+/// Кодировки подписываемой строки и raw tx
+pub const PIPE_V1: &str = "pipe_v1";
+/// pipe_v1 + memo: в raw tx memo_len (u8) | memo после хвоста типа,
+/// в подписываемой строке — после полей типа
+pub const PIPE_V2: &str = "pipe_v2";
+
+/// Максимальная длина memo в байтах
+pub const MAX_MEMO_LEN: usize = 64;
+
+/// Поля, которые есть только у отдельных типов транз.
+/// В raw tx идут хвостом после encoding, в pipe_v1 — после timestamp.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TxExtra {
+    #[default]
+    None,
+    /// create_token: token — новый символ, amount — max_supply, to — issuer
+    CreateToken { decimals: u8, initial_supply: u64 },
+    /// multi_send: (получатель, сумма); to = from, amount — сумма всех выплат
+    MultiSend(Vec<(String, u64)>),
+    /// multisig_transfer: политика адреса from и подписи (индекс ключа, r|s|v в hex).
+    /// Подписи в pipe_v1 не входят — их ставят поверх одной и той же строки.
+    Multisig { threshold: u8, pubkeys: Vec<String>, signatures: Vec<(u8, String)> },
+    /// vesting_transfer: amount уходит на to залоченным, открывается по расписанию
+    Vesting(VestingSchedule),
+}
+
 #[derive(Debug, Clone)]
 pub struct ValidTxCore {
     pub tx_hash: String,
@@ -20,19 +50,63 @@ pub struct ValidTxCore {
     pub timestamp: u64,
     pub pubkey: String,
     pub signature: String,
+    pub extra: TxExtra,
+    /// Memo (pipe_v2); пустая строка — без memo
+    pub memo: String,
+    /// Кодировка, в которой транза подписана (по ней же считан tx_hash)
+    pub encoding: String,
+    /// from/to/token в том виде, в каком их держит state
+    pub from_key: AddrKey,
+    pub to_key: AddrKey,
+    pub token_id: TokenId,
+    /// Выплаты multi_send в ключах state (у остальных типов пусто)
+    pub payouts: Vec<(AddrKey, u64)>,
 }
 
-pub fn validate_tx(tx: &Tx) -> Result<ValidTxCore, String> {
-    basic_prevalidate(tx)?;
-    verify_address_checksum(&tx.from)?;
-    verify_address_checksum(&tx.to)?;
+/// now_ms — текущее время ноды (для проверки timestamp), network — сеть ноды
+pub fn validate_tx(tx: &Tx, now_ms: u64, network: &NetworkProfile) -> Result<ValidTxCore, String> {
+    basic_prevalidate(tx, now_ms, network)?;
+    // с мультисига тратит только multisig_transfer, а он — только с мультисига
+    let from_multisig = decode_address(&tx.from, network)?.is_multisig();
+    if from_multisig != (tx.tx_type == "multisig_transfer") {
+        return Err(if from_multisig {
+            format!("{} cannot be sent from a multisig address", tx.tx_type)
+        } else {
+            "multisig_transfer must be sent from a multisig address".into()
+        });
+    }
+    verify_address_checksum(&tx.to, network)?;
+    if let TxExtra::MultiSend(payouts) = &tx.extra {
+        for (to, _) in payouts {
+            verify_address_checksum(to, network)?;
+        }
+    }
+
+    // компактная транза пришла без pubkey: восстанавливаем его из r|s|v,
+    // дальше (tx_hash, адрес) — как у обычной
+    let recovered_tx;
+    let recovered = tx.pubkey.is_empty();
+    let tx = if recovered {
+        recovered_tx = Tx { pubkey: recover_pubkey(tx)?, ..tx.clone() };
+        &recovered_tx
+    } else {
+        tx
+    };
+
     verify_tx_hash(tx)?;
-    verify_address(tx)?;
-    verify_signature(tx)?;
-    Ok(return_structured_tx(tx))
+    if let TxExtra::Multisig { .. } = tx.extra {
+        verify_multisig(tx, network)?;
+    } else {
+        verify_address(tx, network)?;
+        // восстановление уже проверило подпись этим ключом
+        if !recovered {
+            verify_signature(tx)?;
+        }
+    }
+    return_structured_tx(tx, network)
 }
 
-pub fn basic_prevalidate(tx: &Tx) -> Result<(), String> {
+pub fn basic_prevalidate(tx: &Tx, now_ms: u64, network: &NetworkProfile) -> Result<(), String> {
     // ---------------------------
     // tx_hash
     // ---------------------------
@@ -43,24 +117,16 @@ pub fn basic_prevalidate(tx: &Tx) -> Result<(), String> {
         return Err("tx_hash must be 64 hex chars".into());
     }
 
-    // ---------------------------
-    // domain_tag
-    // ---------------------------
-    if tx.domain_tag.is_empty() {
-        return Err("domain_tag is empty".into());
-    }
-    if tx.domain_tag != "GLD_TX_v1" {
-        return Err("domain_tag must be GLD_TX_v1".into());
-    }
-
     // ---------------------------
     // chain_id
     // ---------------------------
     if tx.chain_id.is_empty() {
         return Err("chain_id is empty".into());
     }
-    if tx.chain_id != "gld-dev-1" {
-        return Err("chain_id must be gld-dev-1".into());
+    // chain_id входит в подпись: транза другой сети здесь не пройдёт
+    let chain_id = network.chain_id;
+    if tx.chain_id != chain_id {
+        return Err(format!("chain_id must be {}", chain_id));
     }
 
     // ---------------------------
@@ -69,8 +135,9 @@ pub fn basic_prevalidate(tx: &Tx) -> Result<(), String> {
     if tx.tx_type.is_empty() {
         return Err("tx_type is empty".into());
     }
-    // пока только один тип
-    if tx.tx_type != "transfer" {
+    if !matches!(tx.tx_type.as_str(), "transfer" | "create_token" | "mint" | "burn" | "multi_send" | "multisig_transfer" | "vesting_transfer"
+            | "stake" | "unstake" | "delegate")
+    {
         return Err("unsupported tx_type".into());
     }
 
@@ -93,12 +160,108 @@ pub fn basic_prevalidate(tx: &Tx) -> Result<(), String> {
     // ---------------------------
     // token
     // ---------------------------
+    // символ по коду подставляет нода из реестра, до проверки
     if tx.token.is_empty() {
         return Err("token is empty".into());
     }
-    // список разрешённых токенов — пока только GLD
-    if tx.token != "GLD" {
-        return Err("unsupported token (only GLD allowed for now)".into());
+
+    // ---------------------------
+    // поля по типу транзы
+    // ---------------------------
+    match (tx.tx_type.as_str(), &tx.extra) {
+        ("transfer" | "mint" | "burn", TxExtra::None) => {
+            if tx.token_id == NO_TOKEN {
+                return Err(format!("{} token code is empty", tx.tx_type));
+            }
+            // сжигать можно только своё: to = from
+            if tx.tx_type == "burn" && tx.to != tx.from {
+                return Err("burn to must equal from".into());
+            }
+        }
+        ("create_token", TxExtra::CreateToken { decimals, initial_supply }) => {
+            check_token_symbol(&tx.token)?;
+            if tx.token_id != NO_TOKEN {
+                return Err("create_token token code must be 0".into());
+            }
+            if *decimals > MAX_TOKEN_DECIMALS {
+                return Err(format!("decimals must be <= {}", MAX_TOKEN_DECIMALS));
+            }
+            if *initial_supply > tx.amount {
+                return Err("initial_supply cannot be greater than max_supply".into());
+            }
+        }
+        ("multi_send", TxExtra::MultiSend(payouts)) => {
+            if tx.token_id == NO_TOKEN {
+                return Err("multi_send token code is empty".into());
+            }
+            if tx.to != tx.from {
+                return Err("multi_send to must equal from".into());
+            }
+            if payouts.is_empty() || payouts.len() > MAX_MULTI_SEND {
+                return Err(format!("multi_send must pay 1..={} recipients", MAX_MULTI_SEND));
+            }
+            let mut total: u64 = 0;
+            for (to, amount) in payouts {
+                if to.len() != 56 {
+                    return Err("multi_send recipient must be 56 chars".into());
+                }
+                if *amount == 0 {
+                    return Err("multi_send amount must be > 0".into());
+                }
+                total = total
+                    .checked_add(*amount)
+                    .ok_or_else(|| "overflow on multi_send total".to_string())?;
+            }
+            if total != tx.amount {
+                return Err("multi_send amount must equal sum of payouts".into());
+            }
+        }
+        ("multisig_transfer", TxExtra::Multisig { threshold, pubkeys, signatures }) => {
+            if tx.token_id == NO_TOKEN {
+                return Err("multisig_transfer token code is empty".into());
+            }
+            if pubkeys.is_empty() || pubkeys.len() > MAX_MULTISIG_KEYS {
+                return Err(format!("multisig must have 1..={} pubkeys", MAX_MULTISIG_KEYS));
+            }
+            if *threshold == 0 || *threshold as usize > pubkeys.len() {
+                return Err(format!("multisig threshold must be 1..={}", pubkeys.len()));
+            }
+            if signatures.len() < *threshold as usize {
+                return Err(format!("multisig needs {} signatures, got {}", threshold, signatures.len()));
+            }
+            // индексы строго по возрастанию: каждый ключ подписывает один раз
+            if signatures.windows(2).any(|w| w[0].0 >= w[1].0)
+                || signatures.iter().any(|(i, _)| *i as usize >= pubkeys.len())
+            {
+                return Err("multisig signature indexes must be sorted, unique and in range".into());
+            }
+            if pubkeys.iter().any(|pk| pk.len() != 66) || signatures.iter().any(|(_, sig)| sig.len() != 130) {
+                return Err("multisig pubkeys must be 33 bytes and signatures 65 bytes".into());
+            }
+            // свои pubkey/signature у транзы пустые (нули)
+            if tx.pubkey != ZERO_PUBKEY_HEX || tx.signature != ZERO_SIGNATURE_HEX {
+                return Err("multisig_transfer pubkey and signature must be zero".into());
+            }
+        }
+        // стейкают только GLD; to — валидатор
+        ("stake" | "unstake" | "delegate", TxExtra::None) => {
+            if tx.token_id != GLD {
+                return Err(format!("{} token must be {}", tx.tx_type, GLD_SYMBOL));
+            }
+            if tx.tx_type == "stake" && tx.to != tx.from {
+                return Err("stake to must equal from".into());
+            }
+            if tx.tx_type == "delegate" && tx.to == tx.from {
+                return Err("cannot delegate to self, use stake".into());
+            }
+        }
+        ("vesting_transfer", TxExtra::Vesting(schedule)) => {
+            if tx.token_id == NO_TOKEN {
+                return Err("vesting_transfer token code is empty".into());
+            }
+            schedule.check()?;
+        }
+        _ => return Err(format!("invalid fields for tx_type {}", tx.tx_type)),
     }
 
     // ---------------------------
@@ -110,12 +273,10 @@ pub fn basic_prevalidate(tx: &Tx) -> Result<(), String> {
     if tx.amount == 0 {
         return Err("amount must be > 0".into());
     }
+    // нижнюю границу fee задаёт base fee блока, её проверяет state
     if tx.fee == 0 {
         return Err("fee must be > 0".into());
     }
-    if tx.fee > tx.amount {
-        return Err("fee cannot be greater than amount".into());
-    }
 
     // nonce просто должен быть >0 (или >=0 если хочешь разрешить 0)
     // тут оставлю >=0, это u64 и так.
@@ -124,10 +285,7 @@ pub fn basic_prevalidate(tx: &Tx) -> Result<(), String> {
     // ---------------------------
     // timestamp: -10 минут / +5 минут в миллисекундах
     // ---------------------------
-    let now_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|_| "system time before UNIX_EPOCH".to_string())?
-        .as_millis() as i64;
+    let now_ms = now_ms as i64;
 
     let tx_ts = tx.timestamp as i64;
 
@@ -144,11 +302,9 @@ pub fn basic_prevalidate(tx: &Tx) -> Result<(), String> {
     // ---------------------------
     // pubkey
     // ---------------------------
-    if tx.pubkey.is_empty() {
-        return Err("pubkey is empty".into());
-    }
+    // пустой — компактная транза, pubkey восстановим из подписи
     // 33 байта в hex → 66 символов
-    if tx.pubkey.len() != 66 {
+    if !tx.pubkey.is_empty() && tx.pubkey.len() != 66 {
         return Err("pubkey must be 33 bytes (66 hex chars)".into());
     }
 
@@ -164,67 +320,18 @@ pub fn basic_prevalidate(tx: &Tx) -> Result<(), String> {
     }
 
     // ---------------------------
-    // encoding
+    // encoding, domain_tag, memo — по правилам версии
     // ---------------------------
     if tx.encoding.is_empty() {
         return Err("encoding is empty".into());
     }
-    if tx.encoding != "pipe_v1" {
-        return Err("encoding must be pipe_v1".into());
-    }
-
+    validate_tx_version(tx)?;
 
     Ok(())
 }
 
-pub fn verify_address_checksum(addr: &str) -> Result<(), String> {
-    use bech32::FromBase32;
-
-    // 1) decode bech32
-    let (hrp, data, _variant) =
-        bech32::decode(addr).map_err(|_| "invalid bech32 address".to_string())?;
-
-    // JS: decoded.hrp !== "gld2"
-    if hrp != "gld2" {
-        return Err("invalid address prefix (hrp must be gld2)".into());
-    }
-
-    // 2) convert 5-bit → 8-bit
-    let full_bytes: Vec<u8> = <Vec<u8> as FromBase32>::from_base32(&data)
-        .map_err(|_| "bech32 invalid base32 payload".to_string())?;
-
-    // JS: if (fullBytes.length < 28) return false;
-    if full_bytes.len() < 28 {
-        return Err(format!("invalid raw address length: {}", full_bytes.len()));
-    }
-
-    // берём первые 28 байт как в JS: const full28 = fullBytes.slice(0, 28);
-    let full28 = &full_bytes[..28];
-
-    // JS:
-    // if (full28[0] !== 'g'.charCodeAt(0) || ... '2')
-    if full28[0] != b'g'
-        || full28[1] != b'l'
-        || full28[2] != b'd'
-        || full28[3] != b'2'
-    {
-        return Err("address prefix bytes mismatch (expected gld2)".into());
-    }
-
-    // JS: const payload24 = full28.slice(0, 24);
-    //      const checksum = full28.slice(24, 28);
-    let payload24 = &full28[..24];
-    let checksum = &full28[24..28];
-
-    // JS: const hash = sha256.array(payload24);
-    let hash = sha256(payload24);
-
-    // JS: compare first 4 bytes hash vs checksum
-    if &hash[..4] != checksum {
-        return Err("invalid address checksum".into());
-    }
-
-    Ok(())
+pub fn verify_address_checksum(addr: &str, network: &NetworkProfile) -> Result<(), String> {
+    decode_address(addr, network).map(|_| ())
 }
 
 // -------------------------------------------------------------
@@ -244,53 +351,90 @@ fn verify_tx_hash(tx: &Tx) -> Result<(), String> {
 // -------------------------------------------------------------
 // 2) Проверка соответствия pubkey → address
 // -------------------------------------------------------------
-fn verify_address(tx: &Tx) -> Result<(), String> {
-    use bech32::ToBase32;
-
+fn verify_address(tx: &Tx, network: &NetworkProfile) -> Result<(), String> {
     let pubkey_bytes = hex_to_bytes(&tx.pubkey)?;
+    let addr = address_from_pubkey(&pubkey_bytes, network)?;
+
+    if addr != tx.from {
+        return Err("address does not match pubkey".into());
+    }
 
+    Ok(())
+}
+
+/// Адрес сети из сжатого pubkey (33 байта)
+pub fn address_from_pubkey(pubkey_bytes: &[u8], network: &NetworkProfile) -> Result<String, String> {
     if pubkey_bytes.len() != 33 {
         return Err("pubkey must be 33 bytes".into());
     }
 
-    // core = pubKey.slice(1)
-    let core = &pubkey_bytes[1..]; // 32 байта
+    // core = pubKey.slice(1), в адрес идут первые 20 байт
+    let mut core = [0u8; 20];
+    core.copy_from_slice(&pubkey_bytes[1..21]);
+    Ok(encode_address(&AddrKey::key(core), network))
+}
 
-    // payload24[0..4] = "gld2", [4..24] = core[0..20]
-    let mut payload24 = [0u8; 24];
-    payload24[0] = b'g';
-    payload24[1] = b'l';
-    payload24[2] = b'd';
-    payload24[3] = b'2';
-    payload24[4..24].copy_from_slice(&core[..20]);
+// -------------------------------------------------------------
+// 3) Проверка подписи
+// -------------------------------------------------------------
+fn verify_signature(tx: &Tx) -> Result<(), String> {
+    // === КРИТИЧЕСКИЙ МОМЕНТ ===
+    // JS: hashBytes = sha256.array(from_utf8(pipe_v1_str))
+    // elliptic.sign(hashHex) => подписываем ГОТОВЫЙ хэш
+    let msg_hash = sha256(build_pipe_string(tx).as_bytes());
+    verify_prehash_signature(&tx.pubkey, &tx.signature, &msg_hash)
+}
 
-    // hash = sha256(payload24)
-    let hash = sha256(&payload24);
+/// Pubkey (hex, сжатый) из подписи r|s|v под pipe-строкой транзы, как ecrecover.
+/// Подпись при этом проверяется: ключ, под который она не сходится, не вернётся.
+pub fn recover_pubkey(tx: &Tx) -> Result<String, String> {
+    let sig_bytes = hex_to_bytes(&tx.signature)?;
+    if sig_bytes.len() != 65 {
+        return Err("signature must be 65 bytes".into());
+    }
 
-    // full28 = payload24 + hash[..4]
-    let mut full28 = [0u8; 28];
-    full28[..24].copy_from_slice(&payload24);
-    full28[24..28].copy_from_slice(&hash[..4]);
+    let signature = Signature::from_slice(&sig_bytes[..64])
+        .map_err(|_| "invalid r,s signature".to_string())?;
+    let recovery_id = RecoveryId::from_byte(sig_bytes[64])
+        .ok_or_else(|| "invalid signature v".to_string())?;
 
-    // convertBits(8 -> 5) + bech32("gld2", ...)
-    let data5 = full28.to_base32();
+    let msg_hash = sha256(build_pipe_string(tx).as_bytes());
+    let verifying_key = VerifyingKey::recover_from_prehash(&msg_hash, &signature, recovery_id)
+        .map_err(|_| "failed to recover pubkey from signature".to_string())?;
 
-    let addr = bech32::encode("gld2", data5, bech32::Variant::Bech32)
-        .map_err(|_| "failed to encode bech32 address".to_string())?;
+    Ok(bytes_to_hex(verifying_key.to_encoded_point(true).as_bytes()))
+}
 
-    if addr != tx.from {
-        return Err("address does not match pubkey".into());
+// -------------------------------------------------------------
+// 2+3) Мультисиг: from — адрес политики, подписей не меньше threshold
+// -------------------------------------------------------------
+fn verify_multisig(tx: &Tx, network: &NetworkProfile) -> Result<(), String> {
+    let TxExtra::Multisig { threshold, pubkeys, signatures } = &tx.extra else {
+        return Err("not a multisig_transfer".into());
+    };
+
+    let pubkey_bytes = pubkeys.iter().map(|pk| hex_to_bytes(pk)).collect::<Result<Vec<_>, _>>()?;
+    check_policy(*threshold, &pubkey_bytes)?;
+    if multisig_address(*threshold, &pubkey_bytes, network)? != tx.from {
+        return Err("address does not match multisig policy".into());
+    }
+
+    // все подписывают одну и ту же строку
+    let msg_hash = sha256(build_pipe_string(tx).as_bytes());
+    for (index, sig) in signatures {
+        let pubkey = pubkeys
+            .get(*index as usize)
+            .ok_or_else(|| format!("multisig signer {} is out of range", index))?;
+        verify_prehash_signature(pubkey, sig, &msg_hash)
+            .map_err(|e| format!("multisig signer {}: {}", index, e))?;
     }
-
     Ok(())
 }
 
-// -------------------------------------------------------------
-// 3) Проверка подписи
-// -------------------------------------------------------------
-fn verify_signature(tx: &Tx) -> Result<(), String> {
-    let pubkey_bytes = hex_to_bytes(&tx.pubkey)?;
-    let sig_bytes = hex_to_bytes(&tx.signature)?;
+/// Подпись r|s|v (hex) под готовым sha256 от pubkey (hex, сжатый)
+fn verify_prehash_signature(pubkey: &str, signature: &str, msg_hash: &[u8; 32]) -> Result<(), String> {
+    let pubkey_bytes = hex_to_bytes(pubkey)?;
+    let sig_bytes = hex_to_bytes(signature)?;
 
     if pubkey_bytes.len() != 33 {
         return Err("pubkey must be 33 bytes".into());
@@ -310,23 +454,30 @@ fn verify_signature(tx: &Tx) -> Result<(), String> {
         VerifyingKey::from_sec1_bytes(&pubkey_bytes)
             .map_err(|_| "invalid pubkey".to_string())?;
 
-    // === КРИТИЧЕСКИЙ МОМЕНТ ===
-    // JS: hashBytes = sha256.array(from_utf8(pipe_v1_str))
-    // elliptic.sign(hashHex) => подписываем ГОТОВЫЙ хэш
-    let pipe_v1 = build_pipe_v1_string(tx);
-
-    let msg_hash = sha256(pipe_v1.as_bytes()); // [u8; 32]
-
     // k256 по умолчанию хэширует сам, поэтому используем prehash-API:
     verifying_key
-        .verify_prehash(&msg_hash, &signature)
+        .verify_prehash(msg_hash, &signature)
         .map_err(|_| "signature verification failed".to_string())
 }
 
 
-fn build_pipe_v1_string(tx: &Tx) -> String {
+/// Подписываемая строка в кодировке транзы
+pub fn build_pipe_string(tx: &Tx) -> String {
+    match tx.encoding.as_str() {
+        PIPE_V2 => build_pipe_v2_string(tx),
+        _ => build_pipe_v1_string(tx),
+    }
+}
+
+/// pipe_v2 = pipe_v1 | "pipe_v2" | длина memo | ":" | memo.
+/// Имя кодировки не даёт выдать v2-подпись за v1, длина — сдвинуть границу memo.
+pub fn build_pipe_v2_string(tx: &Tx) -> String {
+    format!("{}{}{}:{}", build_pipe_v1_string(tx), PIPE_V2, tx.memo.len(), tx.memo)
+}
+
+pub fn build_pipe_v1_string(tx: &Tx) -> String {
     // должен соответствовать JS pipe_v1_merge(domain_tag, chain_id, tx_type, from, to, token, amount, fee, nonce, timestamp)
-    format!(
+    let base = format!(
         "{}{}{}{}{}{}{}{}{}{}",
         tx.domain_tag,
         tx.chain_id,
@@ -338,13 +489,36 @@ fn build_pipe_v1_string(tx: &Tx) -> String {
         tx.fee,
         tx.nonce,
         tx.timestamp,
-    )
+    );
+
+    // поля типа идут следом: create_token — decimals, initial_supply;
+    // multi_send — пары to, amount по порядку
+    match &tx.extra {
+        TxExtra::None => base,
+        TxExtra::CreateToken { decimals, initial_supply } => format!("{}{}{}", base, decimals, initial_supply),
+        TxExtra::MultiSend(payouts) => payouts
+            .iter()
+            .fold(base, |s, (to, amount)| format!("{}{}{}", s, to, amount)),
+        // политика уже зашита в адрес from, подписи в строку не входят
+        TxExtra::Multisig { .. } => base,
+        TxExtra::Vesting(v) => format!("{}{}{}{}{}", base, v.clock.to_byte(), v.start, v.cliff, v.duration),
+    }
 }
+/// Компактный raw tx: raw tx без pubkey (его восстанавливают из подписи).
+/// tx_hash у транзы тот же, что и у полной формы.
+pub fn build_compact_raw_tx(tx: &Tx) -> Result<Vec<u8>, String> {
+    let mut raw = build_raw_tx_from_struct(tx)?;
+    if !tx.pubkey.is_empty() {
+        raw.drain(RAW_TX_PUBKEY_AT..RAW_TX_PUBKEY_AT + 33);
+    }
+    Ok(raw)
+}
+
 // -------------------------------------------------------------
-// RAW_TX Сборщик (НО ОН НЕ ДОЛЖЕН УХОДИТЬ В ПРОТОКОЛ)
-// Он нужен только ВНУТРИ validate_tx.
+// RAW_TX Сборщик
+// Нужен внутри validate_tx и кошельку (wallet) для подписи транз.
 // -------------------------------------------------------------
-fn build_raw_tx_from_struct(tx: &Tx) -> Result<Vec<u8>, String> {
+pub fn build_raw_tx_from_struct(tx: &Tx) -> Result<Vec<u8>, String> {
     let mut buf = Vec::new();
 
     push(&mut buf, &tx.domain_tag, 9)?;
@@ -352,7 +526,7 @@ fn build_raw_tx_from_struct(tx: &Tx) -> Result<Vec<u8>, String> {
     buf.push(tx_type_byte(&tx.tx_type));
     push(&mut buf, &tx.from, 56)?;
     push(&mut buf, &tx.to, 56)?;
-    buf.push(token_byte(&tx.token));
+    buf.push(tx.token_id);
 
     buf.extend_from_slice(&tx.amount.to_be_bytes());
     buf.extend_from_slice(&tx.fee.to_be_bytes());
@@ -367,11 +541,70 @@ fn build_raw_tx_from_struct(tx: &Tx) -> Result<Vec<u8>, String> {
 
     push(&mut buf, &tx.encoding, 7)?;
 
+    match &tx.extra {
+        TxExtra::None => {}
+        // хвост create_token: symbol_len (u8) | symbol | decimals (u8) | initial_supply (u64)
+        TxExtra::CreateToken { decimals, initial_supply } => {
+            let symbol = tx.token.as_bytes();
+            if symbol.len() > u8::MAX as usize {
+                return Err("token symbol too long".into());
+            }
+            buf.push(symbol.len() as u8);
+            buf.extend_from_slice(symbol);
+            buf.push(*decimals);
+            buf.extend_from_slice(&initial_supply.to_be_bytes());
+        }
+        // хвост multi_send: count (u16) | count * (to (56) | amount (u64))
+        TxExtra::MultiSend(payouts) => {
+            if payouts.len() > u16::MAX as usize {
+                return Err("too many multi_send recipients".into());
+            }
+            buf.extend_from_slice(&(payouts.len() as u16).to_be_bytes());
+            for (to, amount) in payouts {
+                push(&mut buf, to, 56)?;
+                buf.extend_from_slice(&amount.to_be_bytes());
+            }
+        }
+        // хвост multisig_transfer: threshold (u8) | n (u8) | n * pubkey (33)
+        //                          | m (u8) | m * (index (u8) | signature (65))
+        TxExtra::Multisig { threshold, pubkeys, signatures } => {
+            if pubkeys.len() > MAX_MULTISIG_KEYS || signatures.len() > MAX_MULTISIG_KEYS {
+                return Err("too many multisig keys".into());
+            }
+            buf.push(*threshold);
+            buf.push(pubkeys.len() as u8);
+            for pk in pubkeys {
+                push_hex(&mut buf, pk, 33, "multisig pubkey")?;
+            }
+            buf.push(signatures.len() as u8);
+            for (index, sig) in signatures {
+                buf.push(*index);
+                push_hex(&mut buf, sig, 65, "multisig signature")?;
+            }
+        }
+        // хвост vesting_transfer: clock (u8) | start (u64) | cliff (u64) | duration (u64)
+        TxExtra::Vesting(v) => {
+            buf.push(v.clock.to_byte());
+            buf.extend_from_slice(&v.start.to_be_bytes());
+            buf.extend_from_slice(&v.cliff.to_be_bytes());
+            buf.extend_from_slice(&v.duration.to_be_bytes());
+        }
+    }
+
+    // pipe_v2: memo_len (u8) | memo
+    if tx.encoding == PIPE_V2 {
+        if tx.memo.len() > MAX_MEMO_LEN {
+            return Err(format!("memo must be at most {} bytes", MAX_MEMO_LEN));
+        }
+        buf.push(tx.memo.len() as u8);
+        buf.extend_from_slice(tx.memo.as_bytes());
+    }
+
     Ok(buf)
 }
 
 // -------------------------------------------------------------
-fn sha256(data: &[u8]) -> [u8; 32] {
+pub fn sha256(data: &[u8]) -> [u8; 32] {
     let mut h = Sha256::new();
     h.update(data);
     h.finalize().into()
@@ -387,8 +620,18 @@ fn push(buf: &mut Vec<u8>, s: &str, len: usize) -> Result<(), String> {
     Ok(())
 }
 
-pub fn return_structured_tx(tx: &Tx) -> ValidTxCore {
-    ValidTxCore {
+fn push_hex(buf: &mut Vec<u8>, hex: &str, len: usize, field: &str) -> Result<(), String> {
+    let bytes = hex_to_bytes(hex)?;
+    if bytes.len() != len {
+        return Err(format!("{} must be {} bytes, got {}", field, len, bytes.len()));
+    }
+    buf.extend_from_slice(&bytes);
+    Ok(())
+}
+
+/// Проверенная транза -> ValidTxCore (адреса переводим в ключи state, код токена — из raw tx)
+pub fn return_structured_tx(tx: &Tx, network: &NetworkProfile) -> Result<ValidTxCore, String> {
+    Ok(ValidTxCore {
         tx_hash: tx.tx_hash.clone(),
         tx_type: tx.tx_type.clone(),
         from: tx.from.clone(),
@@ -400,30 +643,82 @@ pub fn return_structured_tx(tx: &Tx) -> ValidTxCore {
         timestamp: tx.timestamp,
         pubkey: tx.pubkey.clone(),
         signature: tx.signature.clone(),
+        extra: tx.extra.clone(),
+        memo: tx.memo.clone(),
+        encoding: tx.encoding.clone(),
+        from_key: decode_address(&tx.from, network)?,
+        to_key: decode_address(&tx.to, network)?,
+        token_id: tx.token_id,
+        payouts: match &tx.extra {
+            TxExtra::MultiSend(payouts) => payouts
+                .iter()
+                .map(|(to, amount)| Ok((decode_address(to, network)?, *amount)))
+                .collect::<Result<_, String>>()?,
+            _ => Vec::new(),
+        },
+    })
+}
+
+/// Обратно в Tx: raw tx из него — те же байты, что подписал отправитель
+/// (полная форма, даже если транза пришла компактной)
+pub fn tx_from_core(tx: &ValidTxCore, network: &NetworkProfile) -> Tx {
+    Tx {
+        tx_hash: tx.tx_hash.clone(),
+        domain_tag: "GLD_TX_v1".into(),
+        chain_id: network.chain_id.into(),
+        tx_type: tx.tx_type.clone(),
+        from: tx.from.clone(),
+        to: tx.to.clone(),
+        token: tx.token.clone(),
+        token_id: tx.token_id,
+        amount: tx.amount,
+        fee: tx.fee,
+        nonce: tx.nonce,
+        timestamp: tx.timestamp,
+        pubkey: tx.pubkey.clone(),
+        signature: tx.signature.clone(),
+        encoding: tx.encoding.clone(),
+        extra: tx.extra.clone(),
+        memo: tx.memo.clone(),
     }
 }
 
-fn tx_type_byte(s: &str) -> u8 { if s == "transfer" {1} else {0} }
-fn token_byte(s: &str) -> u8 { if s == "GLD" {1} else {0} }
+fn tx_type_byte(s: &str) -> u8 {
+    match s {
+        "transfer" => 1,
+        "create_token" => 2,
+        "mint" => 3,
+        "burn" => 4,
+        "multi_send" => 5,
+        "multisig_transfer" => 6,
+        "vesting_transfer" => 7,
+        "stake" => 8,
+        "unstake" => 9,
+        "delegate" => 10,
+        _ => 0,
+    }
+}
 
-fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
-    if hex.len() % 2 != 0 {
+pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
         return Err("hex string has odd length".into());
     }
 
     let mut bytes = Vec::with_capacity(hex.len() / 2);
 
-    for i in (0..hex.len()).step_by(2) {
-        let byte_str = &hex[i..i + 2];
-        let byte = u8::from_str_radix(byte_str, 16)
-            .map_err(|_| format!("invalid hex byte: {}", byte_str))?;
+    // по байтам, а не срезами строки: срез посреди UTF-8 символа паникует
+    for pair in hex.as_bytes().chunks(2) {
+        let byte = std::str::from_utf8(pair)
+            .ok()
+            .and_then(|byte_str| u8::from_str_radix(byte_str, 16).ok())
+            .ok_or_else(|| format!("invalid hex byte: {}", String::from_utf8_lossy(pair)))?;
         bytes.push(byte);
     }
 
     Ok(bytes)
 }
 
-fn bytes_to_hex(bytes: &[u8]) -> String {
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
     let mut hex = String::with_capacity(bytes.len() * 2);
     for b in bytes {
         hex.push_str(&format!("{:02x}", b));
@@ -0,0 +1,138 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tokio::sync::oneshot;
+use tracing::{error, info};
+
+use crate::model::Tx;
+use crate::network::NetworkProfile;
+use crate::tx::{ValidTxCore, validate_tx};
+
+/// Ответ на транзу, когда очередь проверки заполнена
+pub const BUSY_REASON: &str = "busy";
+
+/// Очередь проверки заполнена — транзу не смотрели, её можно прислать снова
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Busy;
+
+/// Результат проверки одной транзы
+pub type Checked = Result<ValidTxCore, String>;
+
+/// Пул для проверки транз (secp256k1, bech32, хеши).
+///
+/// Эта работа целиком на CPU: на воркере Tokio она держит реактор,
+/// пока висят все остальные соединения. Здесь она идёт на отдельных
+/// потоках, а очередь ограничена: сверх verify_queue транз сразу Busy.
+pub struct VerifyPool {
+    threads: usize,
+    capacity: usize,
+    /// Потоки поднимаем при первой проверке (симулятору пул не нужен)
+    pool: OnceLock<ThreadPool>,
+    /// Транз в очереди и в работе
+    queued: Arc<AtomicUsize>,
+}
+
+/// Занятые места в очереди; освобождаются, когда проверка закончилась
+struct Slots {
+    queued: Arc<AtomicUsize>,
+    n: usize,
+}
+
+impl Drop for Slots {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(self.n, Ordering::AcqRel);
+    }
+}
+
+impl VerifyPool {
+    /// threads = 0 — по числу ядер
+    pub fn new(threads: usize, capacity: usize) -> Self {
+        VerifyPool {
+            threads,
+            capacity,
+            pool: OnceLock::new(),
+            queued: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Сколько транз сейчас ждёт или проходит проверку
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::Acquire)
+    }
+
+    fn pool(&self) -> &ThreadPool {
+        self.pool.get_or_init(|| {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(self.threads)
+                .thread_name(|i| format!("forgex-verify-{}", i))
+                .build()
+                .expect("failed to start verify pool");
+            info!(threads = pool.current_num_threads(), queue = self.capacity, "verify pool started");
+            pool
+        })
+    }
+
+    fn reserve(&self, n: usize) -> Result<Slots, Busy> {
+        self.queued
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |q| {
+                (q + n <= self.capacity).then_some(q + n)
+            })
+            .map(|_| Slots { queued: self.queued.clone(), n })
+            .map_err(|_| Busy)
+    }
+
+    /// Проверить одну транзу на пуле
+    pub async fn verify(&self, tx: Tx, now_ms: u64, network: &'static NetworkProfile) -> Result<Checked, Busy> {
+        let slots = self.reserve(1)?;
+        let (done, result) = oneshot::channel();
+
+        self.pool().spawn(move || {
+            let checked = validate_tx(&tx, now_ms, network);
+            drop(slots);
+            let _ = done.send(checked);
+        });
+
+        Ok(result.await.unwrap_or_else(|_| {
+            error!("verify job dropped without result");
+            Err("internal verification error".into())
+        }))
+    }
+
+    /// Проверить пачку транз параллельно; результаты в том же порядке.
+    /// Пачка занимает в очереди столько мест, сколько в ней транз;
+    /// пачка больше всей очереди отклоняется целиком.
+    pub async fn verify_batch(
+        &self,
+        txs: Vec<Tx>,
+        now_ms: u64,
+        network: &'static NetworkProfile,
+    ) -> Result<Vec<Checked>, Busy> {
+        if txs.is_empty() {
+            return Ok(Vec::new());
+        }
+        // такая пачка не влезет в очередь никогда: отвечаем не busy,
+        // иначе клиент будет повторять её вечно
+        if txs.len() > self.capacity {
+            let reason = format!("batch of {} txs exceeds verify queue of {}", txs.len(), self.capacity);
+            return Ok(vec![Err(reason); txs.len()]);
+        }
+
+        let slots = self.reserve(txs.len())?;
+        let (done, result) = oneshot::channel();
+        let n = txs.len();
+
+        let pool = self.pool();
+        pool.spawn(move || {
+            let checked: Vec<Checked> = txs.par_iter().map(|tx| validate_tx(tx, now_ms, network)).collect();
+            drop(slots);
+            let _ = done.send(checked);
+        });
+
+        Ok(result.await.unwrap_or_else(|_| {
+            error!("verify batch job dropped without result");
+            vec![Err("internal verification error".into()); n]
+        }))
+    }
+}
@@ -0,0 +1,265 @@
+use k256::ecdsa::SigningKey;
+
+use crate::model::Tx;
+use crate::network::NetworkProfile;
+use crate::state::{GLD, GLD_SYMBOL, NO_TOKEN, TokenId, VestingSchedule};
+use crate::tx::{PIPE_V1, PIPE_V2, TxExtra, address_from_pubkey, build_pipe_string, build_raw_tx_from_struct, bytes_to_hex, sha256};
+
+/// Ключ + адрес в сети. Собирает и подписывает транзы так же, как JS-кошелёк.
+pub struct Wallet {
+    key: SigningKey,
+    pub pubkey: Vec<u8>,
+    pub address: String,
+    /// Сеть: от неё адрес и chain_id транз
+    pub network: &'static NetworkProfile,
+}
+
+impl Wallet {
+    /// Кошелёк сети из 32-байтного приватного ключа
+    pub fn from_secret(secret: &[u8], network: &'static NetworkProfile) -> Result<Self, String> {
+        let key = SigningKey::from_slice(secret).map_err(|_| "invalid private key".to_string())?;
+        let pubkey = key.verifying_key().to_encoded_point(true).as_bytes().to_vec();
+        let address = address_from_pubkey(&pubkey, network)?;
+        Ok(Wallet { key, pubkey, address, network })
+    }
+
+    /// Детерминированный кошелёк: один и тот же (seed, index) — тот же ключ.
+    /// Только для тестовых сетей и симулятора.
+    pub fn from_seed(seed: u64, index: u64, network: &'static NetworkProfile) -> Self {
+        let mut material = Vec::with_capacity(13 + 8 + 8 + 8);
+        material.extend_from_slice(b"forgex-wallet");
+        material.extend_from_slice(&seed.to_be_bytes());
+        material.extend_from_slice(&index.to_be_bytes());
+
+        // sha256 почти всегда даёт валидный скаляр, но на всякий случай перебираем
+        for attempt in 0u64.. {
+            let mut m = material.clone();
+            m.extend_from_slice(&attempt.to_be_bytes());
+            if let Ok(w) = Wallet::from_secret(&sha256(&m), network) {
+                return w;
+            }
+        }
+        unreachable!()
+    }
+
+    /// Приватный ключ в hex (для вывода ключей тестовой сети)
+    pub fn secret_hex(&self) -> String {
+        bytes_to_hex(&self.key.to_bytes())
+    }
+
+    /// Собрать и подписать transfer GLD
+    pub fn transfer(&self, to: &str, amount: u64, fee: u64, nonce: u64, timestamp: u64) -> Result<Tx, String> {
+        self.transfer_token(GLD_SYMBOL, GLD, to, amount, fee, nonce, timestamp)
+    }
+
+    /// Собрать и подписать transfer любого токена (символ и код — из реестра)
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_token(
+        &self,
+        token: &str,
+        token_id: TokenId,
+        to: &str,
+        amount: u64,
+        fee: u64,
+        nonce: u64,
+        timestamp: u64,
+    ) -> Result<Tx, String> {
+        self.sign(Tx {
+            tx_type: "transfer".into(),
+            to: to.to_string(),
+            token: token.to_string(),
+            token_id,
+            amount,
+            ..self.unsigned(fee, nonce, timestamp)
+        })
+    }
+
+    /// Собрать и подписать create_token: issuer получит initial_supply
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_token(
+        &self,
+        symbol: &str,
+        decimals: u8,
+        max_supply: u64,
+        initial_supply: u64,
+        issuer: &str,
+        fee: u64,
+        nonce: u64,
+        timestamp: u64,
+    ) -> Result<Tx, String> {
+        self.sign(Tx {
+            tx_type: "create_token".into(),
+            to: issuer.to_string(),
+            token: symbol.to_string(),
+            token_id: NO_TOKEN,
+            amount: max_supply,
+            extra: TxExtra::CreateToken { decimals, initial_supply },
+            ..self.unsigned(fee, nonce, timestamp)
+        })
+    }
+
+    /// Собрать и подписать mint: выпустить amount токена на to (только issuer)
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint(
+        &self,
+        token: &str,
+        token_id: TokenId,
+        to: &str,
+        amount: u64,
+        fee: u64,
+        nonce: u64,
+        timestamp: u64,
+    ) -> Result<Tx, String> {
+        self.sign(Tx {
+            tx_type: "mint".into(),
+            to: to.to_string(),
+            token: token.to_string(),
+            token_id,
+            amount,
+            ..self.unsigned(fee, nonce, timestamp)
+        })
+    }
+
+    /// Собрать и подписать burn: сжечь amount своего токена
+    pub fn burn(&self, token: &str, token_id: TokenId, amount: u64, fee: u64, nonce: u64, timestamp: u64) -> Result<Tx, String> {
+        self.sign(Tx {
+            tx_type: "burn".into(),
+            to: self.address.clone(),
+            token: token.to_string(),
+            token_id,
+            amount,
+            ..self.unsigned(fee, nonce, timestamp)
+        })
+    }
+
+    /// Собрать и подписать vesting_transfer: amount придёт на to залоченным
+    #[allow(clippy::too_many_arguments)]
+    pub fn vesting_transfer(
+        &self,
+        token: &str,
+        token_id: TokenId,
+        to: &str,
+        amount: u64,
+        schedule: VestingSchedule,
+        fee: u64,
+        nonce: u64,
+        timestamp: u64,
+    ) -> Result<Tx, String> {
+        self.sign(Tx {
+            tx_type: "vesting_transfer".into(),
+            to: to.to_string(),
+            token: token.to_string(),
+            token_id,
+            amount,
+            extra: TxExtra::Vesting(schedule),
+            ..self.unsigned(fee, nonce, timestamp)
+        })
+    }
+
+    /// Собрать и подписать stake: amount GLD в свой стейк валидатора
+    pub fn stake(&self, amount: u64, fee: u64, nonce: u64, timestamp: u64) -> Result<Tx, String> {
+        self.staking("stake", &self.address, amount, fee, nonce, timestamp)
+    }
+
+    /// Собрать и подписать delegate: amount GLD в стейк валидатора
+    pub fn delegate(&self, validator: &str, amount: u64, fee: u64, nonce: u64, timestamp: u64) -> Result<Tx, String> {
+        self.staking("delegate", validator, amount, fee, nonce, timestamp)
+    }
+
+    /// Собрать и подписать unstake: вывести amount из стейка на validator (свой — validator = свой адрес)
+    pub fn unstake(&self, validator: &str, amount: u64, fee: u64, nonce: u64, timestamp: u64) -> Result<Tx, String> {
+        self.staking("unstake", validator, amount, fee, nonce, timestamp)
+    }
+
+    /// Собрать и подписать multi_send: одна подпись и один nonce на все выплаты
+    pub fn multi_send(
+        &self,
+        token: &str,
+        token_id: TokenId,
+        payouts: &[(String, u64)],
+        fee: u64,
+        nonce: u64,
+        timestamp: u64,
+    ) -> Result<Tx, String> {
+        let amount = payouts
+            .iter()
+            .try_fold(0u64, |acc, (_, amount)| acc.checked_add(*amount))
+            .ok_or_else(|| "overflow on multi_send total".to_string())?;
+        self.sign(Tx {
+            tx_type: "multi_send".into(),
+            to: self.address.clone(),
+            token: token.to_string(),
+            token_id,
+            amount,
+            extra: TxExtra::MultiSend(payouts.to_vec()),
+            ..self.unsigned(fee, nonce, timestamp)
+        })
+    }
+
+    /// stake / delegate / unstake отличаются только типом: GLD, to — валидатор
+    fn staking(&self, tx_type: &str, validator: &str, amount: u64, fee: u64, nonce: u64, timestamp: u64) -> Result<Tx, String> {
+        self.sign(Tx {
+            tx_type: tx_type.into(),
+            to: validator.to_string(),
+            token: GLD_SYMBOL.into(),
+            token_id: GLD,
+            amount,
+            ..self.unsigned(fee, nonce, timestamp)
+        })
+    }
+
+    /// Заготовка транзы от этого кошелька без подписи
+    fn unsigned(&self, fee: u64, nonce: u64, timestamp: u64) -> Tx {
+        Tx {
+            tx_hash: String::new(),
+            domain_tag: "GLD_TX_v1".into(),
+            chain_id: self.network.chain_id.into(),
+            tx_type: String::new(),
+            from: self.address.clone(),
+            to: String::new(),
+            token: String::new(),
+            token_id: NO_TOKEN,
+            amount: 0,
+            fee,
+            nonce,
+            timestamp,
+            pubkey: bytes_to_hex(&self.pubkey),
+            signature: String::new(),
+            encoding: PIPE_V1.into(),
+            extra: TxExtra::None,
+            memo: String::new(),
+        }
+    }
+
+    /// Переподписать свою транзу в pipe_v2 с memo (memo входит в подпись и tx_hash)
+    pub fn with_memo(&self, tx: Tx, memo: &str) -> Result<Tx, String> {
+        self.sign(Tx {
+            encoding: PIPE_V2.into(),
+            memo: memo.to_string(),
+            ..tx
+        })
+    }
+
+    /// Подпись sha256(pipe-строки в кодировке транзы) в hex, r|s|v (65 байт)
+    pub fn sign_pipe(&self, tx: &Tx) -> Result<String, String> {
+        let msg_hash = sha256(build_pipe_string(tx).as_bytes());
+        let (sig, recid) = self
+            .key
+            .sign_prehash_recoverable(&msg_hash)
+            .map_err(|_| "failed to sign tx".to_string())?;
+
+        let mut sig65 = sig.to_bytes().to_vec();
+        sig65.push(recid.to_byte());
+        Ok(bytes_to_hex(&sig65))
+    }
+
+    /// Подписать транзу и посчитать tx_hash
+    fn sign(&self, mut tx: Tx) -> Result<Tx, String> {
+        tx.signature = self.sign_pipe(&tx)?;
+
+        // tx_hash = sha256(raw_tx)
+        tx.tx_hash = bytes_to_hex(&sha256(&build_raw_tx_from_struct(&tx)?));
+
+        Ok(tx)
+    }
+}
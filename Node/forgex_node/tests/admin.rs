@@ -0,0 +1,94 @@
+//! Админка: токен, дампы только в admin_dump_dir, только свой слушатель на loopback.
+//!
+//!   cargo test --test admin
+
+use std::path::Path;
+
+use forgex_node::admin::{ADMIN_DUMP_MEMPOOL, ADMIN_DUMP_STATE, ADMIN_STATUS, handle_admin};
+use forgex_node::config::{NodeConfig, load_config};
+use forgex_node::handler::handle_message;
+use forgex_node::model::{decode_admin_response, encode_admin_request};
+use forgex_node::node::Node;
+
+const TOKEN: &str = "admin-secret";
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("forgex-admin-{}-{}", name, std::process::id())).to_string_lossy().into_owned()
+}
+
+fn node(dump_dir: &str) -> Node {
+    Node::new(NodeConfig {
+        admin_token: TOKEN.into(),
+        admin_dump_dir: dump_dir.into(),
+        blocks_log_path: String::new(),
+        ..NodeConfig::default()
+    })
+}
+
+fn admin(node: &Node, token: &str, cmd: u8, args: &[u8]) -> (bool, String) {
+    decode_admin_response(&handle_admin(node, token.as_bytes(), cmd, args)).unwrap()
+}
+
+#[test]
+fn wrong_or_missing_token_is_unauthorized() {
+    let node = node(&temp_path("token"));
+
+    assert!(admin(&node, TOKEN, ADMIN_STATUS, &[]).0);
+    assert_eq!(admin(&node, "admin-secreT", ADMIN_STATUS, &[]), (false, "unauthorized".into()));
+    assert_eq!(admin(&node, "", ADMIN_STATUS, &[]), (false, "unauthorized".into()));
+
+    // без токена в конфиге админки нет вовсе
+    let disabled = Node::new(NodeConfig { blocks_log_path: String::new(), ..NodeConfig::default() });
+    assert_eq!(admin(&disabled, "", ADMIN_STATUS, &[]), (false, "admin interface disabled".into()));
+}
+
+#[test]
+fn dumps_stay_inside_admin_dump_dir() {
+    let dir = temp_path("dumps");
+    let node = node(&dir);
+
+    let (ok, text) = admin(&node, TOKEN, ADMIN_DUMP_STATE, b"state.txt");
+    assert!(ok, "{}", text);
+    assert!(Path::new(&dir).join("state.txt").exists());
+    let (ok, text) = admin(&node, TOKEN, ADMIN_DUMP_MEMPOOL, b"daily/mempool.txt");
+    assert!(ok, "{}", text);
+    assert!(Path::new(&dir).join("daily/mempool.txt").exists());
+
+    let outside = temp_path("outside.txt");
+    for path in [outside.as_str(), "../outside.txt", "daily/../../outside.txt", "./state.txt", ""] {
+        for cmd in [ADMIN_DUMP_STATE, ADMIN_DUMP_MEMPOOL] {
+            let (ok, text) = admin(&node, TOKEN, cmd, path.as_bytes());
+            assert!(!ok, "{} was accepted: {}", path, text);
+        }
+    }
+    assert!(!Path::new(&outside).exists());
+    assert!(!Path::new(&dir).parent().unwrap().join("outside.txt").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn p2p_port_does_not_serve_admin() {
+    let node = node(&temp_path("p2p"));
+
+    let request = encode_admin_request(TOKEN, ADMIN_STATUS, &[]).unwrap();
+    let (ok, text) = decode_admin_response(&handle_message(&node, request)).unwrap();
+    assert!(!ok);
+    assert!(text.contains("admin_addr"), "unexpected response: {}", text);
+}
+
+#[test]
+fn admin_addr_must_be_loopback() {
+    let path = temp_path("config.toml");
+
+    for (addr, accepted) in [("127.0.0.1:5051", true), ("[::1]:5051", true), ("0.0.0.0:5051", false), ("10.0.0.5:5051", false), ("localhost", false)] {
+        std::fs::write(&path, format!("admin_token = \"{}\"\nadmin_addr = \"{}\"\n", TOKEN, addr)).unwrap();
+        assert_eq!(load_config(&path).is_ok(), accepted, "admin_addr {}", addr);
+    }
+
+    // на одном порту с P2P — тоже нет
+    std::fs::write(&path, "p2p_addr = \"127.0.0.1:5050\"\nadmin_addr = \"127.0.0.1:5050\"\n").unwrap();
+    assert!(load_config(&path).is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
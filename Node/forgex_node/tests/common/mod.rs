@@ -0,0 +1,92 @@
+//! Общие заготовки интеграционных тестов: генезис, транзы без подписи, блоки.
+//! Каждый тест берёт не всё, отсюда allow(dead_code).
+
+#![allow(dead_code)]
+
+use forgex_node::block::{Block, BlockBody, BlockHeader, Reward};
+use forgex_node::config::{FeeConfig, GenesisBalance, RewardConfig};
+use forgex_node::model::Tx;
+use forgex_node::network::DEVNET;
+use forgex_node::state::{ChainState, GLD, TokenId};
+use forgex_node::tx::{TxExtra, ValidTxCore, return_structured_tx};
+use forgex_node::wallet::Wallet;
+
+pub const NOW_MS: u64 = 1_700_000_000_000;
+
+/// Он же min_base_fee в FEES
+pub const FEE: u64 = 10;
+
+/// Без доли производителя: fee делится между сжиганием и казной
+pub const FEES: FeeConfig = FeeConfig { min_base_fee: FEE, target_block_txs: 10, burn_percent: 50, proposer_percent: 0 };
+
+/// Без выпуска: блоку положены только комиссии
+pub const NO_ISSUANCE: RewardConfig = RewardConfig { block_reward: 0, halving_interval: 0 };
+
+/// Devnet, где у address есть gld GLD, а больше ничего
+pub fn genesis(address: &str, gld: u64) -> ChainState {
+    genesis_with(address, gld, &FEES)
+}
+
+pub fn genesis_with(address: &str, gld: u64, fees: &FeeConfig) -> ChainState {
+    let allocations = [GenesisBalance { address: address.into(), token: "GLD".into(), amount: gld }];
+    ChainState::genesis(&DEVNET, &allocations, fees, &NO_ISSUANCE)
+}
+
+/// Подписи не нужны: apply_block их не проверяет
+#[allow(clippy::too_many_arguments)]
+pub fn unsigned(
+    from: &Wallet,
+    to: &str,
+    tx_type: &str,
+    token: &str,
+    token_id: TokenId,
+    amount: u64,
+    fee: u64,
+    nonce: u64,
+    extra: TxExtra,
+) -> ValidTxCore {
+    let tx = Tx {
+        tx_hash: format!("{:064x}", nonce),
+        domain_tag: "GLD_TX_v1".into(),
+        chain_id: DEVNET.chain_id.into(),
+        tx_type: tx_type.into(),
+        from: from.address.clone(),
+        to: to.into(),
+        token: token.into(),
+        token_id,
+        amount,
+        fee,
+        nonce,
+        timestamp: 0,
+        pubkey: String::new(),
+        signature: String::new(),
+        encoding: "pipe_v1".into(),
+        extra,
+        memo: String::new(),
+    };
+    return_structured_tx(&tx, &DEVNET).unwrap()
+}
+
+pub fn transfer(from: &Wallet, to: &Wallet, amount: u64, nonce: u64) -> ValidTxCore {
+    unsigned(from, &to.address, "transfer", "GLD", GLD, amount, FEE, nonce, TxExtra::None)
+}
+
+pub fn block(height: u64, txs: Vec<ValidTxCore>, rewards: Vec<Reward>) -> Block {
+    block_at(height, 0, txs, rewards)
+}
+
+pub fn block_at(height: u64, timestamp_ms: u64, txs: Vec<ValidTxCore>, rewards: Vec<Reward>) -> Block {
+    Block {
+        block_id: format!("block-{}", height),
+        header: BlockHeader {
+            version: "0.1".into(),
+            chain_id: DEVNET.chain_id.into(),
+            height,
+            prev_hash: "0".repeat(64),
+            timestamp_ms: timestamp_ms as i64,
+            tx_count: txs.len() as u32,
+            tx_root: String::new(),
+        },
+        body: BlockBody { txs, rewards },
+    }
+}
@@ -0,0 +1,182 @@
+//! Конверт транз: разбор по версиям, допуск версий и компактная форма.
+//!
+//!   cargo test --test envelope
+
+mod common;
+
+use common::{NOW_MS, genesis};
+use forgex_node::envelope::{
+    TX_ENVELOPE_MARKER, TX_VERSION_COMPACT, TX_VERSION_PIPE_V1, TX_VERSION_PIPE_V2, check_tx_version_accepted,
+    compact_tx, decode_tx, encode_tx, enveloped_tx_len,
+};
+use forgex_node::model::Tx;
+use forgex_node::network::DEVNET;
+use forgex_node::state::{balance, nonce, select_applicable};
+use forgex_node::tx::{build_raw_tx_from_struct, recover_pubkey, validate_tx};
+use forgex_node::wallet::Wallet;
+
+fn pipe_v1() -> Tx {
+    let (alice, bob) = (Wallet::from_seed(3, 0, &DEVNET), Wallet::from_seed(3, 1, &DEVNET));
+    alice.transfer(&bob.address, 100, 10, 1, NOW_MS).unwrap()
+}
+
+fn pipe_v2() -> Tx {
+    let alice = Wallet::from_seed(3, 0, &DEVNET);
+    alice.with_memo(pipe_v1(), "invoice 42").unwrap()
+}
+
+/// tx_hash в raw tx не входит (его несёт SEND_TX), символ токена тоже —
+/// только код, символ нода берёт из реестра
+fn assert_same(decoded: &Tx, tx: &Tx) {
+    let expected = Tx { tx_hash: String::new(), token: String::new(), ..tx.clone() };
+    assert_eq!(format!("{:?}", decoded), format!("{:?}", expected));
+}
+
+fn roundtrip(tx: &Tx, version: u8) {
+    let data = encode_tx(tx).unwrap();
+    assert_eq!(&data[..2], &[TX_ENVELOPE_MARKER, version]);
+    assert_eq!(enveloped_tx_len(&data).unwrap(), data.len());
+    assert_same(&decode_tx(&data).unwrap(), tx);
+}
+
+#[test]
+fn pipe_v1_roundtrips() {
+    roundtrip(&pipe_v1(), TX_VERSION_PIPE_V1);
+}
+
+#[test]
+fn pipe_v2_roundtrips() {
+    roundtrip(&pipe_v2(), TX_VERSION_PIPE_V2);
+}
+
+#[test]
+fn raw_tx_without_envelope_is_read_by_encoding() {
+    for tx in [pipe_v1(), pipe_v2()] {
+        let raw = build_raw_tx_from_struct(&tx).unwrap();
+        assert_eq!(enveloped_tx_len(&raw).unwrap(), raw.len());
+        assert_same(&decode_tx(&raw).unwrap(), &tx);
+    }
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+    let mut data = encode_tx(&pipe_v1()).unwrap();
+    data[1] = 9;
+    assert_eq!(decode_tx(&data).unwrap_err(), "unknown tx version 9");
+    assert!(enveloped_tx_len(&data).is_err());
+
+    assert!(decode_tx(&[TX_ENVELOPE_MARKER]).is_err());
+}
+
+#[test]
+fn envelope_version_must_match_body() {
+    // тело pipe_v2 под версией pipe_v1
+    let mut data = encode_tx(&pipe_v2()).unwrap();
+    data[1] = TX_VERSION_PIPE_V1;
+    let err = decode_tx(&data).unwrap_err();
+    assert!(err.contains("requires encoding pipe_v1"), "unexpected error: {}", err);
+}
+
+#[test]
+fn only_accepted_versions_pass() {
+    let (v1, v2) = (pipe_v1(), pipe_v2());
+    let all = [TX_VERSION_PIPE_V1, TX_VERSION_PIPE_V2];
+
+    assert!(check_tx_version_accepted(&v1, &all).is_ok());
+    assert!(check_tx_version_accepted(&v2, &all).is_ok());
+
+    assert!(check_tx_version_accepted(&v1, &[TX_VERSION_PIPE_V1]).is_ok());
+    assert_eq!(check_tx_version_accepted(&v2, &[TX_VERSION_PIPE_V1]).unwrap_err(), "tx version 2 is not accepted");
+    assert!(check_tx_version_accepted(&v1, &[TX_VERSION_PIPE_V2]).is_err());
+
+    let unknown = Tx { encoding: "pipe_v9".into(), ..v1 };
+    assert!(check_tx_version_accepted(&unknown, &all).is_err());
+}
+
+// ─────────────────────── КОМПАКТНАЯ ФОРМА ───────────────────────
+
+#[test]
+fn compact_roundtrips_and_recovers_pubkey() {
+    let tx = pipe_v2();
+    let data = encode_tx(&compact_tx(tx.clone()).unwrap()).unwrap();
+    assert_eq!(&data[..2], &[TX_ENVELOPE_MARKER, TX_VERSION_COMPACT]);
+    assert_eq!(enveloped_tx_len(&data).unwrap(), data.len());
+    assert!(data.len() < encode_tx(&tx).unwrap().len());
+
+    let decoded = decode_tx(&data).unwrap();
+    assert!(decoded.pubkey.is_empty());
+    assert_same(&decoded, &Tx { pubkey: String::new(), ..tx.clone() });
+
+    // tx_hash и символ токена нода подставляет сама
+    let decoded = Tx { tx_hash: tx.tx_hash.clone(), token: tx.token.clone(), ..decoded };
+    assert_eq!(recover_pubkey(&decoded).unwrap(), tx.pubkey);
+    assert_eq!(validate_tx(&decoded, NOW_MS, &DEVNET).unwrap().pubkey, tx.pubkey);
+}
+
+/// Как у ноды: tx_hash приходит в SEND_TX, символ — из реестра
+fn received(data: &[u8], tx: &Tx) -> Tx {
+    Tx { tx_hash: tx.tx_hash.clone(), token: tx.token.clone(), ..decode_tx(data).unwrap() }
+}
+
+/// tx_hash у обеих форм общий, но мемпул помнит его только до блока:
+/// повтор в другой кодировке при применении отсекает nonce
+#[test]
+fn same_tx_in_compact_and_full_form_applies_once() {
+    let tx = pipe_v2();
+    let full = received(&encode_tx(&tx).unwrap(), &tx);
+    let compact = received(&encode_tx(&compact_tx(tx.clone()).unwrap()).unwrap(), &tx);
+
+    let mut state = genesis(&tx.from, 1_000);
+
+    let txs = vec![validate_tx(&compact, NOW_MS, &DEVNET).unwrap(), validate_tx(&full, NOW_MS, &DEVNET).unwrap()];
+    let (ok, failed, _) = select_applicable(&mut state, txs, 1, NOW_MS);
+    assert_eq!(ok.len(), 1);
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0].1.contains("nonce"), "unexpected error: {}", failed[0].1);
+
+    assert_eq!(balance(&state, &tx.from, "GLD"), 1_000);
+    assert_eq!(nonce(&state, &tx.from), 0);
+}
+
+#[test]
+fn compact_needs_pipe_v2() {
+    assert!(compact_tx(pipe_v1()).is_err());
+}
+
+/// Транзы, подписанные с v = 0 и с v = 1
+fn txs_by_recovery_id() -> [Tx; 2] {
+    let (alice, bob) = (Wallet::from_seed(3, 0, &DEVNET), Wallet::from_seed(3, 1, &DEVNET));
+    let mut found: [Option<Tx>; 2] = [None, None];
+    for nonce in 1.. {
+        let tx = alice.transfer(&bob.address, 100, 10, nonce, NOW_MS).unwrap();
+        let v = u8::from_str_radix(&tx.signature[128..], 16).unwrap() as usize;
+        found[v].get_or_insert(tx);
+        if let [Some(v0), Some(v1)] = &found {
+            return [v0.clone(), v1.clone()];
+        }
+    }
+    unreachable!()
+}
+
+#[test]
+fn recovers_pubkey_for_both_recovery_ids() {
+    for tx in txs_by_recovery_id() {
+        assert_eq!(recover_pubkey(&tx).unwrap(), tx.pubkey);
+    }
+}
+
+#[test]
+fn wrong_or_invalid_v_does_not_recover_the_signer() {
+    for tx in txs_by_recovery_id() {
+        let (rs, v) = tx.signature.split_at(128);
+
+        // другой v из пары даёт другой ключ (или не даёт никакого)
+        let flipped = Tx { signature: format!("{}{:02x}", rs, u8::from_str_radix(v, 16).unwrap() ^ 1), ..tx.clone() };
+        assert_ne!(recover_pubkey(&flipped).ok(), Some(tx.pubkey.clone()));
+
+        for bad in ["04", "1b", "ff"] {
+            let invalid = Tx { signature: format!("{}{}", rs, bad), ..tx.clone() };
+            assert_eq!(recover_pubkey(&invalid).unwrap_err(), "invalid signature v");
+        }
+    }
+}
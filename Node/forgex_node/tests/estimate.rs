@@ -0,0 +1,96 @@
+//! Оценка fee по мемпулу: пустой, неполный, переполненный, fee у предела u64.
+//!
+//!   cargo test --test estimate
+
+use forgex_node::config::NodeConfig;
+use forgex_node::estimate::{MAX_TARGET_BLOCKS, estimate_fee};
+use forgex_node::model::Tx;
+use forgex_node::network::DEVNET;
+use forgex_node::node::Node;
+use forgex_node::state::{GLD, next_base_fee};
+use forgex_node::tx::{TxExtra, return_structured_tx};
+use forgex_node::wallet::Wallet;
+
+/// Блок на две транзы, без журнала блоков на диске
+fn node() -> Node {
+    Node::new(NodeConfig { max_block_txs: 2, blocks_log_path: String::new(), ..NodeConfig::default() })
+}
+
+/// В мемпул транзы кладём напрямую: estimate_fee смотрит только на fee
+fn add_pending(node: &Node, fees: &[u64]) {
+    let (alice, bob) = (Wallet::from_seed(4, 0, &DEVNET), Wallet::from_seed(4, 1, &DEVNET));
+    let mut mempool = node.mempool.lock().unwrap();
+    for (i, &fee) in fees.iter().enumerate() {
+        let tx = Tx {
+            tx_hash: format!("{:064x}", i),
+            domain_tag: "GLD_TX_v1".into(),
+            chain_id: DEVNET.chain_id.into(),
+            tx_type: "transfer".into(),
+            from: alice.address.clone(),
+            to: bob.address.clone(),
+            token: "GLD".into(),
+            token_id: GLD,
+            amount: 1,
+            fee,
+            nonce: i as u64 + 1,
+            timestamp: 0,
+            pubkey: String::new(),
+            signature: String::new(),
+            encoding: "pipe_v1".into(),
+            extra: TxExtra::None,
+            memo: String::new(),
+        };
+        let tx = return_structured_tx(&tx, &DEVNET).unwrap();
+        mempool.insert(tx.tx_hash.clone(), tx);
+    }
+}
+
+#[test]
+fn empty_mempool_gives_base_fee() {
+    let node = node();
+    let base_fee = node.state.lock().unwrap().base_fee;
+    let estimate = estimate_fee(&node, 1).unwrap();
+
+    assert_eq!(estimate.base_fee, base_fee);
+    assert_eq!(estimate.low, base_fee);
+    assert_eq!(estimate.medium, next_base_fee(base_fee, 2, &node.config().fees));
+    assert!(estimate.high >= estimate.medium);
+}
+
+#[test]
+fn mempool_below_capacity_does_not_raise_fee() {
+    let node = node();
+    let base_fee = node.state.lock().unwrap().base_fee;
+    add_pending(&node, &[base_fee * 5]);
+
+    assert_eq!(estimate_fee(&node, 1).unwrap().low, base_fee);
+}
+
+#[test]
+fn full_mempool_outbids_the_last_fitting_tx() {
+    let node = node();
+    let base_fee = node.state.lock().unwrap().base_fee;
+    add_pending(&node, &[base_fee * 5, base_fee * 4, base_fee * 3]);
+
+    // в один блок влезают две транзы: перебиваем вторую
+    assert_eq!(estimate_fee(&node, 1).unwrap().low, base_fee * 4 + 1);
+    // в два блока влезают все
+    assert_eq!(estimate_fee(&node, 2).unwrap().low, base_fee);
+}
+
+#[test]
+fn max_fee_in_mempool_does_not_overflow() {
+    let node = node();
+    add_pending(&node, &[u64::MAX, u64::MAX, u64::MAX]);
+
+    let estimate = estimate_fee(&node, 1).unwrap();
+    assert_eq!(estimate.low, u64::MAX);
+    assert_eq!(estimate.high, u64::MAX);
+}
+
+#[test]
+fn target_blocks_out_of_range_is_rejected() {
+    let node = node();
+    assert!(estimate_fee(&node, 0).is_err());
+    assert!(estimate_fee(&node, MAX_TARGET_BLOCKS + 1).is_err());
+}
@@ -0,0 +1,111 @@
+//! Деление комиссий, выпуск по халвингам и награда блока.
+//!
+//!   cargo test --test fees
+
+mod common;
+
+use common::{block, genesis_with, unsigned};
+use forgex_node::block::{REWARD_KIND_FEES, Reward};
+use forgex_node::config::{FeeConfig, RewardConfig};
+use forgex_node::network::DEVNET;
+use forgex_node::state::{GLD, apply_block, balance, block_rewards, issuance_at, next_base_fee, select_applicable, split_fee};
+use forgex_node::tx::{TxExtra, ValidTxCore};
+use forgex_node::wallet::Wallet;
+
+const FEES: FeeConfig = FeeConfig { min_base_fee: 10, target_block_txs: 10, burn_percent: 50, proposer_percent: 25 };
+
+#[test]
+fn split_fee_sums_to_fee() {
+    for (fee, base_fee) in [(0, 10), (10, 10), (30, 10), (7, 10), (u64::MAX, 10), (1_000, 999)] {
+        let (burned, proposer, treasury) = split_fee(fee, base_fee, &FEES);
+        assert_eq!(burned as u128 + proposer as u128 + treasury as u128, fee as u128, "fee {}", fee);
+    }
+}
+
+#[test]
+fn split_fee_gives_tip_to_proposer() {
+    // base 10: сгорает 5, производителю 2 + чаевые 20, казне 3
+    assert_eq!(split_fee(30, 10, &FEES), (5, 22, 3));
+    assert_eq!(split_fee(10, 10, &FEES), (5, 2, 3));
+    // fee ниже base fee делится целиком по процентам, чаевых нет
+    assert_eq!(split_fee(8, 10, &FEES), (4, 2, 2));
+}
+
+#[test]
+fn base_fee_follows_block_fullness() {
+    // target 10: шаг не больше 1/8 от base fee
+    assert_eq!(next_base_fee(800, 20, &FEES), 900);
+    assert_eq!(next_base_fee(800, 11, &FEES), 810);
+    assert_eq!(next_base_fee(800, 1_000, &FEES), 800 + 800 * 990 / 10 / 8);
+    assert_eq!(next_base_fee(800, 10, &FEES), 800);
+    assert_eq!(next_base_fee(800, 5, &FEES), 750);
+    assert_eq!(next_base_fee(800, 0, &FEES), 700);
+}
+
+#[test]
+fn base_fee_moves_up_at_least_by_one() {
+    assert_eq!(next_base_fee(10, 11, &FEES), 11);
+    assert_eq!(next_base_fee(u64::MAX, 1_000, &FEES), u64::MAX);
+}
+
+#[test]
+fn base_fee_never_drops_below_min() {
+    assert_eq!(next_base_fee(10, 0, &FEES), 10);
+    assert_eq!(next_base_fee(11, 0, &FEES), 10);
+    assert_eq!(next_base_fee(12, 0, &FEES), 11);
+    // base fee ниже минимума (например, после смены конфига) подтягивается к нему
+    assert_eq!(next_base_fee(1, 10, &FEES), 10);
+}
+
+#[test]
+fn issuance_halves_on_interval_boundaries() {
+    let rewards = RewardConfig { block_reward: 1_000, halving_interval: 10 };
+    assert_eq!(issuance_at(&rewards, 1), 1_000);
+    assert_eq!(issuance_at(&rewards, 10), 1_000);
+    assert_eq!(issuance_at(&rewards, 11), 500);
+    assert_eq!(issuance_at(&rewards, 20), 500);
+    assert_eq!(issuance_at(&rewards, 21), 250);
+    assert_eq!(issuance_at(&rewards, 10 * 63), 1_000 >> 62);
+    assert_eq!(issuance_at(&rewards, 10 * 64 + 1), 0);
+    assert_eq!(issuance_at(&rewards, u64::MAX), 0);
+}
+
+#[test]
+fn issuance_without_halving_is_constant() {
+    let rewards = RewardConfig { block_reward: 1_000, halving_interval: 0 };
+    assert_eq!(issuance_at(&rewards, 1), 1_000);
+    assert_eq!(issuance_at(&rewards, u64::MAX), 1_000);
+}
+
+fn tx(from: &Wallet, to: &Wallet, tx_type: &str, nonce: u64) -> ValidTxCore {
+    unsigned(from, &to.address, tx_type, "GLD", GLD, 100, 30, nonce, TxExtra::None)
+}
+
+#[test]
+fn fee_reward_counts_only_applied_txs() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let mut state = genesis_with(&alice.address, 1_000, &FEES);
+
+    // второй тип state не знает: в блок он не идёт и fee за него не платится
+    let txs = vec![tx(&alice, &bob, "transfer", 1), tx(&alice, &bob, "swap", 2)];
+    let (txs, failed, fees) = select_applicable(&mut state, txs, 1, 0);
+    assert_eq!(txs.len(), 1);
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0].1.contains("unsupported tx_type"), "unexpected error: {}", failed[0].1);
+    assert_eq!(fees, 22);
+
+    // блок с такой транзой не применяется, даже с верными наградами
+    let with_unknown = vec![txs[0].clone(), tx(&alice, &bob, "swap", 2)];
+    let rewards = block_rewards(&state, 1, &bob.address, fees);
+    let err = apply_block(&mut state, &block(1, with_unknown, rewards)).unwrap_err();
+    assert!(err.contains("unsupported tx_type"), "unexpected error: {}", err);
+
+    // награда больше положенной не сходится
+    let greedy = vec![Reward { to: bob.address.clone(), kind: REWARD_KIND_FEES, amount: 44 }];
+    assert!(apply_block(&mut state, &block(1, txs.clone(), greedy)).is_err());
+
+    let rewards = block_rewards(&state, 1, &bob.address, fees);
+    apply_block(&mut state, &block(1, txs, rewards)).unwrap();
+    assert_eq!(balance(&state, &alice.address, "GLD"), 1_000 - 100 - 30);
+    assert_eq!(balance(&state, &bob.address, "GLD"), 100 + 22);
+}
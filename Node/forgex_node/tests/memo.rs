@@ -0,0 +1,52 @@
+//! Memo транзы (pipe_v2): правила и то, что memo подписано.
+//!
+//!   cargo test --test memo
+
+mod common;
+
+use common::NOW_MS;
+use forgex_node::model::Tx;
+use forgex_node::network::DEVNET;
+use forgex_node::tx::{MAX_MEMO_LEN, PIPE_V1, validate_tx};
+use forgex_node::wallet::Wallet;
+
+fn with_memo(memo: &str) -> Result<Tx, String> {
+    let (alice, bob) = (Wallet::from_seed(5, 0, &DEVNET), Wallet::from_seed(5, 1, &DEVNET));
+    let tx = alice.transfer(&bob.address, 100, 10, 1, NOW_MS)?;
+    alice.with_memo(tx, memo)
+}
+
+#[test]
+fn memo_is_accepted_up_to_the_limit() {
+    for memo in ["invoice 42", "счёт №7", &"x".repeat(MAX_MEMO_LEN)] {
+        let tx = with_memo(memo).unwrap();
+        assert_eq!(validate_tx(&tx, NOW_MS, &DEVNET).unwrap().memo, memo);
+    }
+}
+
+#[test]
+fn bad_memo_is_rejected() {
+    // такую кошелёк и не подпишет: длина memo в raw tx — не больше лимита
+    assert!(with_memo(&"x".repeat(MAX_MEMO_LEN + 1)).is_err());
+    let tx = Tx { memo: "x".repeat(MAX_MEMO_LEN + 1), ..with_memo("x").unwrap() };
+    let err = validate_tx(&tx, NOW_MS, &DEVNET).unwrap_err();
+    assert!(err.contains("memo must be at most"), "unexpected error: {}", err);
+
+    for memo in ["line\nbreak", "tab\there", "nul\0"] {
+        let err = validate_tx(&with_memo(memo).unwrap(), NOW_MS, &DEVNET).unwrap_err();
+        assert!(err.contains("control characters"), "unexpected error: {}", err);
+    }
+}
+
+#[test]
+fn memo_needs_pipe_v2() {
+    let tx = Tx { encoding: PIPE_V1.into(), ..with_memo("invoice 42").unwrap() };
+    let err = validate_tx(&tx, NOW_MS, &DEVNET).unwrap_err();
+    assert!(err.contains("memo requires pipe_v2"), "unexpected error: {}", err);
+}
+
+#[test]
+fn memo_is_signed() {
+    let tx = Tx { memo: "invoice 43".into(), ..with_memo("invoice 42").unwrap() };
+    assert!(validate_tx(&tx, NOW_MS, &DEVNET).is_err());
+}
@@ -0,0 +1,102 @@
+//! Снимок мемпула: dump_mempool → load_mempool без потерь для каждого типа транз.
+//!
+//!   cargo test --test mempool
+
+mod common;
+
+use common::{FEE, NOW_MS, genesis};
+use forgex_node::envelope::{compact_tx, decode_tx, encode_tx};
+use forgex_node::mempool::{Mempool, dump_mempool, load_mempool, mempool_add_tx};
+use forgex_node::model::Tx;
+use forgex_node::multisig::{add_signature, multisig_address, new_multisig_transfer};
+use forgex_node::network::DEVNET;
+use forgex_node::state::{GLD, VestingClock, VestingSchedule};
+use forgex_node::tx::{ValidTxCore, validate_tx};
+use forgex_node::wallet::Wallet;
+
+/// По транзе каждого типа, плюс pipe_v2 с memo и пришедшая компактной
+fn every_tx_type(alice: &Wallet, bob: &Wallet) -> Vec<Tx> {
+    let signers: Vec<Wallet> = (0..2).map(|i| Wallet::from_seed(5, 10 + i, &DEVNET)).collect();
+    let mut pubkeys: Vec<Vec<u8>> = signers.iter().map(|w| w.pubkey.clone()).collect();
+    pubkeys.sort();
+    let mut multisig =
+        new_multisig_transfer(&DEVNET, 2, &pubkeys, "GLD", GLD, &bob.address, 5, FEE, 1, NOW_MS).unwrap();
+    for signer in &signers {
+        add_signature(&mut multisig, signer).unwrap();
+    }
+    assert_eq!(multisig.from, multisig_address(2, &pubkeys, &DEVNET).unwrap());
+
+    let schedule = VestingSchedule { clock: VestingClock::Height, start: 10, cliff: 5, duration: 20 };
+    let payouts = [(bob.address.clone(), 3), (signers[0].address.clone(), 4)];
+
+    let memo = alice.with_memo(alice.transfer(&bob.address, 6, FEE, 11, NOW_MS).unwrap(), "invoice 7").unwrap();
+    // компактная приходит без pubkey, нода восстанавливает его из подписи
+    let compact = alice.with_memo(alice.transfer(&bob.address, 7, FEE, 12, NOW_MS).unwrap(), "").unwrap();
+    let received = decode_tx(&encode_tx(&compact_tx(compact.clone()).unwrap()).unwrap()).unwrap();
+    let compact = Tx { token: compact.token, tx_hash: compact.tx_hash, ..received };
+
+    vec![
+        alice.transfer(&bob.address, 1, FEE, 1, NOW_MS).unwrap(),
+        alice.create_token("SILVER", 6, 1_000, 100, &alice.address, FEE, 2, NOW_MS).unwrap(),
+        alice.mint("GLD", GLD, &bob.address, 2, FEE, 3, NOW_MS).unwrap(),
+        alice.burn("GLD", GLD, 3, FEE, 4, NOW_MS).unwrap(),
+        alice.multi_send("GLD", GLD, &payouts, FEE, 5, NOW_MS).unwrap(),
+        alice.vesting_transfer("GLD", GLD, &bob.address, 8, schedule, FEE, 6, NOW_MS).unwrap(),
+        alice.stake(9, FEE, 7, NOW_MS).unwrap(),
+        alice.delegate(&bob.address, 10, FEE, 8, NOW_MS).unwrap(),
+        alice.unstake(&alice.address, 11, FEE, 9, NOW_MS).unwrap(),
+        multisig,
+        memo,
+        compact,
+    ]
+}
+
+fn snapshot_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("forgex-mempool-{}-{}.txt", name, std::process::id())).to_string_lossy().into_owned()
+}
+
+#[test]
+fn dump_and_load_keep_every_tx_type() {
+    let (alice, bob) = (Wallet::from_seed(5, 0, &DEVNET), Wallet::from_seed(5, 1, &DEVNET));
+    let state = genesis(&alice.address, 1_000_000);
+
+    let mut mempool = Mempool::new();
+    let txs: Vec<ValidTxCore> = every_tx_type(&alice, &bob)
+        .iter()
+        .map(|tx| validate_tx(tx, NOW_MS, &DEVNET).unwrap())
+        .collect();
+    for tx in &txs {
+        mempool_add_tx(&mut mempool, tx.clone());
+    }
+    assert_eq!(mempool.len(), txs.len());
+
+    let path = snapshot_path("roundtrip");
+    assert_eq!(dump_mempool(&mempool, &DEVNET, &path).unwrap(), txs.len());
+    let loaded = load_mempool(&path, &state, NOW_MS).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.len(), txs.len());
+    for tx in &txs {
+        let reloaded = loaded.get(&tx.tx_hash).unwrap_or_else(|| panic!("{} {} lost", tx.tx_type, tx.tx_hash));
+        assert_eq!(format!("{:?}", reloaded), format!("{:?}", tx), "{} changed", tx.tx_type);
+    }
+}
+
+#[test]
+fn load_skips_broken_entries() {
+    let (alice, bob) = (Wallet::from_seed(5, 0, &DEVNET), Wallet::from_seed(5, 1, &DEVNET));
+    let state = genesis(&alice.address, 1_000_000);
+    let tx = validate_tx(&alice.transfer(&bob.address, 1, FEE, 1, NOW_MS).unwrap(), NOW_MS, &DEVNET).unwrap();
+
+    let mut mempool = Mempool::new();
+    mempool_add_tx(&mut mempool, tx.clone());
+    let path = snapshot_path("broken");
+    dump_mempool(&mempool, &DEVNET, &path).unwrap();
+    let dumped = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, format!("zz\n{}00\n{}", dumped.trim(), dumped)).unwrap();
+
+    let loaded = load_mempool(&path, &state, NOW_MS).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert!(loaded.contains_key(&tx.tx_hash));
+}
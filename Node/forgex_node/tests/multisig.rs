@@ -0,0 +1,136 @@
+//! Политика и адрес мультисига, проверка multisig_transfer.
+//!
+//!   cargo test --test multisig
+
+mod common;
+
+use common::{NOW_MS, genesis};
+use forgex_node::address::decode_address;
+use forgex_node::multisig::{add_signature, check_policy, multisig_address, new_multisig_transfer};
+use forgex_node::network::{DEVNET, TESTNET};
+use forgex_node::state::{GLD, balance, nonce, select_applicable};
+use forgex_node::tx::validate_tx;
+use forgex_node::wallet::Wallet;
+
+fn signers() -> Vec<Wallet> {
+    (0..3).map(|i| Wallet::from_seed(7, i, &DEVNET)).collect()
+}
+
+fn sorted_pubkeys(wallets: &[Wallet]) -> Vec<Vec<u8>> {
+    let mut pubkeys: Vec<Vec<u8>> = wallets.iter().map(|w| w.pubkey.clone()).collect();
+    pubkeys.sort();
+    pubkeys
+}
+
+#[test]
+fn policy_needs_sorted_unique_keys_and_sane_threshold() {
+    let pubkeys = sorted_pubkeys(&signers());
+    assert!(check_policy(2, &pubkeys).is_ok());
+    assert!(check_policy(3, &pubkeys).is_ok());
+
+    assert!(check_policy(0, &pubkeys).is_err());
+    assert!(check_policy(4, &pubkeys).is_err());
+    assert!(check_policy(1, &[]).is_err());
+
+    let reversed: Vec<_> = pubkeys.iter().rev().cloned().collect();
+    assert!(check_policy(2, &reversed).is_err());
+    let duplicated = vec![pubkeys[0].clone(), pubkeys[0].clone()];
+    assert!(check_policy(1, &duplicated).is_err());
+}
+
+#[test]
+fn address_does_not_depend_on_key_order() {
+    let pubkeys = sorted_pubkeys(&signers());
+    let address = multisig_address(2, &pubkeys, &DEVNET).unwrap();
+
+    for order in [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]] {
+        let shuffled: Vec<_> = order.iter().map(|&i| pubkeys[i].clone()).collect();
+        assert_eq!(multisig_address(2, &shuffled, &DEVNET).unwrap(), address);
+    }
+
+    // другой порог или другая сеть — другой аккаунт
+    assert_ne!(multisig_address(3, &pubkeys, &DEVNET).unwrap(), address);
+    assert_ne!(multisig_address(2, &pubkeys, &TESTNET).unwrap(), address);
+}
+
+#[test]
+fn multisig_address_is_not_a_key_address() {
+    let wallets = signers();
+    let address = multisig_address(2, &sorted_pubkeys(&wallets), &DEVNET).unwrap();
+
+    assert!(address.starts_with(DEVNET.multisig_hrp));
+    assert!(decode_address(&address, &DEVNET).unwrap().is_multisig());
+    assert!(!decode_address(&wallets[0].address, &DEVNET).unwrap().is_multisig());
+    assert!(decode_address(&address, &TESTNET).is_err());
+}
+
+#[test]
+fn transfer_needs_threshold_signatures() {
+    let wallets = signers();
+    let to = Wallet::from_seed(7, 100, &DEVNET);
+    let mut tx =
+        new_multisig_transfer(&DEVNET, 2, &sorted_pubkeys(&wallets), "GLD", GLD, &to.address, 100, 10, 1, NOW_MS)
+            .unwrap();
+
+    assert!(validate_tx(&tx, NOW_MS, &DEVNET).is_err());
+
+    // threshold - 1 подписей мало
+    add_signature(&mut tx, &wallets[2]).unwrap();
+    let err = validate_tx(&tx, NOW_MS, &DEVNET).unwrap_err();
+    assert!(err.contains("needs 2 signatures"), "unexpected error: {}", err);
+
+    add_signature(&mut tx, &wallets[0]).unwrap();
+    let valid = validate_tx(&tx, NOW_MS, &DEVNET).unwrap();
+    assert!(valid.from_key.is_multisig());
+}
+
+#[test]
+fn multisig_and_key_txs_do_not_mix() {
+    let wallets = signers();
+    let address = multisig_address(1, &sorted_pubkeys(&wallets), &DEVNET).unwrap();
+
+    // обычная транза с адреса мультисига
+    let mut tx = wallets[0].transfer(&wallets[1].address, 100, 10, 1, NOW_MS).unwrap();
+    tx.from = address;
+    let err = validate_tx(&tx, NOW_MS, &DEVNET).unwrap_err();
+    assert!(err.contains("cannot be sent from a multisig address"), "unexpected error: {}", err);
+
+    // multisig_transfer с адреса ключа
+    let mut tx =
+        new_multisig_transfer(&DEVNET, 1, &sorted_pubkeys(&wallets), "GLD", GLD, &wallets[1].address, 100, 10, 1, NOW_MS)
+            .unwrap();
+    add_signature(&mut tx, &wallets[0]).unwrap();
+    tx.from = wallets[0].address.clone();
+    let err = validate_tx(&tx, NOW_MS, &DEVNET).unwrap_err();
+    assert!(err.contains("must be sent from a multisig address"), "unexpected error: {}", err);
+}
+
+#[test]
+fn same_payment_signed_by_another_subset_applies_once() {
+    let wallets = signers();
+    let pubkeys = sorted_pubkeys(&wallets);
+    let address = multisig_address(2, &pubkeys, &DEVNET).unwrap();
+    let to = Wallet::from_seed(7, 100, &DEVNET);
+
+    let mut state = genesis(&address, 1_000);
+
+    // одна платёжка, два разных набора подписантов — разные tx_hash
+    let payment = new_multisig_transfer(&DEVNET, 2, &pubkeys, "GLD", GLD, &to.address, 100, 10, 1, NOW_MS).unwrap();
+    let (mut first, mut second) = (payment.clone(), payment);
+    for (tx, subset) in [(&mut first, [0, 1]), (&mut second, [1, 2])] {
+        for i in subset {
+            add_signature(tx, &wallets[i]).unwrap();
+        }
+    }
+    assert_ne!(first.tx_hash, second.tx_hash);
+
+    let txs = vec![validate_tx(&first, NOW_MS, &DEVNET).unwrap(), validate_tx(&second, NOW_MS, &DEVNET).unwrap()];
+    let (ok, failed, _) = select_applicable(&mut state, txs, 1, NOW_MS);
+    assert_eq!(ok.len(), 1);
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0].1.contains("nonce"), "unexpected error: {}", failed[0].1);
+
+    // select_applicable state не меняет
+    assert_eq!(balance(&state, &address, "GLD"), 1_000);
+    assert_eq!(nonce(&state, &address), 0);
+}
@@ -0,0 +1,54 @@
+//! Транзы и адреса одной сети в другой не проходят.
+//!
+//!   cargo test --test network
+
+mod common;
+
+use common::NOW_MS;
+use forgex_node::address::decode_address;
+use forgex_node::network::{DEVNET, MAINNET, NETWORKS, TESTNET};
+use forgex_node::tx::validate_tx;
+use forgex_node::wallet::Wallet;
+
+#[test]
+fn address_is_valid_only_on_its_network() {
+    for &home in NETWORKS {
+        let wallet = Wallet::from_seed(1, 0, home);
+        assert!(wallet.address.starts_with(home.hrp));
+        for &other in NETWORKS {
+            assert_eq!(decode_address(&wallet.address, other).is_ok(), home == other, "{} on {}", home.name, other.name);
+        }
+    }
+}
+
+#[test]
+fn tx_signed_for_testnet_is_rejected_on_devnet() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &TESTNET), Wallet::from_seed(1, 1, &TESTNET));
+    let tx = alice.transfer(&bob.address, 100, 100, 1, NOW_MS).unwrap();
+    assert!(validate_tx(&tx, NOW_MS, &TESTNET).is_ok());
+
+    let err = validate_tx(&tx, NOW_MS, &DEVNET).unwrap_err();
+    assert!(err.contains("chain_id"), "unexpected error: {}", err);
+    assert!(validate_tx(&tx, NOW_MS, &MAINNET).is_err());
+}
+
+#[test]
+fn devnet_tx_to_a_testnet_address_is_rejected() {
+    let alice = Wallet::from_seed(1, 0, &DEVNET);
+    let bob = Wallet::from_seed(1, 1, &TESTNET);
+
+    // кошелёк получателя не проверяет — отсекает нода
+    let tx = alice.transfer(&bob.address, 100, 10, 1, NOW_MS).unwrap();
+    let err = validate_tx(&tx, NOW_MS, &DEVNET).unwrap_err();
+    assert!(err.contains("prefix"), "unexpected error: {}", err);
+}
+
+#[test]
+fn relabelled_chain_id_breaks_the_signature() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &TESTNET), Wallet::from_seed(1, 1, &TESTNET));
+    let mut tx = alice.transfer(&bob.address, 100, 100, 1, NOW_MS).unwrap();
+    tx.chain_id = DEVNET.chain_id.into();
+
+    assert!(validate_tx(&tx, NOW_MS, &DEVNET).is_err());
+    assert!(validate_tx(&tx, NOW_MS, &TESTNET).is_err());
+}
@@ -0,0 +1,59 @@
+//! Производство блока: события после применения, лог блоков пишется в фоне.
+//!
+//!   cargo test --test node
+
+use std::sync::{Arc, Mutex};
+
+use forgex_node::block::{BlockStoreHook, flush_block_store};
+use forgex_node::config::NodeConfig;
+use forgex_node::events::{NodeEvent, NodeHook, register_hook};
+use forgex_node::node::{Node, produce_block};
+use forgex_node::state::block_info;
+
+/// Запоминает высоту state в момент каждого BlockProduced
+#[derive(Default)]
+struct SeenHeights(Mutex<Vec<(u64, u64)>>);
+
+impl NodeHook for SeenHeights {
+    fn on_event(&self, node: &Node, event: &NodeEvent) {
+        if let NodeEvent::BlockProduced { block } = event {
+            let applied = block_info(&node.state.lock().unwrap()).map_or(0, |(height, _)| height);
+            self.0.lock().unwrap().push((block.header.height, applied));
+        }
+    }
+}
+
+fn log_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("forgex-node-{}-{}.txt", name, std::process::id())).to_string_lossy().into_owned()
+}
+
+#[test]
+fn block_produced_is_emitted_after_apply() {
+    let node = Node::new(NodeConfig { blocks_log_path: String::new(), ..NodeConfig::default() });
+    let seen = Arc::new(SeenHeights::default());
+    register_hook(&node, seen.clone());
+
+    for _ in 0..3 {
+        produce_block(&node, true).unwrap();
+    }
+    assert_eq!(*seen.0.lock().unwrap(), vec![(1, 1), (2, 2), (3, 3)]);
+}
+
+#[test]
+fn block_log_is_on_disk_after_flush() {
+    let path = log_path("log");
+    let _ = std::fs::remove_file(&path);
+    let node = Node::new(NodeConfig { blocks_log_path: path.clone(), ..NodeConfig::default() });
+    register_hook(&node, Arc::new(BlockStoreHook));
+
+    for _ in 0..3 {
+        produce_block(&node, true).unwrap();
+    }
+    assert_eq!(node.blocks.lock().unwrap().blocks.len(), 3);
+    flush_block_store(&node.blocks.lock().unwrap()).unwrap();
+
+    let log = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let heights: Vec<&str> = log.lines().filter(|l| l.starts_with("HEIGHT")).collect();
+    assert_eq!(heights, ["HEIGHT   : 1", "HEIGHT   : 2", "HEIGHT   : 3"]);
+}
@@ -0,0 +1,64 @@
+//! Сценарии симулятора: ноды сходятся к одному state, один seed — один прогон.
+//!
+//!   cargo test --test sim
+
+use forgex_node::sim::{SCENARIOS, SimConfig, SimReport, run_simulation, scenario};
+
+/// Короче, чем у forgex_sim по умолчанию, но с теми же потерями и разделениями
+fn base(seed: u64) -> SimConfig {
+    SimConfig {
+        seed,
+        duration_ms: 6_000,
+        settle_ms: 5_000,
+        ..SimConfig::default()
+    }
+}
+
+fn run(name: &str, seed: u64) -> SimReport {
+    let cfg = scenario(name, &base(seed)).unwrap_or_else(|| panic!("unknown scenario {}", name));
+    run_simulation(&cfg)
+}
+
+/// Все ноды на одной высоте и с одним state, и блоки вообще были
+fn assert_agreed(name: &str, report: &SimReport) {
+    let (height, digest) = &report.nodes[0];
+    assert!(*height > 0, "[{}] no blocks were produced", name);
+    assert!(report.txs_accepted > 0, "[{}] no txs were accepted", name);
+    for (i, node) in report.nodes.iter().enumerate() {
+        assert_eq!(node, &(*height, digest.clone()), "[{}] node {} disagrees with node 0", name, i);
+    }
+}
+
+fn assert_reproducible(name: &str, seed: u64) {
+    let first = run(name, seed);
+    let second = run(name, seed);
+    assert_agreed(name, &first);
+    assert_eq!(first.trace_digest, second.trace_digest, "[{}] traces diverged for seed {}", name, seed);
+    assert_eq!(first.nodes, second.nodes, "[{}] final states diverged for seed {}", name, seed);
+}
+
+#[test]
+fn calm_converges_and_reproduces() {
+    assert_reproducible("calm", 1);
+}
+
+#[test]
+fn lossy_converges_and_reproduces() {
+    assert_reproducible("lossy", 2);
+}
+
+#[test]
+fn partition_converges_and_reproduces() {
+    assert_reproducible("partition", 3);
+}
+
+#[test]
+fn unknown_scenario_is_rejected() {
+    assert!(SCENARIOS.iter().all(|name| scenario(name, &base(1)).is_some()));
+    assert!(scenario("storm", &base(1)).is_none());
+}
+
+#[test]
+fn different_seeds_give_different_runs() {
+    assert_ne!(run("calm", 1).trace_digest, run("calm", 4).trace_digest);
+}
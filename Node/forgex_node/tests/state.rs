@@ -0,0 +1,367 @@
+//! apply_block применяет блок целиком или никак; правила транз по типам.
+//!
+//!   cargo test --test state
+
+mod common;
+
+use common::{FEE, NOW_MS, block, block_at, genesis, transfer, unsigned};
+use forgex_node::address::{AddrKey, decode_address};
+use forgex_node::block::{REWARD_KIND_ISSUANCE, Reward};
+use forgex_node::network::DEVNET;
+use forgex_node::state::{
+    ChainState, EPOCH_LENGTH, GLD, MAX_TOKEN_DECIMALS, NO_TOKEN, TokenId, UNBONDING_BLOCKS, VestingClock, VestingSchedule, apply_block,
+    balance, balance_at, nonce, select_applicable, state_lines, supply_of,
+};
+use forgex_node::tx::{TxExtra, ValidTxCore, validate_tx};
+use forgex_node::wallet::Wallet;
+
+/// Токен с issuer = from
+fn create_token(from: &Wallet, symbol: &str, max_supply: u64, initial_supply: u64, nonce: u64) -> ValidTxCore {
+    let extra = TxExtra::CreateToken { decimals: 6, initial_supply };
+    unsigned(from, &from.address, "create_token", symbol, NO_TOKEN, max_supply, FEE, nonce, extra)
+}
+
+fn mint(from: &Wallet, to: &Wallet, token_id: TokenId, amount: u64, nonce: u64) -> ValidTxCore {
+    unsigned(from, &to.address, "mint", "", token_id, amount, FEE, nonce, TxExtra::None)
+}
+
+fn burn(from: &Wallet, token_id: TokenId, amount: u64, nonce: u64) -> ValidTxCore {
+    unsigned(from, &from.address, "burn", "", token_id, amount, FEE, nonce, TxExtra::None)
+}
+
+fn multi_send(from: &Wallet, payouts: &[(&Wallet, u64)], nonce: u64) -> ValidTxCore {
+    let payouts: Vec<(String, u64)> = payouts.iter().map(|(to, amount)| (to.address.clone(), *amount)).collect();
+    let total = payouts.iter().map(|(_, amount)| amount).sum();
+    unsigned(from, &from.address, "multi_send", "GLD", GLD, total, FEE, nonce, TxExtra::MultiSend(payouts))
+}
+
+fn vesting_transfer(from: &Wallet, to: &Wallet, amount: u64, schedule: VestingSchedule, nonce: u64) -> ValidTxCore {
+    unsigned(from, &to.address, "vesting_transfer", "GLD", GLD, amount, FEE, nonce, TxExtra::Vesting(schedule))
+}
+
+/// stake: to = from; delegate: to — валидатор
+fn bond(from: &Wallet, tx_type: &str, validator: &Wallet, amount: u64, nonce: u64) -> ValidTxCore {
+    unsigned(from, &validator.address, tx_type, "GLD", GLD, amount, FEE, nonce, TxExtra::None)
+}
+
+fn key(wallet: &Wallet) -> AddrKey {
+    decode_address(&wallet.address, &DEVNET).unwrap()
+}
+
+#[test]
+fn applies_a_valid_block() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let mut state = genesis(&alice.address, 1_000);
+
+    let diff = apply_block(&mut state, &block(1, vec![transfer(&alice, &bob, 100, 1)], Vec::new())).unwrap();
+
+    assert_eq!(balance(&state, &alice.address, "GLD"), 1_000 - 100 - FEE);
+    assert_eq!(balance(&state, &bob.address, "GLD"), 100);
+    assert_eq!(nonce(&state, &alice.address), 1);
+    assert!(!diff.balances.is_empty());
+}
+
+#[test]
+fn failed_tx_rolls_back_the_whole_block() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let mut state = genesis(&alice.address, 1_000);
+    let before = state_lines(&state);
+
+    // первая транза проходит, вторая — нет: не должно остаться и первой
+    let txs = vec![transfer(&alice, &bob, 100, 1), transfer(&alice, &bob, 5_000, 2)];
+    let err = apply_block(&mut state, &block(1, txs, Vec::new())).unwrap_err();
+
+    assert!(err.contains("insufficient"), "unexpected error: {}", err);
+    assert_eq!(state_lines(&state), before);
+    assert!(state.latest_block.is_none());
+}
+
+#[test]
+fn failed_block_keeps_the_previous_one() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let mut state = genesis(&alice.address, 1_000);
+    apply_block(&mut state, &block(1, vec![transfer(&alice, &bob, 100, 1)], Vec::new())).unwrap();
+    let before = state_lines(&state);
+
+    let txs = vec![transfer(&bob, &alice, 50, 1), transfer(&alice, &bob, 5_000, 2)];
+    assert!(apply_block(&mut state, &block(2, txs, Vec::new())).is_err());
+
+    assert_eq!(state_lines(&state), before);
+    assert_eq!(state.latest_block.as_ref().map(|b| b.height), Some(1));
+}
+
+#[test]
+fn wrong_rewards_roll_back_applied_txs() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let mut state = genesis(&alice.address, 1_000);
+    let before = state_lines(&state);
+
+    // транзы применились, а награды уже не сошлись
+    let rewards = vec![Reward { to: bob.address.clone(), kind: REWARD_KIND_ISSUANCE, amount: 1 }];
+    let err = apply_block(&mut state, &block(1, vec![transfer(&alice, &bob, 100, 1)], rewards)).unwrap_err();
+
+    assert!(err.contains("rewards do not match"), "unexpected error: {}", err);
+    assert_eq!(state_lines(&state), before);
+}
+
+#[test]
+fn create_token_registers_symbol_once() {
+    let alice = Wallet::from_seed(1, 0, &DEVNET);
+    let mut state = genesis(&alice.address, 1_000);
+
+    apply_block(&mut state, &block(1, vec![create_token(&alice, "SILVER", 1_000, 100, 1)], Vec::new())).unwrap();
+    let silver = state.tokens.id_of("SILVER").unwrap();
+    assert_eq!(supply_of(&state, silver), 100);
+    assert_eq!(balance(&state, &alice.address, "SILVER"), 100);
+    let before = state_lines(&state);
+
+    // символ занят: блок не применяется целиком
+    let err = apply_block(&mut state, &block(2, vec![create_token(&alice, "SILVER", 5_000, 0, 2)], Vec::new())).unwrap_err();
+    assert!(err.contains("SILVER"), "unexpected error: {}", err);
+    assert_eq!(state_lines(&state), before);
+}
+
+#[test]
+fn create_token_checks_decimals_and_initial_supply() {
+    let alice = Wallet::from_seed(1, 0, &DEVNET);
+    let create = |decimals: u8, max_supply: u64, initial_supply: u64| {
+        let tx = alice.create_token("SILVER", decimals, max_supply, initial_supply, &alice.address, FEE, 1, NOW_MS).unwrap();
+        validate_tx(&tx, NOW_MS, &DEVNET)
+    };
+
+    assert!(create(MAX_TOKEN_DECIMALS, 1_000, 1_000).is_ok());
+    assert!(create(0, 1_000, 0).is_ok());
+
+    let err = create(MAX_TOKEN_DECIMALS + 1, 1_000, 0).unwrap_err();
+    assert!(err.contains("decimals"), "unexpected error: {}", err);
+    let err = create(6, 1_000, 1_001).unwrap_err();
+    assert!(err.contains("initial_supply"), "unexpected error: {}", err);
+}
+
+/// SILVER с потолком 1_000, из них 100 сразу у alice (блок 1, nonce 1)
+fn with_silver(alice: &Wallet) -> (ChainState, TokenId) {
+    let mut state = genesis(&alice.address, 1_000);
+    apply_block(&mut state, &block(1, vec![create_token(alice, "SILVER", 1_000, 100, 1)], Vec::new())).unwrap();
+    let silver = state.tokens.id_of("SILVER").unwrap();
+    (state, silver)
+}
+
+#[test]
+fn mint_is_capped_at_max_supply() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let (mut state, silver) = with_silver(&alice);
+
+    // ровно до потолка можно
+    apply_block(&mut state, &block(2, vec![mint(&alice, &bob, silver, 900, 2)], Vec::new())).unwrap();
+    assert_eq!(supply_of(&state, silver), 1_000);
+    assert_eq!(balance(&state, &bob.address, "SILVER"), 900);
+    let before = state_lines(&state);
+
+    let err = apply_block(&mut state, &block(3, vec![mint(&alice, &bob, silver, 1, 3)], Vec::new())).unwrap_err();
+    assert!(err.contains("max supply"), "unexpected error: {}", err);
+    assert_eq!(state_lines(&state), before);
+}
+
+#[test]
+fn only_issuer_can_mint() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let (mut state, silver) = with_silver(&alice);
+    apply_block(&mut state, &block(2, vec![transfer(&alice, &bob, 100, 2)], Vec::new())).unwrap();
+    let before = state_lines(&state);
+
+    let err = apply_block(&mut state, &block(3, vec![mint(&bob, &bob, silver, 10, 1)], Vec::new())).unwrap_err();
+    assert!(err.contains("only issuer"), "unexpected error: {}", err);
+    assert_eq!(state_lines(&state), before);
+}
+
+#[test]
+fn burn_reduces_balance_and_supply() {
+    let alice = Wallet::from_seed(1, 0, &DEVNET);
+    let (mut state, silver) = with_silver(&alice);
+
+    apply_block(&mut state, &block(2, vec![burn(&alice, silver, 40, 2)], Vec::new())).unwrap();
+    assert_eq!(balance(&state, &alice.address, "SILVER"), 60);
+    assert_eq!(supply_of(&state, silver), 60);
+
+    // сожжённое можно выпустить снова: потолок считается по текущему выпуску
+    apply_block(&mut state, &block(3, vec![mint(&alice, &alice, silver, 940, 3)], Vec::new())).unwrap();
+    assert_eq!(supply_of(&state, silver), 1_000);
+}
+
+#[test]
+fn cannot_burn_more_than_balance() {
+    let alice = Wallet::from_seed(1, 0, &DEVNET);
+    let (mut state, silver) = with_silver(&alice);
+    let before = state_lines(&state);
+
+    let err = apply_block(&mut state, &block(2, vec![burn(&alice, silver, 101, 2)], Vec::new())).unwrap_err();
+    assert!(err.contains("insufficient"), "unexpected error: {}", err);
+    assert_eq!(state_lines(&state), before);
+    assert_eq!(supply_of(&state, silver), 100);
+}
+
+#[test]
+fn multi_send_with_one_unfundable_payout_pays_nobody() {
+    let alice = Wallet::from_seed(1, 0, &DEVNET);
+    let (bob, carol, dave) = (Wallet::from_seed(1, 1, &DEVNET), Wallet::from_seed(1, 2, &DEVNET), Wallet::from_seed(1, 3, &DEVNET));
+    let mut state = genesis(&alice.address, 1_000);
+    let before = state_lines(&state);
+
+    // на первые две выплаты денег хватает, на третью — уже нет
+    let send = multi_send(&alice, &[(&bob, 400), (&carol, 400), (&dave, 400)], 1);
+    let err = apply_block(&mut state, &block(1, vec![send.clone()], Vec::new())).unwrap_err();
+    assert!(err.contains("insufficient"), "unexpected error: {}", err);
+    assert_eq!(state_lines(&state), before);
+    for payee in [&bob, &carol, &dave] {
+        assert_eq!(balance(&state, &payee.address, "GLD"), 0);
+    }
+
+    // при отборе в блок она просто отпадает, соседняя транза проходит
+    let (ok, failed, _) = select_applicable(&mut state, vec![send, transfer(&alice, &bob, 100, 1)], 1, 0);
+    assert_eq!(ok.len(), 1);
+    assert_eq!(ok[0].tx_type, "transfer");
+    assert_eq!(failed.len(), 1);
+
+    // по силам — проходят все выплаты
+    let send = multi_send(&alice, &[(&bob, 300), (&carol, 300), (&dave, 300)], 1);
+    apply_block(&mut state, &block(1, vec![send], Vec::new())).unwrap();
+    for payee in [&bob, &carol, &dave] {
+        assert_eq!(balance(&state, &payee.address, "GLD"), 300);
+    }
+    assert_eq!(balance(&state, &alice.address, "GLD"), 1_000 - 900 - FEE);
+}
+
+#[test]
+fn vesting_unlocks_nothing_before_cliff_then_linearly() {
+    let schedule = VestingSchedule { clock: VestingClock::Height, start: 10, cliff: 5, duration: 20 };
+
+    assert_eq!(schedule.unlocked(1_000, 0, NOW_MS), 0);
+    assert_eq!(schedule.unlocked(1_000, 14, NOW_MS), 0);
+    // на cliff сразу открывается всё, что натикало с start
+    assert_eq!(schedule.unlocked(1_000, 15, NOW_MS), 250);
+    assert_eq!(schedule.unlocked(1_000, 20, NOW_MS), 500);
+    assert_eq!(schedule.unlocked(1_000, 29, NOW_MS), 950);
+    assert_eq!(schedule.unlocked(1_000, 30, NOW_MS), 1_000);
+    assert_eq!(schedule.unlocked(1_000, u64::MAX, NOW_MS), 1_000);
+
+    // по времени высота не важна
+    let schedule = VestingSchedule { clock: VestingClock::Time, start: NOW_MS, cliff: 1_000, duration: 4_000 };
+    assert_eq!(schedule.unlocked(1_000, u64::MAX, NOW_MS + 999), 0);
+    assert_eq!(schedule.unlocked(1_000, 0, NOW_MS + 1_000), 250);
+    assert_eq!(schedule.unlocked(1_000, 0, NOW_MS + 4_000), 1_000);
+}
+
+#[test]
+fn vesting_by_height_moves_to_spendable_balance_block_by_block() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let mut state = genesis(&alice.address, 1_000);
+    let schedule = VestingSchedule { clock: VestingClock::Height, start: 10, cliff: 4, duration: 20 };
+
+    apply_block(&mut state, &block(1, vec![vesting_transfer(&alice, &bob, 400, schedule, 1)], Vec::new())).unwrap();
+    assert_eq!(balance(&state, &alice.address, "GLD"), 1_000 - 400 - FEE);
+    assert_eq!(balance(&state, &bob.address, "GLD"), 0);
+    assert_eq!(balance_at(&state, &bob.address, "GLD", 13, 0), (0, 400));
+    assert_eq!(balance_at(&state, &bob.address, "GLD", 14, 0), (80, 320));
+
+    // до cliff тратить нечего
+    let spend = |amount, nonce| transfer(&bob, &alice, amount, nonce);
+    assert!(apply_block(&mut state, &block(13, vec![spend(1, 1)], Vec::new())).is_err());
+
+    apply_block(&mut state, &block(14, Vec::new(), Vec::new())).unwrap();
+    assert_eq!(balance(&state, &bob.address, "GLD"), 80);
+    assert_eq!(balance_at(&state, &bob.address, "GLD", 14, 0), (80, 320));
+
+    // к блоку 15 открыто 100: больше потратить нельзя, а в пределах — можно
+    assert!(apply_block(&mut state, &block(15, vec![spend(100, 1)], Vec::new())).is_err());
+    apply_block(&mut state, &block(15, vec![spend(90, 1)], Vec::new())).unwrap();
+    assert_eq!(balance(&state, &bob.address, "GLD"), 0);
+    assert_eq!(balance_at(&state, &bob.address, "GLD", 15, 0), (0, 300));
+
+    apply_block(&mut state, &block(30, Vec::new(), Vec::new())).unwrap();
+    assert_eq!(balance(&state, &bob.address, "GLD"), 300);
+    assert_eq!(balance_at(&state, &bob.address, "GLD", 30, 0), (300, 0));
+    assert_eq!(supply_of(&state, GLD), 1_000 - 2 * (FEE / 2));
+}
+
+#[test]
+fn vesting_by_time_follows_block_timestamp_not_height() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let mut state = genesis(&alice.address, 1_000);
+    let schedule = VestingSchedule { clock: VestingClock::Time, start: NOW_MS, cliff: 1_000, duration: 4_000 };
+
+    apply_block(&mut state, &block_at(1, NOW_MS, vec![vesting_transfer(&alice, &bob, 400, schedule, 1)], Vec::new())).unwrap();
+
+    // высота далеко, а время ещё до cliff
+    apply_block(&mut state, &block_at(1_000, NOW_MS + 999, Vec::new(), Vec::new())).unwrap();
+    assert_eq!(balance(&state, &bob.address, "GLD"), 0);
+    assert_eq!(balance_at(&state, &bob.address, "GLD", 1_000, NOW_MS + 999), (0, 400));
+
+    apply_block(&mut state, &block_at(1_001, NOW_MS + 2_000, Vec::new(), Vec::new())).unwrap();
+    assert_eq!(balance(&state, &bob.address, "GLD"), 200);
+    assert_eq!(balance_at(&state, &bob.address, "GLD", 1_001, NOW_MS + 2_000), (200, 200));
+
+    apply_block(&mut state, &block_at(1_002, NOW_MS + 10_000, Vec::new(), Vec::new())).unwrap();
+    assert_eq!(balance_at(&state, &bob.address, "GLD", 1_002, NOW_MS + 10_000), (400, 0));
+}
+
+#[test]
+fn unstaked_gld_comes_back_only_after_unbonding_period() {
+    let alice = Wallet::from_seed(1, 0, &DEVNET);
+    let mut state = genesis(&alice.address, 1_000);
+
+    apply_block(&mut state, &block(1, vec![bond(&alice, "stake", &alice, 300, 1)], Vec::new())).unwrap();
+    assert_eq!(state.bonds.get(&(key(&alice), key(&alice))), Some(&300));
+
+    // вывести больше, чем в стейке, нельзя
+    assert!(apply_block(&mut state, &block(5, vec![bond(&alice, "unstake", &alice, 301, 2)], Vec::new())).is_err());
+
+    apply_block(&mut state, &block(5, vec![bond(&alice, "unstake", &alice, 100, 2)], Vec::new())).unwrap();
+    let free = 1_000 - 300 - 2 * FEE;
+    assert_eq!(balance(&state, &alice.address, "GLD"), free);
+    assert_eq!(state.bonds.get(&(key(&alice), key(&alice))), Some(&200));
+
+    let due = 5 + UNBONDING_BLOCKS;
+    assert_eq!(balance_at(&state, &alice.address, "GLD", due - 1, 0), (free, 0));
+    assert_eq!(balance_at(&state, &alice.address, "GLD", due, 0), (free + 100, 0));
+
+    apply_block(&mut state, &block(due - 1, Vec::new(), Vec::new())).unwrap();
+    assert_eq!(balance(&state, &alice.address, "GLD"), free);
+    apply_block(&mut state, &block(due, Vec::new(), Vec::new())).unwrap();
+    assert_eq!(balance(&state, &alice.address, "GLD"), free + 100);
+    assert!(state.unbonding.is_empty());
+}
+
+#[test]
+fn validator_set_changes_only_at_epoch_boundary() {
+    let (alice, bob) = (Wallet::from_seed(1, 0, &DEVNET), Wallet::from_seed(1, 1, &DEVNET));
+    let mut state = genesis(&alice.address, 1_000);
+
+    let txs = vec![bond(&alice, "stake", &alice, 300, 1), transfer(&alice, &bob, 200, 2)];
+    let diff = apply_block(&mut state, &block(1, txs, Vec::new())).unwrap();
+    assert!(diff.validator_set.is_none());
+    assert!(state.validator_set.validators.is_empty());
+
+    let diff = apply_block(&mut state, &block(EPOCH_LENGTH - 1, Vec::new(), Vec::new())).unwrap();
+    assert!(diff.validator_set.is_none());
+    assert!(state.validator_set.validators.is_empty());
+
+    let diff = apply_block(&mut state, &block(EPOCH_LENGTH, Vec::new(), Vec::new())).unwrap();
+    let set = diff.validator_set.unwrap();
+    assert_eq!(set.epoch, 1);
+    assert_eq!(set.validators, vec![(key(&alice), 300)]);
+    assert_eq!(state.validator_set, set);
+
+    // делегирование и unstake внутри эпохи набор не трогают
+    let txs = vec![bond(&bob, "delegate", &alice, 150, 1), bond(&alice, "unstake", &alice, 50, 3)];
+    let diff = apply_block(&mut state, &block(EPOCH_LENGTH + 1, txs, Vec::new())).unwrap();
+    assert!(diff.validator_set.is_none());
+    assert_eq!(state.validator_set, set);
+
+    apply_block(&mut state, &block(2 * EPOCH_LENGTH - 1, Vec::new(), Vec::new())).unwrap();
+    assert_eq!(state.validator_set, set);
+
+    let diff = apply_block(&mut state, &block(2 * EPOCH_LENGTH, Vec::new(), Vec::new())).unwrap();
+    let set = diff.validator_set.unwrap();
+    assert_eq!(set.epoch, 2);
+    assert_eq!(set.validators, vec![(key(&alice), 300 - 50 + 150)]);
+}
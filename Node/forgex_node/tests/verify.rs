@@ -0,0 +1,80 @@
+//! Пул проверки транз: очередь занята, пачка больше очереди, кривые поля.
+//!
+//!   cargo test --test verify
+
+mod common;
+
+use common::NOW_MS;
+use forgex_node::envelope::decode_tx;
+use forgex_node::model::Tx;
+use forgex_node::multisig::{add_signature, new_multisig_transfer};
+use forgex_node::network::DEVNET;
+use forgex_node::state::GLD;
+use forgex_node::tx::{build_raw_tx_from_struct, hex_to_bytes, validate_tx};
+use forgex_node::verify::{Busy, VerifyPool};
+use forgex_node::wallet::Wallet;
+
+fn transfers(n: u64) -> Vec<Tx> {
+    let (alice, bob) = (Wallet::from_seed(6, 0, &DEVNET), Wallet::from_seed(6, 1, &DEVNET));
+    (1..=n).map(|nonce| alice.transfer(&bob.address, 1, 10, nonce, NOW_MS).unwrap()).collect()
+}
+
+#[tokio::test]
+async fn full_queue_replies_busy() {
+    let pool = VerifyPool::new(1, 64);
+    let mut txs = transfers(65);
+    let single = txs.pop().unwrap();
+
+    // пачка занимает всю очередь, пока её проверяют, одиночная не влезает
+    let (batch, busy) = tokio::join!(pool.verify_batch(txs, NOW_MS, &DEVNET), pool.verify(single.clone(), NOW_MS, &DEVNET));
+    assert_eq!(busy.unwrap_err(), Busy);
+    assert!(batch.unwrap().iter().all(Result::is_ok));
+
+    // места освободились — та же транза проходит
+    assert_eq!(pool.queued(), 0);
+    assert!(pool.verify(single, NOW_MS, &DEVNET).await.unwrap().is_ok());
+}
+
+#[tokio::test]
+async fn batch_larger_than_queue_is_rejected_not_busy() {
+    let pool = VerifyPool::new(1, 2);
+
+    let checked = pool.verify_batch(transfers(3), NOW_MS, &DEVNET).await.unwrap();
+    assert_eq!(checked.len(), 3);
+    for result in checked {
+        let err = result.unwrap_err();
+        assert!(err.contains("exceeds verify queue"), "unexpected error: {}", err);
+    }
+    assert_eq!(pool.queued(), 0);
+
+    // пачка по размеру очереди проходит
+    let checked = pool.verify_batch(transfers(2), NOW_MS, &DEVNET).await.unwrap();
+    assert!(checked.iter().all(Result::is_ok));
+}
+
+#[test]
+fn non_ascii_hex_is_an_error() {
+    assert!(hex_to_bytes("é0").is_err());
+    assert!(hex_to_bytes("0g").is_err());
+    assert_eq!(hex_to_bytes("0aff").unwrap(), vec![0x0a, 0xff]);
+
+    // подпись нужной длины в байтах, но не hex — отказ, а не паника
+    let tx = Tx { signature: "é".repeat(65), ..transfers(1).remove(0) };
+    assert!(validate_tx(&tx, NOW_MS, &DEVNET).is_err());
+}
+
+#[test]
+fn truncated_multisig_tail_is_an_error() {
+    let signers: Vec<Wallet> = (0..2).map(|i| Wallet::from_seed(6, 10 + i, &DEVNET)).collect();
+    let mut pubkeys: Vec<Vec<u8>> = signers.iter().map(|w| w.pubkey.clone()).collect();
+    pubkeys.sort();
+    let to = Wallet::from_seed(6, 1, &DEVNET);
+    let mut tx = new_multisig_transfer(&DEVNET, 1, &pubkeys, "GLD", GLD, &to.address, 1, 10, 1, NOW_MS).unwrap();
+    add_signature(&mut tx, &signers[0]).unwrap();
+
+    let raw = build_raw_tx_from_struct(&tx).unwrap();
+    assert!(decode_tx(&raw).is_ok());
+    for len in [raw.len() - 1, raw.len() - 66, raw.len() - 67] {
+        assert!(decode_tx(&raw[..len]).is_err(), "accepted raw tx cut to {} bytes", len);
+    }
+}
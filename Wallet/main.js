@@ -5,7 +5,28 @@ const BECH32_CHARSET = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
 
 const ec = new elliptic.ec("secp256k1");
 
-const url = "http://127.0.0.1:8080"
+// Network profiles: same chain_id and HRPs as the node (forgex_node::network).
+// multisig_hrp prefixes multisig account addresses.
+// min_base_fee (micro-GLD) is the fee floor of the network, used when the node can't estimate.
+const NETWORKS = {
+    devnet:  { chain_id: "gld-dev-1", hrp: "gld2", multisig_hrp: "gms2", min_base_fee: 10, rpc: "http://127.0.0.1:8080" },
+    testnet: { chain_id: "gld-test1", hrp: "gldt", multisig_hrp: "gmst", min_base_fee: 100, rpc: "http://127.0.0.1:6080" },
+    mainnet: { chain_id: "gld-main1", hrp: "gldm", multisig_hrp: "gmsm", min_base_fee: 100, rpc: "http://127.0.0.1:7080" },
+};
+
+// ?network=testnet picks (and remembers) the network, devnet by default
+function select_network() {
+    const requested = new URLSearchParams(window.location.search).get("network");
+    if (requested && NETWORKS[requested]) {
+        localStorage.setItem("forgex_network", requested);
+    }
+    const name = localStorage.getItem("forgex_network");
+    return NETWORKS[name] ? name : "devnet";
+}
+
+const network_name = select_network();
+const NETWORK = NETWORKS[network_name];
+const url = NETWORK.rpc;
 
 var priv_key
 var pub_key
@@ -293,14 +314,16 @@ function address(pubKey) {
         throw new Error("pubKey must be Uint8Array(33)");
     }
 
-    const core = pubKey.slice(1);
+    return address_from_core(pubKey.slice(1, 21));
+}
 
+// payload24 = hrp bytes | 20-byte core, then 4 bytes of sha256 checksum
+function address_from_core(core) {
     const payload24 = new Uint8Array(24);
-    payload24[0] = "g".charCodeAt(0);
-    payload24[1] = "l".charCodeAt(0);
-    payload24[2] = "d".charCodeAt(0);
-    payload24[3] = "2".charCodeAt(0);
-    payload24.set(core.slice(0, 20), 4);
+    for (let i = 0; i < 4; i++) {
+        payload24[i] = NETWORK.hrp.charCodeAt(i);
+    }
+    payload24.set(core, 4);
 
     const hash = sha256.array(payload24);
 
@@ -310,17 +333,33 @@ function address(pubKey) {
 
     const data5 = convertBits(full28, 8, 5, true);
 
-    const addr = bech32Encode("gld2", data5);
+    const addr = bech32Encode(NETWORK.hrp, data5);
 
     return addr;
 }
 
+// The same key on the selected network (a stored wallet may come from another one)
+function address_for_network(addr) {
+    const decoded = bech32Decode(addr);
+    if (!decoded || decoded.hrp === NETWORK.hrp) return addr;
+    const full28 = Uint8Array.from(convertBits(decoded.data, 5, 8, true)).slice(0, 28);
+    return address_from_core(full28.slice(4, 24));
+}
+
+// A well-formed address of one of the other known networks
+function is_other_network_address(addr) {
+    const decoded = bech32Decode(addr);
+    return !!decoded && decoded.hrp !== NETWORK.hrp && decoded.hrp !== NETWORK.multisig_hrp &&
+        Object.values(NETWORKS).some(n => n.hrp === decoded.hrp || n.multisig_hrp === decoded.hrp);
+}
+
 function is_valid_address(addr) {
   try {
     if (typeof addr !== "string") return false;
 
+    // a recipient may be a key account or a multisig account
     const decoded = bech32Decode(addr);
-    if (!decoded || decoded.hrp !== "gld2") return false;
+    if (!decoded || (decoded.hrp !== NETWORK.hrp && decoded.hrp !== NETWORK.multisig_hrp)) return false;
 
     const data5 = decoded.data;
     const fullBytes = Uint8Array.from(convertBits(data5, 5, 8, true));
@@ -328,13 +367,8 @@ function is_valid_address(addr) {
 
     const full28 = fullBytes.slice(0, 28);
 
-    if (
-      full28[0] !== "g".charCodeAt(0) ||
-      full28[1] !== "l".charCodeAt(0) ||
-      full28[2] !== "d".charCodeAt(0) ||
-      full28[3] !== "2".charCodeAt(0)
-    ) {
-      return false;
+    for (let i = 0; i < 4; i++) {
+      if (full28[i] !== decoded.hrp.charCodeAt(i)) return false;
     }
 
     const payload24 = full28.slice(0, 24);
@@ -432,14 +466,30 @@ async function decrypt_key(encrypted, password) {
 //Dashboard functions
 
 function load_dashboard() {
+    check_rpc_network();
     go("dashboard");
     nonce = load_nonce();
     dashboard_address.innerText = address;
     dashboard_balance.innerText = load_balance() + "GLD";
 }
 
-function calculate_fee(amount) {
-    return amount * 0.01;
+// node's "medium" suggestion to get into one of the next 3 blocks,
+// never below the network's min_base_fee (a lower fee is rejected anyway)
+function calculate_fee() {
+    try {
+        var response = JSON.parse(Get("/estimate_fee?target_blocks=3"));
+        return Math.max(response.medium || 0, NETWORK.min_base_fee);
+    } catch (e) {
+        return NETWORK.min_base_fee;
+    }
+}
+
+// Warn when the RPC serves another network than the one the wallet signs for
+function check_rpc_network() {
+    const response = JSON.parse(Get("/network"));
+    if (response.chain_id !== NETWORK.chain_id) {
+        alert("RPC is on " + response.network + ", wallet is on " + network_name);
+    }
 }
 
 //Conections functions
@@ -577,7 +627,7 @@ password_btn.addEventListener("click", async function() {
 
 unlock_btn.addEventListener("click", async function() {
     const wallet = load_data('forgex_wallet');
-    address = wallet.address;
+    address = address_for_network(wallet.address);
     pub_key = wallet.public_key;
 
     const result = await decrypt_key(wallet.private_key, unlock_input.value);
@@ -611,7 +661,7 @@ dashboard_btn.addEventListener("click", function() {
 dashboard_send_btn.addEventListener("click", function() {
     const toAddress = dashboard_address_input.value.trim();
     if (!is_valid_address(toAddress)) {
-        alert("Invalid address");
+        alert(is_other_network_address(toAddress) ? "Address belongs to another network" : "Invalid address");
         return;
     } else if (toAddress === address) {
         alert("Cannot send to your own address");
@@ -619,19 +669,19 @@ dashboard_send_btn.addEventListener("click", function() {
     }
     const amount = parseInt(parseFloat(dashboard_amount_input.value.trim())*1000000);
     const balance = parseInt(parseFloat(load_balance())*1000000);
-    const fee = calculate_fee(amount);
+    const fee = calculate_fee();
     nonce = nonce + 1;
     const timestamp_ms = Date.now();
     if (isNaN(amount) || amount <= 0) {
         alert("Invalid amount");
         return;
-    } else if (amount > (balance*1.01)) {
+    } else if (amount + fee > balance) {
         alert("Insufficient balance");
         return;
     }
     const timestamp_ms_str = timestamp_ms.toString();
     pipe_v1_str = pipe_v1_merge("GLD_TX_v1", 
-                "gld-dev-1", 
+                NETWORK.chain_id, 
                 "transfer", 
                 address, 
                 toAddress, 
@@ -642,7 +692,7 @@ dashboard_send_btn.addEventListener("click", function() {
                 timestamp_ms_str);
     const signature = sign_tx(pipe_v1_str);
     const tx_json = build_tx_json("GLD_TX_v1",
-                                  "gld-dev-1",
+                                  NETWORK.chain_id,
                                   "transfer",
                                   toAddress,
                                   "GLD",
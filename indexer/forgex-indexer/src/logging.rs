@@ -0,0 +1,26 @@
+use tracing_subscriber::EnvFilter;
+
+/// Поднять tracing-подписчик.
+///
+/// Уровни: FORGEX_LOG, иначе RUST_LOG, иначе "info".
+/// Формат: FORGEX_LOG_FORMAT ("text" | "json").
+pub fn init_logging() {
+    let filter = std::env::var("FORGEX_LOG")
+        .or_else(|_| std::env::var("RUST_LOG"))
+        .unwrap_or_else(|_| "info".into());
+
+    let filter = EnvFilter::try_new(&filter).unwrap_or_else(|e| {
+        eprintln!("invalid log filter {:?}: {}, falling back to info", filter, e);
+        EnvFilter::new("info")
+    });
+
+    let json = std::env::var("FORGEX_LOG_FORMAT").is_ok_and(|f| f == "json");
+
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+
+    if json {
+        builder.json().with_current_span(true).with_span_list(true).init();
+    } else {
+        builder.init();
+    }
+}
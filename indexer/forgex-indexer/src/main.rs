@@ -1,5 +1,8 @@
 mod model;
 mod storage;
+mod metrics;
+mod logging;
+mod network;
 mod p2p; // если у тебя есть p2p сервер
 
 use axum::{
@@ -10,12 +13,17 @@ use axum::{
 use tower_http::cors::CorsLayer;
 use http::Method;
 use serde::Serialize;
+use tracing::{error, info, info_span, warn};
 use crate::model::decode_block_raw;
+use crate::network::{network, select_network_from_env};
 use crate::storage::{
     store_block,
     get_latest_block,
     get_block_by_hash,
     get_block_by_tx_hash,
+    get_token,
+    get_tokens,
+    set_token_supply,
 };
 
 #[derive(Serialize)]
@@ -33,18 +41,31 @@ struct TxResponse {
     block: Option<model::Block>,
 }
 
+#[derive(Serialize)]
+struct TokensResponse {
+    tokens: Vec<model::Token>,
+}
+
+#[derive(Serialize)]
+struct TokenResponse {
+    token: Option<model::Token>,
+}
+
 fn handle_p2p_msg(data: Vec<u8>) {
     match decode_block_raw(&data) {
+        Ok(block) if block.header.chain_id != network().chain_id => {
+            warn!(chain_id = %block.header.chain_id, "block from another network");
+            metrics::block_decode_failed();
+        }
         Ok(block) => {
-            println!(
-                "Decoded block: height {}, tx_count {}",
-                block.header.height,
-                block.header.tx_count
-            );
+            let _span = info_span!("block", height = block.header.height).entered();
+            info!(block_id = %block.block_id, tx_count = block.header.tx_count, "indexed block");
             store_block(block);
+            metrics::block_indexed();
         }
         Err(e) => {
-            eprintln!("Failed to decode block: {}", e);
+            warn!(error = %e, "failed to decode block");
+            metrics::block_decode_failed();
         }
     }
 }
@@ -59,21 +80,92 @@ async fn http_get_block(Path(block_id): Path<String>) -> Json<BlockResponse> {
     Json(BlockResponse { block })
 }
 
+async fn http_metrics() -> ([(http::header::HeaderName, &'static str); 1], String) {
+    (
+        [(http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render_metrics(),
+    )
+}
+
 async fn http_get_tx(Path(tx_hash): Path<String>) -> Json<TxResponse> {
     let block = get_block_by_tx_hash(&tx_hash);
     Json(TxResponse { block })
 }
 
+async fn http_get_tokens() -> Json<TokensResponse> {
+    Json(TokensResponse { tokens: get_tokens() })
+}
+
+async fn http_get_token(Path(symbol): Path<String>) -> Json<TokenResponse> {
+    let token = get_token(&symbol);
+    Json(TokenResponse { token })
+}
+
+/// Раз в 5 секунд спрашиваем у ноды высоту — для метрики отставания,
+/// и общий выпуск известных токенов
+async fn run_node_height_poller(node_addr: String) {
+    loop {
+        match p2p::ask_node_height(&node_addr).await {
+            Ok(height) => metrics::set_node_height(height),
+            Err(e) => warn!(node = %node_addr, error = %e, "failed to ask node height"),
+        }
+
+        for token in get_tokens() {
+            match p2p::ask_node_token_supply(&node_addr, &token.symbol).await {
+                Ok(Some(supply)) => set_token_supply(&token.symbol, supply),
+                Ok(None) => {}
+                Err(e) => warn!(node = %node_addr, token = %token.symbol, error = %e, "failed to ask token supply"),
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+}
+
+/// Держим подписку на ноду, после обрыва переподключаемся
+/// и продолжаем с высоты, следующей за последним сохранённым блоком.
+async fn run_node_subscription(node_addr: String) {
+    loop {
+        let from_height = get_latest_block()
+            .map(|b| b.header.height + 1)
+            .unwrap_or(0);
+
+        if let Err(e) = p2p::run_subscriber(&node_addr, from_height, handle_p2p_msg).await {
+            warn!(node = %node_addr, error = %e, "subscription lost");
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    use tokio::net::TcpListener; 
+    use tokio::net::TcpListener;
+
+    logging::init_logging();
+
+    let net = select_network_from_env().map_err(anyhow::Error::msg)?;
+    info!(network = %net.name, chain_id = %net.chain_id, "network selected");
 
-    tokio::spawn(async {
-        if let Err(e) = p2p::run_p2p_server("0.0.0.0:9000", handle_p2p_msg).await {
-            eprintln!("P2P server error: {e}");
+    // адреса можно переопределить (devnet раздаёт свободные порты), по умолчанию — порты сети
+    let p2p_addr = std::env::var("FORGEX_INDEXER_P2P_ADDR").unwrap_or_else(|_| format!("0.0.0.0:{}", net.p2p_port));
+    let http_addr = std::env::var("FORGEX_INDEXER_HTTP_ADDR").unwrap_or_else(|_| format!("127.0.0.2:{}", net.http_port));
+
+    tokio::spawn(async move {
+        if let Err(e) = p2p::run_p2p_server(&p2p_addr, handle_p2p_msg).await {
+            error!(error = %e, "P2P server failed");
         }
     });
 
+    // Подписка на ноду вместо (или вместе с) push на :9000.
+    // FORGEX_NODE_SUBSCRIBE=127.0.0.1:5050 (порт P2P ноды в devnet)
+    if let Ok(node_addr) = std::env::var("FORGEX_NODE_SUBSCRIBE") {
+        tokio::spawn(run_node_subscription(node_addr));
+    }
+
+    let node_addr = std::env::var("FORGEX_NODE_ADDR").unwrap_or_else(|_| format!("127.0.0.1:{}", net.node_port));
+    tokio::spawn(run_node_height_poller(node_addr));
+
     // CORS: разрешим всё (для тестов ок)
     let cors = CorsLayer::new()
         .allow_origin(tower_http::cors::Any)
@@ -84,10 +176,13 @@ async fn main() -> anyhow::Result<()> {
         .route("/latest_block", get(http_latest_block))
         .route("/block/:block_id", get(http_get_block))
         .route("/tx/:tx_hash", get(http_get_tx))
+        .route("/tokens", get(http_get_tokens))
+        .route("/token/:symbol", get(http_get_token))
+        .route("/metrics", get(http_metrics))
         .layer(cors); // <- вот это важно
 
-    println!("HTTP RPC on http://127.0.0.2:8080");
-    let listener = TcpListener::bind("127.0.0.2:8080").await?;
+    info!(addr = %http_addr, "HTTP API listening");
+    let listener = TcpListener::bind(&http_addr).await?;
     axum::serve(listener, app).await?;
 
     Ok(())
@@ -0,0 +1,64 @@
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use once_cell::sync::Lazy;
+
+use crate::storage::get_latest_block;
+
+pub struct IndexerMetrics {
+    blocks_indexed: AtomicU64,
+    decode_failures: AtomicU64,
+    node_height: AtomicU64,
+    p2p_connections: AtomicU64,
+}
+
+pub static METRICS: Lazy<IndexerMetrics> = Lazy::new(|| IndexerMetrics {
+    blocks_indexed: AtomicU64::new(0),
+    decode_failures: AtomicU64::new(0),
+    node_height: AtomicU64::new(0),
+    p2p_connections: AtomicU64::new(0),
+});
+
+pub fn block_indexed() {
+    METRICS.blocks_indexed.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn block_decode_failed() {
+    METRICS.decode_failures.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn set_node_height(height: u64) {
+    METRICS.node_height.store(height, Ordering::Relaxed);
+}
+
+pub fn p2p_connection_opened() {
+    METRICS.p2p_connections.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn p2p_connection_closed() {
+    METRICS.p2p_connections.fetch_sub(1, Ordering::Relaxed);
+}
+
+fn render_value(out: &mut String, name: &str, kind: &str, help: &str, v: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "{} {}", name, v);
+}
+
+/// Все метрики индексера в текстовом формате Prometheus
+pub fn render_metrics() -> String {
+    let m = &*METRICS;
+    let mut out = String::new();
+
+    let indexed_height = get_latest_block().map(|b| b.header.height).unwrap_or(0);
+    let node_height = m.node_height.load(Ordering::Relaxed);
+
+    render_value(&mut out, "forgex_indexer_block_height", "gauge", "Height of the latest indexed block", indexed_height);
+    render_value(&mut out, "forgex_indexer_node_height", "gauge", "Latest height reported by the node", node_height);
+    render_value(&mut out, "forgex_indexer_lag_blocks", "gauge", "How many blocks the indexer is behind the node", node_height.saturating_sub(indexed_height));
+    render_value(&mut out, "forgex_indexer_blocks_indexed_total", "counter", "Blocks stored by the indexer", m.blocks_indexed.load(Ordering::Relaxed));
+    render_value(&mut out, "forgex_indexer_block_decode_failures_total", "counter", "Block messages that failed to decode", m.decode_failures.load(Ordering::Relaxed));
+    render_value(&mut out, "forgex_indexer_p2p_connections", "gauge", "Open P2P connections", m.p2p_connections.load(Ordering::Relaxed));
+
+    out
+}
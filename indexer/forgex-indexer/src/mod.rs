@@ -1,4 +1,7 @@
+pub mod logging;
+pub mod metrics;
 pub mod model;
+pub mod network;
 pub mod p2p; 
 pub mod storage;
  
\ No newline at end of file
@@ -18,6 +18,52 @@ pub struct ValidTxCore {
     pub timestamp: i64,
     pub pubkey: String,
     pub signature: String,
+    /// только у create_token (token — символ, amount — max_supply, to — issuer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_supply: Option<u64>,
+    /// только у multi_send: выплаты по порядку (to = from, amount — их сумма)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payouts: Option<Vec<Payout>>,
+    /// только у multisig_transfer: политика адреса from и подписи
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<Multisig>,
+    /// только у vesting_transfer: расписание разлока
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vesting: Option<Vesting>,
+    /// Memo отправителя (pipe_v2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payout {
+    pub to: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Multisig {
+    pub threshold: u8,
+    pub pubkeys: Vec<String>,
+    pub signatures: Vec<MultisigSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigSignature {
+    /// индекс ключа в pubkeys
+    pub index: u8,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vesting {
+    /// "height" или "time"
+    pub unlock_by: String,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +80,17 @@ pub struct BlockHeader {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockBody {
     pub txs: Vec<ValidTxCore>,
+    /// Награды производителю блока (выпуск и доля комиссий)
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reward {
+    pub to: String,
+    /// "issuance" или "fees"
+    pub kind: String,
+    pub amount: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +100,23 @@ pub struct Block {
     pub body: BlockBody,
 }
 
+/// Токен, заведённый в сети (GLD есть с генезиса)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Token {
+    pub symbol: String,
+    pub decimals: u8,
+    pub max_supply: u64,
+    pub issuer: String,
+    /// Блок и транза create_token; у GLD их нет
+    pub created_height: u64,
+    pub tx_hash: Option<String>,
+    /// Выпущено и сожжено по проиндексированным блокам (начальный выпуск — в minted)
+    pub minted: u64,
+    pub burned: u64,
+    /// Общий выпуск по данным ноды (раздачу в генезисе индексер из блоков не видит)
+    pub total_supply: Option<u64>,
+}
+
 const MAGIC: &[u8; 4] = b"FGX1";
 const MSG_TYPE_BLOCK: u8 = 7;
 
@@ -126,6 +200,21 @@ pub fn decode_block_raw(data: &[u8]) -> Result<Block, String> {
         txs.push(tx);
     }
 
+    // ===== REWARDS =====
+
+    let reward_count = read_u16(payload, &mut offset)? as usize;
+    let mut rewards = Vec::with_capacity(reward_count);
+    for _ in 0..reward_count {
+        let to = read_str_u16(payload, &mut offset)?;
+        let kind = match read_u8(payload, &mut offset)? {
+            1 => "issuance",
+            2 => "fees",
+            other => return Err(format!("unknown reward kind {}", other)),
+        };
+        let amount = read_u64(payload, &mut offset)?;
+        rewards.push(Reward { to, kind: kind.into(), amount });
+    }
+
     let header = BlockHeader {
         version,
         chain_id,
@@ -136,7 +225,7 @@ pub fn decode_block_raw(data: &[u8]) -> Result<Block, String> {
         tx_root,
     };
 
-    let body = BlockBody { txs };
+    let body = BlockBody { txs, rewards };
 
     Ok(Block {
         block_id,
@@ -161,6 +250,10 @@ pub fn decode_block_raw(data: &[u8]) -> Result<Block, String> {
 // i64       timestamp
 // 33 байта  pubkey
 // 65 байт   signature
+// create_token дальше: u8 decimals, u64 initial_supply
+// multi_send дальше: u16 count, count * (str_u16 to, u64 amount)
+// multisig_transfer дальше: u8 threshold, u8 n, n * 33 pubkey, u8 m, m * (u8 index, 65 signature)
+// vesting_transfer дальше: u8 clock (0 — height, 1 — time), u64 start, u64 cliff, u64 duration
 //
 
 fn decode_tx_from_block(buf: &[u8]) -> Result<ValidTxCore, String> {
@@ -212,6 +305,73 @@ fn decode_tx_from_block(buf: &[u8]) -> Result<ValidTxCore, String> {
         return Err("not enough bytes for signature".into());
     }
     let signature = hex::encode(&buf[offset..offset + 65]);
+    offset += 65;
+
+    // поля create_token
+    let (decimals, initial_supply) = if tx_type == "create_token" {
+        if offset + 1 > buf.len() {
+            return Err("not enough bytes for decimals".into());
+        }
+        let decimals = buf[offset];
+        offset += 1;
+        (Some(decimals), Some(read_u64(buf, &mut offset)?))
+    } else {
+        (None, None)
+    };
+
+    // выплаты multi_send
+    let payouts = if tx_type == "multi_send" {
+        let count = read_u16(buf, &mut offset)? as usize;
+        let mut payouts = Vec::with_capacity(count);
+        for _ in 0..count {
+            let to = read_str_u16(buf, &mut offset)?;
+            let amount = read_u64(buf, &mut offset)?;
+            payouts.push(Payout { to, amount });
+        }
+        Some(payouts)
+    } else {
+        None
+    };
+
+    // политика и подписи multisig_transfer
+    let multisig = if tx_type == "multisig_transfer" {
+        let threshold = read_u8(buf, &mut offset)?;
+        let n = read_u8(buf, &mut offset)? as usize;
+        let mut pubkeys = Vec::with_capacity(n);
+        for _ in 0..n {
+            pubkeys.push(hex::encode(read_bytes(buf, &mut offset, 33)?));
+        }
+        let m = read_u8(buf, &mut offset)? as usize;
+        let mut signatures = Vec::with_capacity(m);
+        for _ in 0..m {
+            let index = read_u8(buf, &mut offset)?;
+            let signature = hex::encode(read_bytes(buf, &mut offset, 65)?);
+            signatures.push(MultisigSignature { index, signature });
+        }
+        Some(Multisig { threshold, pubkeys, signatures })
+    } else {
+        None
+    };
+
+    // расписание vesting_transfer
+    let vesting = if tx_type == "vesting_transfer" {
+        let unlock_by = match read_u8(buf, &mut offset)? {
+            0 => "height",
+            1 => "time",
+            other => return Err(format!("unknown vesting clock {}", other)),
+        };
+        Some(Vesting {
+            unlock_by: unlock_by.into(),
+            start: read_u64(buf, &mut offset)?,
+            cliff: read_u64(buf, &mut offset)?,
+            duration: read_u64(buf, &mut offset)?,
+        })
+    } else {
+        None
+    };
+
+    // memo есть у каждой транзы в блоке, пустое — без memo
+    let memo = Some(read_str_u16(buf, &mut offset)?).filter(|m| !m.is_empty());
 
     Ok(ValidTxCore {
         tx_hash,
@@ -225,12 +385,27 @@ fn decode_tx_from_block(buf: &[u8]) -> Result<ValidTxCore, String> {
         timestamp,
         pubkey,
         signature,
+        decimals,
+        initial_supply,
+        payouts,
+        multisig,
+        vesting,
+        memo,
     })
 }
 
 fn decode_tx_type(code: u8) -> Result<String, String> {
     match code {
         1 => Ok("transfer".into()),
+        2 => Ok("create_token".into()),
+        3 => Ok("mint".into()),
+        4 => Ok("burn".into()),
+        5 => Ok("multi_send".into()),
+        6 => Ok("multisig_transfer".into()),
+        7 => Ok("vesting_transfer".into()),
+        8 => Ok("stake".into()),
+        9 => Ok("unstake".into()),
+        10 => Ok("delegate".into()),
         other => Err(format!("unknown tx_type code {}", other)),
     }
 }
@@ -239,6 +414,21 @@ fn decode_tx_type(code: u8) -> Result<String, String> {
 // ==== ХЕЛПЕРЫ ДЛЯ ЧТЕНИЯ ====
 //
 
+fn read_u8(buf: &[u8], offset: &mut usize) -> Result<u8, String> {
+    let v = *buf.get(*offset).ok_or_else(|| "read_u8 out of bounds".to_string())?;
+    *offset += 1;
+    Ok(v)
+}
+
+fn read_bytes<'a>(buf: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    if *offset + len > buf.len() {
+        return Err("read_bytes out of bounds".into());
+    }
+    let v = &buf[*offset..*offset + len];
+    *offset += len;
+    Ok(v)
+}
+
 fn read_u16(buf: &[u8], offset: &mut usize) -> Result<u16, String> {
     if *offset + 2 > buf.len() {
         return Err("read_u16 out of bounds".into());
@@ -0,0 +1,61 @@
+//! Профиль сети: FORGEX_NETWORK (devnet, testnet, mainnet; по умолчанию devnet).
+//! Та же таблица, что у ноды (forgex_node::network), только поля индексера.
+
+use once_cell::sync::OnceCell;
+
+#[derive(Debug)]
+pub struct NetworkProfile {
+    pub name: &'static str,
+    /// Блоки другой сети не индексируем
+    pub chain_id: &'static str,
+    /// Казна — issuer GLD; адрес в HRP сети
+    pub treasury: &'static str,
+    /// Порты по умолчанию: куда нода пушит блоки, HTTP API, P2P ноды
+    pub p2p_port: u16,
+    pub http_port: u16,
+    pub node_port: u16,
+}
+
+pub const NETWORKS: &[NetworkProfile] = &[
+    NetworkProfile {
+        name: "devnet",
+        chain_id: "gld-dev-1",
+        treasury: "gld21vakxgv57d5snlk3t06emkcu7uyk5snmu5xtsp7sjtasx69tqawy",
+        p2p_port: 9000,
+        http_port: 8080,
+        node_port: 5050,
+    },
+    NetworkProfile {
+        name: "testnet",
+        chain_id: "gld-test1",
+        treasury: "gldt1vakxgay7d5snlk3t06emkcu7uyk5snmu5xtsp752uq4fvm6vp3x",
+        p2p_port: 6000,
+        http_port: 6090,
+        node_port: 6050,
+    },
+    NetworkProfile {
+        name: "mainnet",
+        chain_id: "gld-main1",
+        treasury: "gldm1vakxgmv7d5snlk3t06emkcu7uyk5snmu5xtsp73l3gmpztmm359",
+        p2p_port: 7000,
+        http_port: 7090,
+        node_port: 7050,
+    },
+];
+
+static ACTIVE: OnceCell<&'static NetworkProfile> = OnceCell::new();
+
+/// Выбрать сеть по FORGEX_NETWORK (один раз, при старте)
+pub fn select_network_from_env() -> Result<&'static NetworkProfile, String> {
+    let name = std::env::var("FORGEX_NETWORK").unwrap_or_else(|_| "devnet".into());
+    let profile = NETWORKS
+        .iter()
+        .find(|n| n.name == name)
+        .ok_or_else(|| format!("unknown network {} (known: devnet, testnet, mainnet)", name))?;
+    Ok(*ACTIVE.get_or_init(|| profile))
+}
+
+/// Активная сеть (до выбора — devnet)
+pub fn network() -> &'static NetworkProfile {
+    ACTIVE.get().copied().unwrap_or(&NETWORKS[0])
+}
@@ -1,6 +1,15 @@
 use anyhow::Result;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
+use tracing::{Instrument, debug, info, info_span, warn};
+
+use crate::metrics::{p2p_connection_closed, p2p_connection_opened};
+
+const MSG_TYPE_SUBSCRIBE: u8 = 8;
+const MSG_TYPE_ASK_HEIGHT: u8 = 10;
+const MSG_TYPE_HEIGHT: u8 = 11;
+const MSG_TYPE_ASK_TOKEN: u8 = 16;
+const MSG_TYPE_TOKEN: u8 = 17;
 
 /// handler: функция, которая принимает сырые байты сообщения
 /// НИЧЕГО не возвращает, просто обрабатывает (логика в main)
@@ -9,14 +18,21 @@ pub async fn run_p2p_server(
     handler: fn(Vec<u8>),
 ) -> Result<()> {
     let listener = TcpListener::bind(addr).await?;
-    println!("P2P node listening on {}", addr);
+    info!(addr = %addr, "P2P listening");
 
     loop {
         let (socket, peer_addr) = listener.accept().await?;
-        println!("Incoming connection from {}", peer_addr);
-
-        // на каждое соединение — отдельная таска
-        tokio::spawn(handle_connection(socket, handler));
+        // на каждое соединение — отдельная таска со своим span
+        let span = info_span!("peer", peer = %peer_addr);
+        tokio::spawn(
+            async move {
+                debug!("incoming connection");
+                p2p_connection_opened();
+                handle_connection(socket, handler).await;
+                p2p_connection_closed();
+            }
+            .instrument(span),
+        );
     }
 }
 
@@ -26,21 +42,110 @@ async fn handle_connection(mut stream: TcpStream, handler: fn(Vec<u8>)) {
     loop {
         match stream.read(&mut buf).await {
             Ok(0) => {
-                println!("Peer disconnected");
+                debug!("peer disconnected");
                 return; // соединение закрыто
             }
             Ok(n) => {
                 let msg = buf[..n].to_vec();
-                println!("Received {} bytes", n);
+                debug!(bytes = n, "received message");
 
                 // просто передаём данные в handler (который ты объявишь в main)
                 handler(msg);
                 // НИЧЕГО не пишем в stream
             }
             Err(e) => {
-                eprintln!("Socket read error: {}", e);
+                warn!(error = %e, "socket read error");
                 return;
             }
         }
     }
-}
\ No newline at end of file
+}
+/// Подписка на поток блоков ноды (FGX1 SUBSCRIBE с from_height).
+/// Соединение долгоживущее: читаем кадры FGX1|type|u32 len|payload
+/// и каждый кадр целиком отдаём в handler.
+/// Возвращается только с ошибкой (обрыв соединения и т.п.).
+pub async fn run_subscriber(
+    node_addr: &str,
+    from_height: u64,
+    handler: fn(Vec<u8>),
+) -> Result<()> {
+    let mut stream = TcpStream::connect(node_addr).await?;
+    info!(node = %node_addr, from_height, "subscribed to node");
+
+    // FGX1 | 8 | len=9 | from_height (u64) | flags (u8, без квитанций)
+    let mut req = Vec::with_capacity(16);
+    req.extend_from_slice(b"FGX1");
+    req.push(MSG_TYPE_SUBSCRIBE);
+    req.extend_from_slice(&9u16.to_be_bytes());
+    req.extend_from_slice(&from_height.to_be_bytes());
+    req.push(0);
+    stream.write_all(&req).await?;
+
+    loop {
+        let mut header = [0u8; 9];
+        stream.read_exact(&mut header).await?;
+
+        if &header[0..4] != b"FGX1" {
+            anyhow::bail!("invalid magic in block stream");
+        }
+
+        let payload_len = u32::from_be_bytes([header[5], header[6], header[7], header[8]]) as usize;
+
+        let mut frame = Vec::with_capacity(9 + payload_len);
+        frame.extend_from_slice(&header);
+        frame.resize(9 + payload_len, 0);
+        stream.read_exact(&mut frame[9..]).await?;
+
+        handler(frame);
+    }
+}
+
+/// Спросить у ноды высоту последнего блока (FGX1 ASK_HEIGHT)
+pub async fn ask_node_height(node_addr: &str) -> Result<u64> {
+    let mut stream = TcpStream::connect(node_addr).await?;
+
+    let mut req = Vec::with_capacity(7);
+    req.extend_from_slice(b"FGX1");
+    req.push(MSG_TYPE_ASK_HEIGHT);
+    req.extend_from_slice(&0u16.to_be_bytes());
+    stream.write_all(&req).await?;
+
+    // FGX1 | 11 | len=8 | height
+    let mut resp = [0u8; 15];
+    stream.read_exact(&mut resp).await?;
+
+    if &resp[0..4] != b"FGX1" || resp[4] != MSG_TYPE_HEIGHT {
+        anyhow::bail!("unexpected height response");
+    }
+
+    Ok(u64::from_be_bytes(resp[7..15].try_into()?))
+}
+
+/// Спросить у ноды общий выпуск токена (FGX1 ASK_TOKEN).
+/// None — нода такого токена не знает.
+pub async fn ask_node_token_supply(node_addr: &str, symbol: &str) -> Result<Option<u64>> {
+    let mut stream = TcpStream::connect(node_addr).await?;
+
+    let mut req = Vec::with_capacity(7 + symbol.len());
+    req.extend_from_slice(b"FGX1");
+    req.push(MSG_TYPE_ASK_TOKEN);
+    req.extend_from_slice(&(symbol.len() as u16).to_be_bytes());
+    req.extend_from_slice(symbol.as_bytes());
+    stream.write_all(&req).await?;
+
+    // FGX1 | 17 | len | found | code | decimals | max_supply (u64) | supply (u64) | issuer | symbol
+    let mut header = [0u8; 7];
+    stream.read_exact(&mut header).await?;
+    if &header[0..4] != b"FGX1" || header[4] != MSG_TYPE_TOKEN {
+        anyhow::bail!("unexpected token response");
+    }
+
+    let mut payload = vec![0u8; u16::from_be_bytes([header[5], header[6]]) as usize];
+    stream.read_exact(&mut payload).await?;
+
+    match payload.first() {
+        Some(0) => Ok(None),
+        Some(_) if payload.len() >= 19 => Ok(Some(u64::from_be_bytes(payload[11..19].try_into()?))),
+        _ => anyhow::bail!("truncated token response"),
+    }
+}
@@ -3,7 +3,8 @@ use std::sync::RwLock;
 
 use once_cell::sync::Lazy;
 
-use crate::model::{Block, BlockBody, ValidTxCore};
+use crate::model::{Block, BlockBody, Token};
+use crate::network::network;
 
 /// Глобальное in-memory хранилище
 static STORAGE: Lazy<RwLock<InMemoryStorage>> = Lazy::new(|| {
@@ -16,6 +17,8 @@ struct InMemoryStorage {
     blocks: HashMap<String, Block>,
     /// tx_hash -> block_id
     tx_to_block: HashMap<String, String>,
+    /// Токены в порядке создания
+    tokens: Vec<Token>,
 }
 
 impl InMemoryStorage {
@@ -23,15 +26,70 @@ impl InMemoryStorage {
         Self {
             blocks: HashMap::new(),
             tx_to_block: HashMap::new(),
+            tokens: vec![Token {
+                symbol: "GLD".into(),
+                decimals: 6,
+                max_supply: u64::MAX,
+                issuer: network().treasury.into(),
+                created_height: 0,
+                tx_hash: None,
+                minted: 0,
+                burned: 0,
+                total_supply: None,
+            }],
         }
     }
 
     fn insert_block(&mut self, block: Block) {
         let block_id = block.block_id.clone();
 
+        // блок может прийти повторно (push + подписка) — второй раз его не считаем
+        if self.blocks.contains_key(&block_id) {
+            return;
+        }
+
         // проиндексируем все tx из блока
         for tx in &block.body.txs {
             self.tx_to_block.insert(tx.tx_hash.clone(), block_id.clone());
+
+            match tx.tx_type.as_str() {
+                "create_token" if self.get_token(&tx.token).is_none() => {
+                    self.tokens.push(Token {
+                        symbol: tx.token.clone(),
+                        decimals: tx.decimals.unwrap_or(0),
+                        max_supply: tx.amount,
+                        issuer: tx.to.clone(),
+                        created_height: block.header.height,
+                        tx_hash: Some(tx.tx_hash.clone()),
+                        minted: tx.initial_supply.unwrap_or(0),
+                        burned: 0,
+                        total_supply: None,
+                    });
+                }
+                "mint" => {
+                    if let Some(t) = self.tokens.iter_mut().find(|t| t.symbol == tx.token) {
+                        t.minted = t.minted.saturating_add(tx.amount);
+                    }
+                }
+                "burn" => {
+                    if let Some(t) = self.tokens.iter_mut().find(|t| t.symbol == tx.token) {
+                        t.burned = t.burned.saturating_add(tx.amount);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // выпуск за блок — новые GLD
+        let issued: u64 = block
+            .body
+            .rewards
+            .iter()
+            .filter(|r| r.kind == "issuance")
+            .map(|r| r.amount)
+            .fold(0, u64::saturating_add);
+        if let Some(gld) = self.tokens.first_mut() {
+            gld.minted = gld.minted.saturating_add(issued);
         }
 
         self.blocks.insert(block_id, block);
@@ -41,6 +99,10 @@ impl InMemoryStorage {
         self.blocks.get(block_id).cloned()
     }
 
+    fn get_token(&self, symbol: &str) -> Option<&Token> {
+        self.tokens.iter().find(|t| t.symbol == symbol)
+    }
+
     fn get_latest_block(&self) -> Option<Block> {
         self.blocks
             .values()
@@ -61,8 +123,8 @@ impl InMemoryStorage {
             .find(|t| t.tx_hash == tx_hash)?
             .clone();
 
-        // собираем новый блок с тем же хедером и id, но одной транзой
-        let single_body = BlockBody { txs: vec![tx] };
+        // собираем новый блок с тем же хедером и id, но одной транзой (без наград)
+        let single_body = BlockBody { txs: vec![tx], rewards: Vec::new() };
 
         Some(Block {
             block_id: full_block.block_id.clone(),
@@ -97,3 +159,23 @@ pub fn get_block_by_tx_hash(tx_hash: &str) -> Option<Block> {
     let s = STORAGE.read().expect("lock read");
     s.get_block_by_tx(tx_hash)
 }
+
+/// Все известные токены в порядке создания
+pub fn get_tokens() -> Vec<Token> {
+    let s = STORAGE.read().expect("lock read");
+    s.tokens.clone()
+}
+
+/// Токен по символу
+pub fn get_token(symbol: &str) -> Option<Token> {
+    let s = STORAGE.read().expect("lock read");
+    s.get_token(symbol).cloned()
+}
+
+/// Запомнить общий выпуск токена, который сообщила нода
+pub fn set_token_supply(symbol: &str, supply: u64) {
+    let mut s = STORAGE.write().expect("lock write");
+    if let Some(t) = s.tokens.iter_mut().find(|t| t.symbol == symbol) {
+        t.total_supply = Some(supply);
+    }
+}
@@ -1,10 +1,13 @@
 mod p2p;
 mod validate;
 mod model;
+mod metrics;
+mod logging;
+mod network;
 
 use axum::{
     routing::{get, post},
-    Json, Router, extract::Query
+    Json, Router, extract::Query, middleware
 };
 
 use http::Method;
@@ -13,16 +16,29 @@ use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tower_http::cors::{Any, CorsLayer};
 use p2p::p2p_send;
-use crate::validate::Tx;
-use crate::validate::parse_tx;
-use model::{make_raw_tx, send_tx, ask_balance, ask_nonce, decode_p2p_response};
+use crate::network::{network, select_network_from_env};
+use crate::validate::{Tx, parse_tx};
+use model::{make_raw_tx, send_tx, ask_balance, ask_nonce, ask_token, ask_validators, ask_fees, ask_fee_estimate, decode_p2p_response};
 use sha2::{Sha256, Digest};
+use once_cell::sync::Lazy;
+use tracing::{Instrument, info, info_span, warn};
 
-const IP_PORT: &str = "127.0.0.1:5050";
+/// P2P-адрес ноды: FORGEX_NODE_ADDR или порт ноды выбранной сети (в devnet 127.0.0.1:5050)
+static NODE_ADDR: Lazy<String> = Lazy::new(|| {
+    std::env::var("FORGEX_NODE_ADDR").unwrap_or_else(|_| format!("127.0.0.1:{}", network().node_port))
+});
 
 
 #[tokio::main]
 async fn main() {
+    logging::init_logging();
+
+    let net = select_network_from_env().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+    info!(network = %net.name, chain_id = %net.chain_id, "network selected");
+
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods([Method::GET, Method::POST])
@@ -31,14 +47,25 @@ async fn main() {
     let app = Router::new()
         .route("/health", get(health_check))
         .route("/get_info", get(get_info))
+        .route("/network", get(get_network))
         .route("/balance", get(get_balance))
         .route("/nonce", get(get_nonce))
+        .route("/token", get(get_token))
+        .route("/validators", get(get_validators))
+        .route("/base_fee", get(get_base_fee))
+        .route("/estimate_fee", get(get_estimate_fee))
         .route("/broadcast_tx", post(broadcast_tx))
+        .route("/metrics", get(get_metrics))
+        .route_layer(middleware::from_fn(metrics::track_requests))
         .layer(cors);
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+    // FORGEX_RPC_ADDR переопределяет адрес (devnet раздаёт свободные порты)
+    let addr: SocketAddr = std::env::var("FORGEX_RPC_ADDR")
+        .ok()
+        .and_then(|a| a.parse().ok())
+        .unwrap_or_else(|| SocketAddr::from(([127, 0, 0, 1], net.rpc_port)));
     let listener = TcpListener::bind(addr).await.unwrap();
-    println!("RPC server running on http://{}", listener.local_addr().unwrap());
+    info!(addr = %listener.local_addr().unwrap(), "RPC server listening");
 
     axum::serve(listener, app).await.unwrap();
 }
@@ -49,6 +76,13 @@ async fn health_check() -> &'static str {
     "OK"
 }
 
+async fn get_metrics() -> ([(http::header::HeaderName, &'static str); 1], String) {
+    (
+        [(http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render_metrics(),
+    )
+}
+
 async fn get_info() -> Json<Value> {
     Json(json!({
         "service": "forgex_rpc",
@@ -57,25 +91,37 @@ async fn get_info() -> Json<Value> {
     }))
 }
 
+/// Сеть, к которой подключён RPC: кошелёк сверяет с ней chain_id и HRP адресов
+async fn get_network() -> Json<Value> {
+    let net = network();
+    Json(json!({
+        "network": net.name,
+        "chain_id": net.chain_id,
+        "hrp": net.hrp,
+        "multisig_hrp": net.multisig_hrp
+    }))
+}
+
 async fn get_balance(Query(params): Query<Value>) -> Json<Value> {
     let address = params.get("address").and_then(|v| v.as_str()).unwrap_or("");
     let token   = params.get("token").and_then(|v| v.as_str()).unwrap_or("GLD");
 
     let msg = ask_balance(address, token).unwrap();
-    let raw_res = p2p_send(IP_PORT, &msg).await.unwrap();
+    let raw_res = p2p_send(&NODE_ADDR, &msg).await.unwrap();
 
     let decoded = decode_p2p_response(&raw_res).unwrap();
 
     Json(json!({
         "address": decoded.address.unwrap(),
-        "balance": decoded.balance.unwrap()
+        "balance": decoded.balance.unwrap(),
+        "locked": decoded.locked.unwrap_or(0)
     }))
 }
 
 async fn get_nonce(Query(params): Query<Value>) -> Json<Value> {
     let address = params.get("address").and_then(|v| v.as_str()).unwrap_or("");
-    let msg = ask_nonce(&address).unwrap();
-    let raw_res = p2p_send(IP_PORT, &msg).await.unwrap();
+    let msg = ask_nonce(address).unwrap();
+    let raw_res = p2p_send(&NODE_ADDR, &msg).await.unwrap();
 
     let decoded = decode_p2p_response(&raw_res).unwrap();
 
@@ -85,31 +131,172 @@ async fn get_nonce(Query(params): Query<Value>) -> Json<Value> {
     }))
 }
 
+async fn get_token(Query(params): Query<Value>) -> Json<Value> {
+    let symbol = params.get("symbol").and_then(|v| v.as_str()).unwrap_or("");
+
+    match lookup_token(symbol).await {
+        Ok(Some(t)) => Json(json!({
+            "symbol": t.symbol,
+            "code": t.code,
+            "decimals": t.decimals,
+            "max_supply": t.max_supply,
+            "supply": t.supply,
+            "issuer": t.issuer
+        })),
+        Ok(None) => Json(json!({
+            "symbol": symbol,
+            "error": "unknown token"
+        })),
+        Err(e) => {
+            warn!(token = %symbol, error = %e, "token lookup failed");
+            Json(json!({
+                "symbol": symbol,
+                "error": e
+            }))
+        }
+    }
+}
+
+/// Активный набор валидаторов текущей эпохи
+async fn get_validators() -> Json<Value> {
+    let res = async {
+        let raw_res = p2p_send(&NODE_ADDR, &ask_validators()).await.map_err(|e| e.to_string())?;
+        decode_p2p_response(&raw_res)?
+            .validators
+            .ok_or_else(|| "unexpected response".to_string())
+    }
+    .await;
+
+    match res {
+        Ok(set) => Json(json!({
+            "epoch": set.epoch,
+            "validators": set
+                .validators
+                .iter()
+                .map(|(address, stake)| json!({ "address": address, "stake": stake }))
+                .collect::<Vec<_>>()
+        })),
+        Err(e) => {
+            warn!(error = %e, "validators lookup failed");
+            Json(json!({ "error": e }))
+        }
+    }
+}
+
+/// base fee следующего блока: fee транзы должна быть не ниже,
+/// всё сверх — чаевые производителю (порядок в блоке по fee)
+async fn get_base_fee() -> Json<Value> {
+    let res = async {
+        let raw_res = p2p_send(&NODE_ADDR, &ask_fees()).await.map_err(|e| e.to_string())?;
+        decode_p2p_response(&raw_res)?
+            .fees
+            .ok_or_else(|| "unexpected response".to_string())
+    }
+    .await;
+
+    match res {
+        Ok(fees) => Json(json!({
+            "height": fees.height,
+            "base_fee": fees.base_fee,
+            "min_base_fee": fees.min_base_fee,
+            "target_block_txs": fees.target_block_txs,
+            "burn_percent": fees.burn_percent,
+            "proposer_percent": fees.proposer_percent
+        })),
+        Err(e) => {
+            warn!(error = %e, "base fee lookup failed");
+            Json(json!({ "error": e }))
+        }
+    }
+}
+
+/// Сколько ставить fee за обычный transfer, чтобы попасть в блок
+/// за target_blocks блоков (по умолчанию 1): low / medium / high
+async fn get_estimate_fee(Query(params): Query<Value>) -> Json<Value> {
+    let target = params.get("target_blocks").and_then(|v| v.as_str()).unwrap_or("1");
+
+    let res = async {
+        let target_blocks: u16 = target.parse().map_err(|_| format!("invalid target_blocks: {}", target))?;
+        let raw_res = p2p_send(&NODE_ADDR, &ask_fee_estimate(target_blocks)).await.map_err(|e| e.to_string())?;
+        decode_p2p_response(&raw_res)?
+            .fee_estimate
+            .ok_or_else(|| "unexpected response".to_string())
+    }
+    .await;
+
+    match res {
+        Ok(e) => Json(json!({
+            "target_blocks": e.target_blocks,
+            "base_fee": e.base_fee,
+            "low": e.low,
+            "medium": e.medium,
+            "high": e.high
+        })),
+        Err(e) => {
+            warn!(error = %e, "fee estimate failed");
+            Json(json!({ "error": e }))
+        }
+    }
+}
+
+/// Спросить у ноды запись реестра токенов
+async fn lookup_token(symbol: &str) -> Result<Option<model::TokenInfo>, String> {
+    let msg = ask_token(symbol)?;
+    let raw_res = p2p_send(&NODE_ADDR, &msg).await.map_err(|e| e.to_string())?;
+    Ok(decode_p2p_response(&raw_res)?.token)
+}
+
+/// Код токена для raw tx: create_token заводит новый (0), остальным — из реестра ноды
+async fn token_code(tx: &Tx) -> Result<u8, String> {
+    if tx.tx_type == "create_token" {
+        return Ok(0);
+    }
+    lookup_token(&tx.token)
+        .await?
+        .map(|t| t.code)
+        .ok_or_else(|| format!("unknown token {}", tx.token))
+}
+
 async fn broadcast_tx(Json(body): Json<Value>) -> Json<Value> {
     let tx_opt = parse_tx(&body.to_string());
 
     if let Some(ref tx) = tx_opt {
-        match make_raw_tx(tx) {
+        let raw = match token_code(tx).await {
+            Ok(code) => make_raw_tx(tx, code),
+            Err(e) => Err(e),
+        };
+        match raw {
             Ok(raw) => {
                 let tx_hash: [u8; 32] = sha256_bytes(&raw);
                 let tx_hash_hex = bytes_to_hex(&tx_hash);
+                let span = info_span!("tx", tx_hash = %tx_hash_hex);
 
-                let sent_tx = send_tx(&raw).unwrap();
-                let raw_res = p2p_send(IP_PORT, &sent_tx).await.unwrap();
+                async move {
+                    let sent_tx = send_tx(&raw).unwrap();
+                    let raw_res = p2p_send(&NODE_ADDR, &sent_tx).await.unwrap();
 
-                let decoded = decode_p2p_response(&raw_res).unwrap();
+                    let decoded = decode_p2p_response(&raw_res).unwrap();
+                    let status = decoded.status.unwrap_or("unknown".into());
+                    info!(status = %status, "tx broadcast");
 
+                    Json(json!({
+                        "status": status,
+                        "tx_hash": tx_hash_hex
+                    }))
+                }
+                .instrument(span)
+                .await
+            }
+            Err(e) => {
+                warn!(error = %e, "failed to build raw tx");
                 Json(json!({
-                    "status": decoded.status.unwrap_or("unknown".into()),
-                    "tx_hash": tx_hash_hex
+                    "status": "rejected",
+                    "reason": format!("Raw tx build error: {}", e)
                 }))
             }
-            Err(e) => Json(json!({
-                "status": "rejected",
-                "reason": format!("Raw tx build error: {}", e)
-            })),
         }
     } else {
+        warn!("rejected invalid transaction json");
         Json(json!({
             "status": "rejected",
             "reason": "Invalid transaction"
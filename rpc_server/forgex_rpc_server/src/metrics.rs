@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use once_cell::sync::Lazy;
+use tracing::{Instrument, debug, info_span};
+
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+
+struct RouteStats {
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+pub struct RpcMetrics {
+    /// (route, status) -> число запросов
+    requests: Mutex<BTreeMap<(String, u16), u64>>,
+    /// route -> гистограмма латентности
+    latency: Mutex<BTreeMap<String, RouteStats>>,
+    p2p_requests: AtomicU64,
+    p2p_failures: AtomicU64,
+}
+
+pub static METRICS: Lazy<RpcMetrics> = Lazy::new(|| RpcMetrics {
+    requests: Mutex::new(BTreeMap::new()),
+    latency: Mutex::new(BTreeMap::new()),
+    p2p_requests: AtomicU64::new(0),
+    p2p_failures: AtomicU64::new(0),
+});
+
+fn observe(route: &str, status: u16, seconds: f64) {
+    *METRICS
+        .requests
+        .lock()
+        .unwrap()
+        .entry((route.to_string(), status))
+        .or_insert(0) += 1;
+
+    let mut latency = METRICS.latency.lock().unwrap();
+    let stats = latency.entry(route.to_string()).or_insert_with(|| RouteStats {
+        counts: vec![0; LATENCY_BUCKETS.len()],
+        sum: 0.0,
+        count: 0,
+    });
+    if let Some(i) = LATENCY_BUCKETS.iter().position(|b| seconds <= *b) {
+        stats.counts[i] += 1;
+    }
+    stats.sum += seconds;
+    stats.count += 1;
+}
+
+/// Запрос к ноде по P2P (ok = false, если соединение/ответ не удались)
+pub fn p2p_request(ok: bool) {
+    METRICS.p2p_requests.fetch_add(1, Ordering::Relaxed);
+    if !ok {
+        METRICS.p2p_failures.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Middleware: span на запрос, латентность и статус по шаблону маршрута
+pub async fn track_requests(req: Request, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".into());
+
+    let span = info_span!("request", method = %req.method(), route = %route);
+
+    let started = Instant::now();
+    let response = next.run(req).instrument(span.clone()).await;
+    let elapsed = started.elapsed().as_secs_f64();
+    observe(&route, response.status().as_u16(), elapsed);
+
+    span.in_scope(|| debug!(status = response.status().as_u16(), seconds = elapsed, "request finished"));
+
+    response
+}
+
+/// Все метрики RPC в текстовом формате Prometheus
+pub fn render_metrics() -> String {
+    let m = &*METRICS;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# HELP forgex_rpc_requests_total HTTP requests by route and status");
+    let _ = writeln!(out, "# TYPE forgex_rpc_requests_total counter");
+    for ((route, status), n) in m.requests.lock().unwrap().iter() {
+        let _ = writeln!(out, "forgex_rpc_requests_total{{route=\"{}\",status=\"{}\"}} {}", route, status, n);
+    }
+
+    let _ = writeln!(out, "# HELP forgex_rpc_request_duration_seconds HTTP request latency by route");
+    let _ = writeln!(out, "# TYPE forgex_rpc_request_duration_seconds histogram");
+    for (route, stats) in m.latency.lock().unwrap().iter() {
+        let mut cumulative = 0;
+        for (bound, c) in LATENCY_BUCKETS.iter().zip(&stats.counts) {
+            cumulative += c;
+            let _ = writeln!(out, "forgex_rpc_request_duration_seconds_bucket{{route=\"{}\",le=\"{}\"}} {}", route, bound, cumulative);
+        }
+        let _ = writeln!(out, "forgex_rpc_request_duration_seconds_bucket{{route=\"{}\",le=\"+Inf\"}} {}", route, stats.count);
+        let _ = writeln!(out, "forgex_rpc_request_duration_seconds_sum{{route=\"{}\"}} {}", route, stats.sum);
+        let _ = writeln!(out, "forgex_rpc_request_duration_seconds_count{{route=\"{}\"}} {}", route, stats.count);
+    }
+
+    let _ = writeln!(out, "# HELP forgex_rpc_p2p_requests_total Requests sent to the node over P2P");
+    let _ = writeln!(out, "# TYPE forgex_rpc_p2p_requests_total counter");
+    let _ = writeln!(out, "forgex_rpc_p2p_requests_total {}", m.p2p_requests.load(Ordering::Relaxed));
+    let _ = writeln!(out, "# HELP forgex_rpc_p2p_failures_total P2P requests to the node that failed");
+    let _ = writeln!(out, "# TYPE forgex_rpc_p2p_failures_total counter");
+    let _ = writeln!(out, "forgex_rpc_p2p_failures_total {}", m.p2p_failures.load(Ordering::Relaxed));
+
+    out
+}
@@ -1,3 +1,6 @@
 pub mod p2p;
 pub mod validate;
-pub mod model;
\ No newline at end of file
+pub mod model;
+pub mod metrics;
+pub mod logging;
+pub mod network;
@@ -1,59 +1,103 @@
 use sha2::{Sha256, Digest};
-use crate::validate::Tx;
+use crate::validate::{MAX_MEMO_LEN, Tx};
 
 pub struct DecodedResponse {
-    pub msg_type: u8,
     pub balance: Option<u64>,
+    /// Залоченное вестингом (только у BALANCE)
+    pub locked: Option<u64>,
     pub nonce: Option<u64>,
     pub status: Option<String>,
     pub address: Option<String>,
+    pub token: Option<TokenInfo>,
+    pub validators: Option<ValidatorSet>,
+    pub fees: Option<FeeInfo>,
+    pub fee_estimate: Option<FeeEstimate>,
 }
 
+/// Запись реестра токенов на ноде
+pub struct TokenInfo {
+    pub code: u8,
+    pub symbol: String,
+    pub decimals: u8,
+    pub max_supply: u64,
+    /// Общий выпуск сейчас
+    pub supply: u64,
+    pub issuer: String,
+}
 
-fn tx_type_byte(s: &str) -> u8 {
-    match s {
-        "transfer" => 1,
-        _ => 0,
-    }
+/// Активный набор валидаторов на ноде
+pub struct ValidatorSet {
+    pub epoch: u64,
+    /// (адрес, весь стейк) по убыванию стейка
+    pub validators: Vec<(String, u64)>,
+}
+
+/// base fee следующего блока и параметры комиссий ноды
+pub struct FeeInfo {
+    /// Высота блока, для которого действует base_fee
+    pub height: u64,
+    pub base_fee: u64,
+    pub min_base_fee: u64,
+    pub target_block_txs: u32,
+    pub burn_percent: u8,
+    pub proposer_percent: u8,
 }
 
-fn token_byte(s: &str) -> u8 {
+/// Предложения fee, чтобы попасть в блок за target_blocks блоков
+pub struct FeeEstimate {
+    pub target_blocks: u16,
+    pub base_fee: u64,
+    pub low: u64,
+    pub medium: u64,
+    pub high: u64,
+}
+
+/// Raw tx без хвостов по типу
+const RAW_TX_BASE_LEN: usize = 269;
+
+
+fn tx_type_byte(s: &str) -> u8 {
     match s {
-        "GLD" => 1,
+        "transfer" => 1,
+        "create_token" => 2,
+        "mint" => 3,
+        "burn" => 4,
+        "multi_send" => 5,
+        "multisig_transfer" => 6,
+        "vesting_transfer" => 7,
+        "stake" => 8,
+        "unstake" => 9,
+        "delegate" => 10,
         _ => 0,
     }
 }
 
 pub fn send_tx(raw_tx: &[u8]) -> Result<Vec<u8>, String> {
-    if raw_tx.len() != 269 {
+    if raw_tx.len() < RAW_TX_BASE_LEN {
         return Err(format!(
-            "raw_tx must be 269 bytes, got {}",
+            "raw_tx must be at least {} bytes, got {}",
+            RAW_TX_BASE_LEN,
             raw_tx.len()
         ));
     }
 
     let tx_hash = sha256_bytes(raw_tx);
 
-    let mut buf = Vec::with_capacity(308);
+    // 32 (tx_hash) + raw_tx (269 + хвост по типу)
+    let payload_len = 32 + raw_tx.len();
+
+    let mut buf = Vec::with_capacity(7 + payload_len);
 
     buf.extend_from_slice(b"FGX1");
 
     buf.push(1u8);
 
-    let payload_len: u16 = 301;
-    buf.extend_from_slice(&payload_len.to_be_bytes());
+    buf.extend_from_slice(&(payload_len as u16).to_be_bytes());
 
     buf.extend_from_slice(&tx_hash);
 
     buf.extend_from_slice(raw_tx);
 
-    if buf.len() != 308 {
-        return Err(format!(
-            "final message must be 308 bytes, got {}",
-            buf.len()
-        ));
-    }
-
     Ok(buf)
 }
 
@@ -93,20 +137,20 @@ pub fn ask_balance(address: &str, token: &str) -> Result<Vec<u8>, String> {
     }
 
     let token_bytes = utf8_to_bytes(token);
-    if token_bytes.len() != 3 {
+    if token_bytes.is_empty() || token_bytes.len() > 8 {
         return Err(format!(
-            "token must be 3 bytes utf-8, got {}",
+            "token must be 1..=8 bytes utf-8, got {}",
             token_bytes.len()
         ));
     }
 
     let msg_type: u8 = 3;
 
-    // 56 (address) + 3 (token) = 59
-    let payload_len: u16 = 56 + 3;
+    // 56 (address) + символ токена
+    let payload_len = (56 + token_bytes.len()) as u16;
 
-    // 4 ("FGX1") + 1 (msg_type) + 2 (len) + 59 (payload) = 66
-    let mut buf = Vec::with_capacity(66);
+    // 4 ("FGX1") + 1 (msg_type) + 2 (len) + payload
+    let mut buf = Vec::with_capacity(7 + payload_len as usize);
 
     buf.extend_from_slice(b"FGX1");
     buf.push(msg_type);
@@ -114,13 +158,72 @@ pub fn ask_balance(address: &str, token: &str) -> Result<Vec<u8>, String> {
 
     // payload:
     buf.extend_from_slice(&addr_bytes);   // первые 56 байт — адрес
-    buf.extend_from_slice(&token_bytes);  // дальше 3 байта — токен
+    buf.extend_from_slice(&token_bytes);  // дальше символ токена
+
+    Ok(buf)
+}
+
+pub fn ask_token(symbol: &str) -> Result<Vec<u8>, String> {
+    let symbol_bytes = utf8_to_bytes(symbol);
+    if symbol_bytes.is_empty() || symbol_bytes.len() > 8 {
+        return Err(format!(
+            "token must be 1..=8 bytes utf-8, got {}",
+            symbol_bytes.len()
+        ));
+    }
+
+    let msg_type: u8 = 16;
+    let payload_len = symbol_bytes.len() as u16;
+
+    let mut buf = Vec::with_capacity(7 + symbol_bytes.len());
+
+    buf.extend_from_slice(b"FGX1");
+    buf.push(msg_type);
+    buf.extend_from_slice(&payload_len.to_be_bytes());
+    buf.extend_from_slice(&symbol_bytes);
 
     Ok(buf)
 }
 
+pub fn ask_fees() -> Vec<u8> {
+    let msg_type: u8 = 20;
 
-pub fn make_raw_tx(tx: &Tx) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::with_capacity(7);
+
+    buf.extend_from_slice(b"FGX1");
+    buf.push(msg_type);
+    buf.extend_from_slice(&0u16.to_be_bytes());
+
+    buf
+}
+
+pub fn ask_fee_estimate(target_blocks: u16) -> Vec<u8> {
+    let msg_type: u8 = 22;
+
+    let mut buf = Vec::with_capacity(9);
+
+    buf.extend_from_slice(b"FGX1");
+    buf.push(msg_type);
+    buf.extend_from_slice(&2u16.to_be_bytes());
+    buf.extend_from_slice(&target_blocks.to_be_bytes());
+
+    buf
+}
+
+pub fn ask_validators() -> Vec<u8> {
+    let msg_type: u8 = 18;
+
+    let mut buf = Vec::with_capacity(7);
+
+    buf.extend_from_slice(b"FGX1");
+    buf.push(msg_type);
+    buf.extend_from_slice(&0u16.to_be_bytes());
+
+    buf
+}
+
+/// token_code — код токена из реестра ноды (0 у create_token)
+pub fn make_raw_tx(tx: &Tx, token_code: u8) -> Result<Vec<u8>, String> {
     let mut buf = Vec::new();
 
     push_fixed_utf8(&mut buf, &tx.domain_tag, 9, "domain_tag")?;
@@ -133,7 +236,7 @@ pub fn make_raw_tx(tx: &Tx) -> Result<Vec<u8>, String> {
 
     push_fixed_utf8(&mut buf, &tx.to, 56, "to")?;
 
-    buf.push(token_byte(&tx.token));
+    buf.push(token_code);
 
     buf.extend_from_slice(&tx.amount.to_be_bytes());
 
@@ -163,8 +266,78 @@ pub fn make_raw_tx(tx: &Tx) -> Result<Vec<u8>, String> {
 
     push_fixed_utf8(&mut buf, &tx.encoding, 7, "encoding")?;
 
-    if buf.len() != 269 {
-        return Err(format!("raw_tx must be 269 bytes, got {}", buf.len()));
+    if buf.len() != RAW_TX_BASE_LEN {
+        return Err(format!("raw_tx must be {} bytes, got {}", RAW_TX_BASE_LEN, buf.len()));
+    }
+
+    // хвост create_token: symbol_len (u8) | symbol | decimals (u8) | initial_supply (u64)
+    if tx.tx_type == "create_token" {
+        let symbol = utf8_to_bytes(&tx.token);
+        if symbol.len() > 8 {
+            return Err(format!("token symbol must be <= 8 bytes, got {}", symbol.len()));
+        }
+        buf.push(symbol.len() as u8);
+        buf.extend_from_slice(&symbol);
+        buf.push(tx.decimals.ok_or("create_token requires decimals")?);
+        buf.extend_from_slice(&tx.initial_supply.unwrap_or(0).to_be_bytes());
+    }
+
+    // хвост multi_send: count (u16) | count * (to (56) | amount (u64))
+    if tx.tx_type == "multi_send" {
+        let recipients = tx.recipients.as_deref().ok_or("multi_send requires recipients")?;
+        if recipients.len() > u16::MAX as usize {
+            return Err(format!("too many recipients: {}", recipients.len()));
+        }
+        buf.extend_from_slice(&(recipients.len() as u16).to_be_bytes());
+        for r in recipients {
+            push_fixed_utf8(&mut buf, &r.to, 56, "recipient")?;
+            buf.extend_from_slice(&r.amount.to_be_bytes());
+        }
+    }
+
+    // хвост multisig_transfer: threshold (u8) | n (u8) | n * pubkey (33) | m (u8) | m * (index (u8) | sig (65))
+    if tx.tx_type == "multisig_transfer" {
+        let ms = tx.multisig.as_ref().ok_or("multisig_transfer requires multisig params")?;
+        if ms.pubkeys.len() > u8::MAX as usize || ms.signatures.len() > u8::MAX as usize {
+            return Err("too many multisig keys".into());
+        }
+        buf.push(ms.threshold);
+        buf.push(ms.pubkeys.len() as u8);
+        for pk in &ms.pubkeys {
+            let b = hex_to_bytes(pk)?;
+            if b.len() != 33 {
+                return Err(format!("multisig pubkey must be 33 bytes, got {}", b.len()));
+            }
+            buf.extend_from_slice(&b);
+        }
+        buf.push(ms.signatures.len() as u8);
+        for (index, sig) in &ms.signatures {
+            let b = hex_to_bytes(sig)?;
+            if b.len() != 65 {
+                return Err(format!("multisig signature must be 65 bytes, got {}", b.len()));
+            }
+            buf.push(*index);
+            buf.extend_from_slice(&b);
+        }
+    }
+
+    // хвост vesting_transfer: clock (u8, 0 — высота, 1 — время) | start (u64) | cliff (u64) | duration (u64)
+    if tx.tx_type == "vesting_transfer" {
+        let v = tx.vesting.as_ref().ok_or("vesting_transfer requires vesting schedule")?;
+        buf.push(v.clock);
+        buf.extend_from_slice(&v.start.to_be_bytes());
+        buf.extend_from_slice(&v.cliff.to_be_bytes());
+        buf.extend_from_slice(&v.duration.to_be_bytes());
+    }
+
+    // pipe_v2: memo_len (u8) | memo (без memo — нулевая длина)
+    if tx.encoding == "pipe_v2" {
+        let memo = tx.memo.as_deref().unwrap_or("");
+        if memo.len() > MAX_MEMO_LEN {
+            return Err(format!("memo must be at most {} bytes", MAX_MEMO_LEN));
+        }
+        buf.push(memo.len() as u8);
+        buf.extend_from_slice(memo.as_bytes());
     }
 
     Ok(buf)
@@ -193,19 +366,12 @@ fn sha256_bytes(input: &[u8]) -> [u8; 32] {
     out
 }
 
-fn bytes_to_utf8(bytes: &[u8]) -> Result<String, String> {
-    match String::from_utf8(bytes.to_vec()) {
-        Ok(s) => Ok(s),
-        Err(_) => Err("Invalid UTF-8 sequence".into()),
-    }
-}
-
 fn utf8_to_bytes(s: &str) -> Vec<u8> {
     s.as_bytes().to_vec()
 }
 
 fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         return Err("Hex string has odd length".into());
     }
 
@@ -221,16 +387,6 @@ fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
     Ok(bytes)
 }
 
-fn bytes_to_hex(bytes: &[u8]) -> String {
-    let mut hex = String::with_capacity(bytes.len() * 2);
-
-    for b in bytes {
-        hex.push_str(&format!("{:02x}", b));
-    }
-
-    hex
-}
-
 pub fn decode_p2p_response(msg: &[u8]) -> Result<DecodedResponse, String> {
     if msg.len() < 7 {
         return Err("response too short".into());
@@ -258,11 +414,15 @@ pub fn decode_p2p_response(msg: &[u8]) -> Result<DecodedResponse, String> {
                 .map_err(|_| "invalid utf8 in tx response")?;
 
             Ok(DecodedResponse {
-                msg_type,
                 balance: None,
+                locked: None,
                 nonce: None,
                 status: Some(status),
                 address: None,
+                token: None,
+                validators: None,
+                fees: None,
+                fee_estimate: None,
             })
         }
 
@@ -270,20 +430,26 @@ pub fn decode_p2p_response(msg: &[u8]) -> Result<DecodedResponse, String> {
         // TYPE 4 — BALANCE
         // -----------------------------
         4 => {
-            if payload.len() != 64 {
+            // balance (u64) | address (56) | locked (u64)
+            if payload.len() != 72 {
                 return Err("invalid balance response length".into());
             }
 
             let balance = u64::from_be_bytes(payload[0..8].try_into().unwrap());
-            let address = String::from_utf8(payload[8..].to_vec())
+            let address = String::from_utf8(payload[8..64].to_vec())
                 .map_err(|_| "invalid utf8 in address")?;
+            let locked = u64::from_be_bytes(payload[64..72].try_into().unwrap());
 
             Ok(DecodedResponse {
-                msg_type,
                 balance: Some(balance),
+                locked: Some(locked),
                 nonce: None,
                 status: None,
                 address: Some(address),
+                token: None,
+                validators: None,
+                fees: None,
+                fee_estimate: None,
             })
         }
 
@@ -300,11 +466,145 @@ pub fn decode_p2p_response(msg: &[u8]) -> Result<DecodedResponse, String> {
                 .map_err(|_| "invalid utf8 in address")?;
 
             Ok(DecodedResponse {
-                msg_type,
                 balance: None,
+                locked: None,
                 nonce: Some(nonce),
                 status: None,
                 address: Some(address),
+                token: None,
+                validators: None,
+                fees: None,
+                fee_estimate: None,
+            })
+        }
+
+        // -----------------------------
+        // TYPE 17 — TOKEN
+        // -----------------------------
+        17 => {
+            let token = match payload.first() {
+                Some(0) => None,
+                Some(_) if payload.len() > 75 => Some(TokenInfo {
+                    code: payload[1],
+                    decimals: payload[2],
+                    max_supply: u64::from_be_bytes(payload[3..11].try_into().unwrap()),
+                    supply: u64::from_be_bytes(payload[11..19].try_into().unwrap()),
+                    issuer: String::from_utf8(payload[19..75].to_vec())
+                        .map_err(|_| "invalid utf8 in issuer")?,
+                    symbol: String::from_utf8(payload[75..].to_vec())
+                        .map_err(|_| "invalid utf8 in token symbol")?,
+                }),
+                _ => return Err("invalid token response length".into()),
+            };
+
+            Ok(DecodedResponse {
+                balance: None,
+                locked: None,
+                nonce: None,
+                status: None,
+                address: None,
+                token,
+                validators: None,
+                fees: None,
+                fee_estimate: None,
+            })
+        }
+
+        // -----------------------------
+        // TYPE 19 — VALIDATORS
+        // -----------------------------
+        19 => {
+            // epoch (u64) | count (u16) | count * (address (56) | stake (u64))
+            if payload.len() < 10 {
+                return Err("invalid validators response length".into());
+            }
+            let epoch = u64::from_be_bytes(payload[0..8].try_into().unwrap());
+            let count = u16::from_be_bytes([payload[8], payload[9]]) as usize;
+            if payload.len() != 10 + count * 64 {
+                return Err("invalid validators response length".into());
+            }
+
+            let mut validators = Vec::with_capacity(count);
+            for entry in payload[10..].chunks_exact(64) {
+                let address = String::from_utf8(entry[..56].to_vec())
+                    .map_err(|_| "invalid utf8 in address")?;
+                validators.push((address, u64::from_be_bytes(entry[56..].try_into().unwrap())));
+            }
+
+            Ok(DecodedResponse {
+                balance: None,
+                locked: None,
+                nonce: None,
+                status: None,
+                address: None,
+                token: None,
+                validators: Some(ValidatorSet { epoch, validators }),
+                fees: None,
+                fee_estimate: None,
+            })
+        }
+
+        // -----------------------------
+        // TYPE 21 — FEES
+        // -----------------------------
+        21 => {
+            // height (u64) | base_fee (u64) | min_base_fee (u64) | target_block_txs (u32)
+            // | burn_percent (u8) | proposer_percent (u8)
+            if payload.len() != 30 {
+                return Err("invalid fees response length".into());
+            }
+
+            Ok(DecodedResponse {
+                balance: None,
+                locked: None,
+                nonce: None,
+                status: None,
+                address: None,
+                token: None,
+                validators: None,
+                fees: Some(FeeInfo {
+                    height: u64::from_be_bytes(payload[0..8].try_into().unwrap()),
+                    base_fee: u64::from_be_bytes(payload[8..16].try_into().unwrap()),
+                    min_base_fee: u64::from_be_bytes(payload[16..24].try_into().unwrap()),
+                    target_block_txs: u32::from_be_bytes(payload[24..28].try_into().unwrap()),
+                    burn_percent: payload[28],
+                    proposer_percent: payload[29],
+                }),
+                fee_estimate: None,
+            })
+        }
+
+        // -----------------------------
+        // TYPE 23 — FEE_ESTIMATE
+        // -----------------------------
+        23 => {
+            // ok (u8) | target_blocks (u16) | base_fee | low | medium | high (u64)
+            // ok = 0: дальше текст ошибки
+            match payload.first() {
+                Some(1) if payload.len() == 35 => {}
+                Some(0) => {
+                    return Err(String::from_utf8_lossy(&payload[1..]).into_owned());
+                }
+                _ => return Err("invalid fee estimate response length".into()),
+            }
+            let at = |i: usize| u64::from_be_bytes(payload[i..i + 8].try_into().unwrap());
+
+            Ok(DecodedResponse {
+                balance: None,
+                locked: None,
+                nonce: None,
+                status: None,
+                address: None,
+                token: None,
+                validators: None,
+                fees: None,
+                fee_estimate: Some(FeeEstimate {
+                    target_blocks: u16::from_be_bytes([payload[1], payload[2]]),
+                    base_fee: at(3),
+                    low: at(11),
+                    medium: at(19),
+                    high: at(27),
+                }),
             })
         }
 
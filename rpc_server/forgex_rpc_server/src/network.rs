@@ -0,0 +1,41 @@
+//! Профиль сети: FORGEX_NETWORK (devnet, testnet, mainnet; по умолчанию devnet).
+//! Та же таблица, что у ноды (forgex_node::network), только поля RPC.
+
+use once_cell::sync::OnceCell;
+
+#[derive(Debug)]
+pub struct NetworkProfile {
+    pub name: &'static str,
+    /// Транзы другой сети не пропускаем к ноде
+    pub chain_id: &'static str,
+    /// HRP адресов (он же первые 4 байта payload)
+    pub hrp: &'static str,
+    /// HRP адресов мультисигов
+    pub multisig_hrp: &'static str,
+    /// Порты по умолчанию: HTTP RPC и P2P ноды
+    pub rpc_port: u16,
+    pub node_port: u16,
+}
+
+pub const NETWORKS: &[NetworkProfile] = &[
+    NetworkProfile { name: "devnet", chain_id: "gld-dev-1", hrp: "gld2", multisig_hrp: "gms2", rpc_port: 8080, node_port: 5050 },
+    NetworkProfile { name: "testnet", chain_id: "gld-test1", hrp: "gldt", multisig_hrp: "gmst", rpc_port: 6080, node_port: 6050 },
+    NetworkProfile { name: "mainnet", chain_id: "gld-main1", hrp: "gldm", multisig_hrp: "gmsm", rpc_port: 7080, node_port: 7050 },
+];
+
+static ACTIVE: OnceCell<&'static NetworkProfile> = OnceCell::new();
+
+/// Выбрать сеть по FORGEX_NETWORK (один раз, при старте)
+pub fn select_network_from_env() -> Result<&'static NetworkProfile, String> {
+    let name = std::env::var("FORGEX_NETWORK").unwrap_or_else(|_| "devnet".into());
+    let profile = NETWORKS
+        .iter()
+        .find(|n| n.name == name)
+        .ok_or_else(|| format!("unknown network {} (known: devnet, testnet, mainnet)", name))?;
+    Ok(*ACTIVE.get_or_init(|| profile))
+}
+
+/// Активная сеть (до выбора — devnet)
+pub fn network() -> &'static NetworkProfile {
+    ACTIVE.get().copied().unwrap_or(&NETWORKS[0])
+}
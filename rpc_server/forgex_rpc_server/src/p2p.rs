@@ -2,7 +2,15 @@ use anyhow::Result;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
+use crate::metrics;
+
 pub async fn p2p_send(addr: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let res = p2p_request(addr, data).await;
+    metrics::p2p_request(res.is_ok());
+    res
+}
+
+async fn p2p_request(addr: &str, data: &[u8]) -> Result<Vec<u8>> {
     let mut stream = TcpStream::connect(addr).await?;
 
     stream.write_all(data).await?;
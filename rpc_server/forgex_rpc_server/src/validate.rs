@@ -2,6 +2,10 @@ use serde::{Serialize, Deserialize};
 use serde_json::{Value, Map};
 use bech32::FromBase32;
 use sha2::{Sha256, Digest};
+use crate::network::network;
+
+/// Максимальная длина memo в байтах (как на ноде)
+pub const MAX_MEMO_LEN: usize = 64;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tx {
@@ -18,6 +22,40 @@ pub struct Tx {
     pub pubkey: String,
     pub signature: String,
     pub encoding: String,
+    /// create_token: знаков после запятой и начальный выпуск (issuer = to, max_supply = amount)
+    pub decimals: Option<u8>,
+    pub initial_supply: Option<u64>,
+    /// multi_send: выплаты по порядку (to = from, amount = сумма выплат)
+    pub recipients: Option<Vec<Recipient>>,
+    /// multisig_transfer: политика адреса from и подписи (pub_key/sig тогда не нужны)
+    pub multisig: Option<Multisig>,
+    /// vesting_transfer: расписание разлока
+    pub vesting: Option<Vesting>,
+    /// memo, только с encoding pipe_v2 (входит в подпись)
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipient {
+    pub to: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Multisig {
+    pub threshold: u8,
+    pub pubkeys: Vec<String>,
+    /// (индекс ключа в pubkeys, подпись r|s|v в hex)
+    pub signatures: Vec<(u8, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vesting {
+    /// 0 — по высоте блока, 1 — по timestamp блока (мс)
+    pub clock: u8,
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
 }
 
 fn get_u64_from_mixed(obj: &Map<String, Value>, key: &str) -> Option<u64> {
@@ -55,6 +93,12 @@ pub fn parse_tx(json_str: &str) -> Option<Tx> {
         pubkey: String::new(),
         signature: String::new(),
         encoding: String::new(),
+        decimals: None,
+        initial_supply: None,
+        recipients: None,
+        multisig: None,
+        vesting: None,
+        memo: None,
     };
 
 
@@ -68,7 +112,7 @@ pub fn parse_tx(json_str: &str) -> Option<Tx> {
     // chain_id
     {
         let s = obj.get("chain_id")?.as_str()?;
-        if s.is_empty() { return None; }
+        if s != network().chain_id { return None; }
         tx.chain_id = s.to_string();
     }
 
@@ -83,7 +127,9 @@ pub fn parse_tx(json_str: &str) -> Option<Tx> {
     {
         let s = obj.get("from")?.as_str()?;
         if s.is_empty() { return None; }
-        if !validate_address(s) { return None; }
+        // с мультисига тратит только multisig_transfer, и наоборот
+        let hrp = address_hrp(s)?;
+        if (hrp == network().multisig_hrp) != (tx.tx_type == "multisig_transfer") { return None; }
         tx.from = s.to_string();
     }
 
@@ -102,27 +148,40 @@ pub fn parse_tx(json_str: &str) -> Option<Tx> {
         tx.token = s.to_string();
     }
 
-    // pubkey (JSON: "pub_key")
-    {
-        let s = obj.get("pub_key")?.as_str()?;
-        if s.is_empty() { return None; }
-        tx.pubkey = s.to_string();
-    }
+    // multisig_transfer: своих pubkey/sig нет (в raw tx нули), подписи в "signatures"
+    if tx.tx_type == "multisig_transfer" {
+        tx.pubkey = "00".repeat(33);
+        tx.signature = "00".repeat(65);
+    } else {
+        // pubkey (JSON: "pub_key")
+        {
+            let s = obj.get("pub_key")?.as_str()?;
+            if s.is_empty() { return None; }
+            tx.pubkey = s.to_string();
+        }
 
-    // signature (JSON: "sig")
-    {
-        let s = obj.get("sig")?.as_str()?;
-        if s.is_empty() { return None; }
-        tx.signature = s.to_string();
+        // signature (JSON: "sig")
+        {
+            let s = obj.get("sig")?.as_str()?;
+            if s.is_empty() { return None; }
+            tx.signature = s.to_string();
+        }
     }
 
     // encoding
     {
         let s = obj.get("encoding")?.as_str()?;
-        if s.is_empty() { return None; }
+        if s != "pipe_v1" && s != "pipe_v2" { return None; }
         tx.encoding = s.to_string();
     }
 
+    // memo: только pipe_v2, не длиннее MAX_MEMO_LEN байт
+    if let Some(m) = obj.get("memo") {
+        let s = m.as_str()?;
+        if tx.encoding != "pipe_v2" || s.len() > MAX_MEMO_LEN { return None; }
+        tx.memo = Some(s.to_string());
+    }
+
     // amount
     {
         let n = get_u64_from_mixed(obj, "amount")?;
@@ -154,30 +213,91 @@ pub fn parse_tx(json_str: &str) -> Option<Tx> {
         }
     }
 
+    // create_token: decimals обязателен, initial_supply по умолчанию 0
+    if tx.tx_type == "create_token" {
+        let decimals = get_u64_from_mixed(obj, "decimals")?;
+        tx.decimals = Some(u8::try_from(decimals).ok()?);
+        tx.initial_supply = Some(get_u64_from_mixed(obj, "initial_supply").unwrap_or(0));
+    }
+
+    // multi_send: recipients обязателен, [{ "to": ..., "amount": ... }, ...]
+    if tx.tx_type == "multi_send" {
+        let list = obj.get("recipients")?.as_array()?;
+        if list.is_empty() { return None; }
+        let mut recipients = Vec::with_capacity(list.len());
+        for item in list {
+            let r = item.as_object()?;
+            let to = r.get("to")?.as_str()?;
+            if !validate_address(to) { return None; }
+            let amount = get_u64_from_mixed(r, "amount")?;
+            if amount == 0 { return None; }
+            recipients.push(Recipient { to: to.to_string(), amount });
+        }
+        tx.recipients = Some(recipients);
+    }
+
+    // multisig_transfer: "threshold", "pubkeys": [hex], "signatures": [{ "index": i, "sig": hex }]
+    if tx.tx_type == "multisig_transfer" {
+        let threshold = u8::try_from(get_u64_from_mixed(obj, "threshold")?).ok()?;
+        let pubkeys = obj
+            .get("pubkeys")?
+            .as_array()?
+            .iter()
+            .map(|pk| pk.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()?;
+        let mut signatures = Vec::new();
+        for item in obj.get("signatures")?.as_array()? {
+            let s = item.as_object()?;
+            let index = u8::try_from(get_u64_from_mixed(s, "index")?).ok()?;
+            let sig = s.get("sig")?.as_str()?;
+            if sig.is_empty() { return None; }
+            signatures.push((index, sig.to_string()));
+        }
+        if pubkeys.is_empty() || signatures.is_empty() { return None; }
+        tx.multisig = Some(Multisig { threshold, pubkeys, signatures });
+    }
+
+    // vesting_transfer: "vesting": { "unlock_by": "height" | "time", "start", "cliff", "duration" }
+    if tx.tx_type == "vesting_transfer" {
+        let v = obj.get("vesting")?.as_object()?;
+        let clock = match v.get("unlock_by")?.as_str()? {
+            "height" => 0,
+            "time" => 1,
+            _ => return None,
+        };
+        let duration = get_u64_from_mixed(v, "duration")?;
+        if duration == 0 { return None; }
+        tx.vesting = Some(Vesting {
+            clock,
+            start: get_u64_from_mixed(v, "start")?,
+            cliff: get_u64_from_mixed(v, "cliff").unwrap_or(0),
+            duration,
+        });
+    }
+
     Some(tx)
 }
 
 pub fn validate_address(addr: &str) -> bool {
-    let (hrp, data5, _variant) = match bech32::decode(addr) {
-        Ok(v) => v,
-        Err(_) => return false,
-    };
+    address_hrp(addr).is_some()
+}
 
-    if hrp != "gld2" {
-        return false;
-    }
+/// HRP корректного адреса сети: hrp ключа или мультисига
+pub fn address_hrp(addr: &str) -> Option<&'static str> {
+    let (hrp, data5, _variant) = bech32::decode(addr).ok()?;
 
-    let data8: Vec<u8> = match Vec::<u8>::from_base32(&data5) {
-        Ok(v) => v,
-        Err(_) => return false,
-    };
+    // адрес другой сети
+    let net = network();
+    let expected = [net.hrp, net.multisig_hrp].into_iter().find(|h| *h == hrp)?;
+
+    let data8: Vec<u8> = Vec::<u8>::from_base32(&data5).ok()?;
 
     if data8.len() != 28 {
-        return false;
+        return None;
     }
 
-    if &data8[0..4] != b"gld2" {
-        return false;
+    if &data8[0..4] != expected.as_bytes() {
+        return None;
     }
 
     let payload24 = &data8[0..24];
@@ -188,8 +308,8 @@ pub fn validate_address(addr: &str) -> bool {
     let hash = hasher.finalize();
 
     if &hash[0..4] != checksum4 {
-        return false;
+        return None;
     }
 
-    true
+    Some(expected)
 }